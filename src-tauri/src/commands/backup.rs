@@ -0,0 +1,57 @@
+use crate::models::{BackupSettings, ConfigBackup, JsonChange};
+use crate::utils::{backup, config_repo, json_diff, secrets, settings};
+use log::{error, info};
+use serde_json::Value;
+use tauri::command;
+
+/// 列出 openclaw.json 备份（最新的在前）
+#[command]
+pub async fn list_config_backups() -> Result<Vec<ConfigBackup>, String> {
+    Ok(backup::list())
+}
+
+/// 对比备份与当前 openclaw.json：列出从备份到当前文件的变化
+#[command]
+pub async fn diff_config_backup(backup_id: String) -> Result<Vec<JsonChange>, String> {
+    let content = backup::read(&backup_id)?;
+    let old: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("备份 {} 不是有效的 JSON: {}", backup_id, e))?;
+    let current = config_repo::load_raw()?;
+    let mut changes = json_diff::diff(&old, &current, None);
+    changes.iter_mut().for_each(secrets::mask_change);
+    Ok(changes)
+}
+
+/// 用备份恢复 openclaw.json（恢复前会先备份当前文件）
+#[command]
+pub async fn restore_config_backup(backup_id: String) -> Result<String, String> {
+    info!("[配置备份] 恢复备份: {}", backup_id);
+    match config_repo::restore(&backup_id) {
+        Ok(_) => {
+            info!("[配置备份] ✓ 已恢复备份 {}", backup_id);
+            Ok(format!("已恢复备份 {}", backup_id))
+        }
+        Err(e) => {
+            error!("[配置备份] ✗ 恢复失败: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// 获取备份设置
+#[command]
+pub async fn get_backup_settings() -> Result<BackupSettings, String> {
+    Ok(settings::load().backup)
+}
+
+/// 保存备份保留数量，并立即删除超出数量的旧备份
+#[command]
+pub async fn save_backup_settings(max_backups: usize) -> Result<BackupSettings, String> {
+    if max_backups == 0 {
+        return Err("至少需要保留 1 个备份".to_string());
+    }
+    info!("[配置备份] 保存备份设置: max_backups={}", max_backups);
+    let saved = settings::update(|s| s.backup.max_backups = max_backups)?;
+    backup::prune(max_backups);
+    Ok(saved.backup)
+}
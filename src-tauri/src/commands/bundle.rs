@@ -0,0 +1,97 @@
+use crate::commands::config;
+use crate::models::{BundleImportPreview, BundleImportResult, BundleManifest, SecretMode};
+use crate::utils::{bundle, shell};
+use log::{error, info, warn};
+use std::path::PathBuf;
+use tauri::{command, AppHandle};
+
+/// 导出配置包（openclaw.json、env、工作区 Markdown、插件列表），密钥可移除或用密码加密
+#[command]
+pub async fn export_setup_bundle(
+    path: String,
+    secret_mode: SecretMode,
+    passphrase: Option<String>,
+) -> Result<BundleManifest, String> {
+    info!("[配置包] 导出到 {} (密钥: {:?})", path, secret_mode);
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        bundle::export(&PathBuf::from(&path), secret_mode, passphrase.as_deref())
+    })
+    .await
+    .map_err(|e| format!("导出配置包失败: {}", e))?;
+    if let Err(e) = &result {
+        error!("[配置包] ✗ 导出失败: {}", e);
+    }
+    result
+}
+
+/// 预览导入配置包会覆盖的内容（不写入磁盘）
+#[command]
+pub async fn preview_setup_bundle(
+    path: String,
+    passphrase: Option<String>,
+) -> Result<BundleImportPreview, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let bundle = bundle::read(&PathBuf::from(&path), passphrase.as_deref())?;
+        bundle::preview(&bundle)
+    })
+    .await
+    .map_err(|e| format!("读取配置包失败: {}", e))?
+}
+
+/// 导入配置包：备份当前配置后写入，并按需安装本机缺少的插件
+#[command]
+pub async fn import_setup_bundle(
+    app: AppHandle,
+    path: String,
+    passphrase: Option<String>,
+    install_plugins: bool,
+) -> Result<BundleImportResult, String> {
+    info!("[配置包] 从 {} 导入", path);
+    let imported = tauri::async_runtime::spawn_blocking(move || {
+        let bundle = bundle::read(&PathBuf::from(&path), passphrase.as_deref())?;
+        let missing = bundle::missing_plugins(&bundle.manifest);
+        bundle::apply(&bundle).map(|result| (result, missing))
+    })
+    .await
+    .map_err(|e| format!("导入配置包失败: {}", e))?;
+    let (mut result, missing) = match imported {
+        Ok(imported) => imported,
+        Err(e) => {
+            error!("[配置包] ✗ 导入失败: {}", e);
+            return Err(e);
+        }
+    };
+
+    if !install_plugins {
+        return Ok(result);
+    }
+    for plugin in missing {
+        info!("[配置包] 安装插件 {}", plugin.id);
+        let installed = if plugin.id == "qqbot" {
+            // QQ 插件随 Manager 打包，不走 npm
+            config::install_qq_plugin(app.clone()).await
+        } else {
+            match bundle::install_spec(&plugin) {
+                Ok(spec) => tauri::async_runtime::spawn_blocking(move || {
+                    shell::run_openclaw(&["plugins", "install", &spec])
+                })
+                .await
+                .unwrap_or_else(|e| Err(e.to_string())),
+                Err(e) => Err(e),
+            }
+        };
+        match installed {
+            Ok(_) => result.plugins_installed.push(plugin.id),
+            Err(e) => {
+                warn!("[配置包] 安装插件 {} 失败: {}", plugin.id, e);
+                result.plugin_errors.push(format!("{}: {}", plugin.id, e));
+            }
+        }
+    }
+    info!(
+        "[配置包] ✓ 导入完成：写入 {} 个文件，安装 {} 个插件",
+        result.files_written.len(),
+        result.plugins_installed.len()
+    );
+    Ok(result)
+}
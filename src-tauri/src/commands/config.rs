@@ -1,8 +1,11 @@
 use crate::models::{
-    AIConfigOverview, ChannelConfig, ConfiguredModel, ConfiguredProvider, ModelConfig,
-    OfficialProvider, SuggestedModel,
+    AIConfigOverview, ChannelConfig, ConfigMutation, ConfigPreview, ConfiguredModel,
+    ConfiguredProvider, EventTrigger, GatewayAuthMode, GatewayTokenRotation, ModelConfig,
+    OfficialProvider, OpenClawConfig, ProviderConfig, SchemaReport, SuggestedModel,
+};
+use crate::utils::{
+    bundled, config_repo, dotenv, gateway, platform, schema, secrets, shell, supervisor, systemd,
 };
-use crate::utils::{bundled, file, platform, shell};
 use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -10,29 +13,6 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use tauri::command;
 
-/// 获取 openclaw.json 配置
-fn load_openclaw_config() -> Result<Value, String> {
-    let config_path = platform::get_config_file_path();
-
-    if !file::file_exists(&config_path) {
-        return Ok(json!({}));
-    }
-
-    let content = file::read_file(&config_path).map_err(|e| format!("读取配置文件失败: {}", e))?;
-
-    serde_json::from_str(&content).map_err(|e| format!("解析配置文件失败: {}", e))
-}
-
-/// 保存 openclaw.json 配置
-fn save_openclaw_config(config: &Value) -> Result<(), String> {
-    let config_path = platform::get_config_file_path();
-
-    let content =
-        serde_json::to_string_pretty(config).map_err(|e| format!("序列化配置失败: {}", e))?;
-
-    file::write_file(&config_path, &content).map_err(|e| format!("写入配置文件失败: {}", e))
-}
-
 /// 获取本应用（OpenClaw Manager）版本号
 #[command]
 pub fn get_app_version() -> String {
@@ -114,7 +94,7 @@ pub fn check_manager_update_from_latest(latest_tag: String) -> ManagerUpdateInfo
 #[command]
 pub async fn get_config() -> Result<Value, String> {
     info!("[获取配置] 读取 openclaw.json 配置...");
-    let result = load_openclaw_config();
+    let result = config_repo::load_raw();
     match &result {
         Ok(_) => info!("[获取配置] ✓ 配置读取成功"),
         Err(e) => error!("[获取配置] ✗ 配置读取失败: {}", e),
@@ -130,10 +110,10 @@ pub async fn save_config(config: Value) -> Result<String, String> {
         "[保存配置] 配置内容: {}",
         serde_json::to_string_pretty(&config).unwrap_or_default()
     );
-    match save_openclaw_config(&config) {
-        Ok(_) => {
+    match commit_mutation(&ConfigMutation::SaveConfig { config }, None) {
+        Ok(message) => {
             info!("[保存配置] ✓ 配置保存成功");
-            Ok("配置已保存".to_string())
+            Ok(message)
         }
         Err(e) => {
             error!("[保存配置] ✗ 配置保存失败: {}", e);
@@ -142,12 +122,28 @@ pub async fn save_config(config: Value) -> Result<String, String> {
     }
 }
 
+/// 校验 openclaw.json 结构，返回每个问题的位置（JSON Pointer + 行列）和修复建议
+#[command]
+pub async fn validate_openclaw_config() -> Result<SchemaReport, String> {
+    let report = schema::validate()?;
+    info!(
+        "[配置校验] 发现 {} 个问题（{}）",
+        report.issues.len(),
+        if report.valid {
+            "无错误"
+        } else {
+            "有错误"
+        }
+    );
+    Ok(report)
+}
+
 /// 获取环境变量值
 #[command]
 pub async fn get_env_value(key: String) -> Result<Option<String>, String> {
     info!("[获取环境变量] 读取环境变量: {}", key);
     let env_path = platform::get_env_file_path();
-    let value = file::read_env_value(&env_path, &key);
+    let value = dotenv::read_value(&env_path, &key);
     match &value {
         Some(v) => debug!(
             "[获取环境变量] {}={} (已脱敏)",
@@ -166,7 +162,7 @@ pub async fn save_env_value(key: String, value: String) -> Result<String, String
     let env_path = platform::get_env_file_path();
     debug!("[保存环境变量] 环境文件路径: {}", env_path);
 
-    match file::set_env_value(&env_path, &key, &value) {
+    match dotenv::set_value(&env_path, &key, &value) {
         Ok(_) => {
             info!("[保存环境变量] ✓ 环境变量 {} 保存成功", key);
             Ok("环境变量已保存".to_string())
@@ -180,65 +176,80 @@ pub async fn save_env_value(key: String, value: String) -> Result<String, String
 
 // ============ Gateway Token 命令 ============
 
-/// 生成随机 token
-fn generate_token() -> String {
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_nanos();
-
-    // 使用时间戳和随机数生成 token
-    let random_part: u64 = (timestamp as u64) ^ 0x5DEECE66Du64;
-    format!(
-        "{:016x}{:016x}{:016x}",
-        random_part,
-        random_part.wrapping_mul(0x5DEECE66Du64),
-        timestamp as u64
-    )
-}
-
 /// 获取或生成 Gateway Token
 #[command]
 pub async fn get_or_create_gateway_token() -> Result<String, String> {
     info!("[Gateway Token] 获取或创建 Gateway Token...");
 
-    let mut config = load_openclaw_config()?;
-
-    // 检查是否已有 token
-    if let Some(token) = config
-        .pointer("/gateway/auth/token")
-        .and_then(|v| v.as_str())
-    {
-        if !token.is_empty() {
+    config_repo::update(|config| {
+        // 检查是否已有 token
+        if let Some(token) = config.gateway_token() {
             info!("[Gateway Token] ✓ 使用现有 Token");
             return Ok(token.to_string());
         }
-    }
 
-    // 生成新 token
-    let new_token = generate_token();
-    info!("[Gateway Token] 生成新 Token: {}...", &new_token[..8]);
+        // 生成新 token
+        let new_token = secrets::generate_token()?;
+        config.set_gateway_token(new_token.clone());
 
-    // 确保路径存在
-    if config.get("gateway").is_none() {
-        config["gateway"] = json!({});
-    }
-    if config["gateway"].get("auth").is_none() {
-        config["gateway"]["auth"] = json!({});
+        info!("[Gateway Token] ✓ Token 已保存到配置");
+        Ok(new_token)
+    })
+}
+
+/// 更换 Gateway Token：写入 openclaw.json，同步 env 文件中的 OPENCLAW_GATEWAY_TOKEN
+/// 和 systemd 单元文件；Gateway 正在运行时先用旧 token 停止，写入新 token 后再启动
+#[command]
+pub async fn rotate_gateway_token(app: tauri::AppHandle) -> Result<GatewayTokenRotation, String> {
+    info!("[Gateway Token] 更换 Gateway Token...");
+
+    // 停止命令需要用旧 token 连接 Gateway，必须在写入新 token 之前执行；停止失败时不更换
+    let token = secrets::generate_token()?;
+    let was_running = gateway::collect_status_async().await?.running;
+    if was_running {
+        supervisor::stop_with_reason(&app, EventTrigger::User, "更换 Gateway Token")
+            .await
+            .map_err(|e| format!("停止 Gateway 失败，未更换 Token: {}", e))?;
     }
 
-    // 设置 token 和 mode
-    config["gateway"]["auth"]["token"] = json!(new_token);
-    config["gateway"]["auth"]["mode"] = json!("token");
-    config["gateway"]["mode"] = json!("local");
+    config_repo::update(|config| {
+        config.set_gateway_token(token.clone());
+        Ok(())
+    })?;
+    info!("[Gateway Token] ✓ 新 Token 已保存到配置");
 
-    // 保存配置
-    save_openclaw_config(&config)?;
+    let mut updated = vec!["openclaw.json".to_string()];
+    let env_path = platform::get_env_file_path();
+    if dotenv::read_value(&env_path, "OPENCLAW_GATEWAY_TOKEN").is_some() {
+        dotenv::set_value(&env_path, "OPENCLAW_GATEWAY_TOKEN", &token)
+            .map_err(|e| format!("更新 env 文件失败: {}", e))?;
+        updated.push("env".to_string());
+
+        // systemd 单元文件中的环境变量取自 env 文件，需要重新生成
+        let unit = systemd::status();
+        if unit.supported && unit.installed {
+            systemd::install()?;
+            updated.push("systemd".to_string());
+        }
+    }
 
-    info!("[Gateway Token] ✓ Token 已保存到配置");
-    Ok(new_token)
+    let mut rotation = GatewayTokenRotation {
+        token,
+        updated,
+        restarted: false,
+        restart_error: None,
+    };
+    if was_running {
+        match supervisor::start_with_reason(EventTrigger::User, "更换 Gateway Token", true).await
+        {
+            Ok(_) => rotation.restarted = true,
+            Err(e) => {
+                error!("[Gateway Token] ✗ 启动 Gateway 失败: {}", e);
+                rotation.restart_error = Some(e.to_string());
+            }
+        }
+    }
+    Ok(rotation)
 }
 
 /// 获取 Dashboard URL（带 token）
@@ -246,10 +257,23 @@ pub async fn get_or_create_gateway_token() -> Result<String, String> {
 pub async fn get_dashboard_url() -> Result<String, String> {
     info!("[Dashboard URL] 获取 Dashboard URL...");
 
-    let token = get_or_create_gateway_token().await?;
-    let url = format!("http://localhost:18789?token={}", token);
+    // 启用 gateway.tls 时使用 HTTPS；密码认证或不认证时 URL 中不带 token
+    let config = config_repo::load()?;
+    let scheme = if config.gateway_tls_enabled() {
+        "https"
+    } else {
+        "http"
+    };
+    let base = format!("{}://localhost:{}", scheme, gateway::get_gateway_port());
+    let url = match config.gateway_auth_mode() {
+        GatewayAuthMode::Token => {
+            format!("{}?token={}", base, get_or_create_gateway_token().await?)
+        }
+        _ => base.clone(),
+    };
 
-    info!("[Dashboard URL] ✓ URL: {}...", &url[..50.min(url.len())]);
+    // URL 中带 token，日志只记录不含 token 的部分
+    info!("[Dashboard URL] ✓ URL: {}", base);
     Ok(url)
 }
 
@@ -660,109 +684,50 @@ pub async fn get_ai_config() -> Result<AIConfigOverview, String> {
     let config_path = platform::get_config_file_path();
     info!("[AI 配置] 配置文件路径: {}", config_path);
 
-    let config = load_openclaw_config()?;
-    debug!(
-        "[AI 配置] 配置内容: {}",
-        serde_json::to_string_pretty(&config).unwrap_or_default()
-    );
+    let config = config_repo::load()?;
 
     // 解析主模型
-    let primary_model = config
-        .pointer("/agents/defaults/model/primary")
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_string());
+    let primary_model = config.primary_model().map(|s| s.to_string());
     info!("[AI 配置] 主模型: {:?}", primary_model);
 
     // 解析可用模型列表
-    let available_models: Vec<String> = config
-        .pointer("/agents/defaults/models")
-        .and_then(|v| v.as_object())
-        .map(|obj| obj.keys().cloned().collect())
-        .unwrap_or_default();
+    let available_models = config.available_models();
     info!("[AI 配置] 可用模型数: {}", available_models.len());
 
     // 解析已配置的 Provider
-    let mut configured_providers: Vec<ConfiguredProvider> = Vec::new();
-
-    let providers_value = config.pointer("/models/providers");
-    info!(
-        "[AI 配置] providers 节点存在: {}",
-        providers_value.is_some()
-    );
-
-    if let Some(providers) = providers_value.and_then(|v| v.as_object()) {
-        info!("[AI 配置] 找到 {} 个 Provider", providers.len());
-
-        for (provider_name, provider_config) in providers {
-            info!("[AI 配置] 解析 Provider: {}", provider_name);
-
-            let base_url = provider_config
-                .get("baseUrl")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-
-            let api_key = provider_config
-                .get("apiKey")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
-
-            let api_key_masked = api_key.as_ref().map(|key| {
-                if key.len() > 8 {
-                    format!("{}...{}", &key[..4], &key[key.len() - 4..])
-                } else {
-                    "****".to_string()
-                }
-            });
-
-            // 解析模型列表
-            let models_array = provider_config.get("models").and_then(|v| v.as_array());
-            info!(
-                "[AI 配置] Provider {} 的 models 数组: {:?}",
-                provider_name,
-                models_array.map(|a| a.len())
-            );
+    info!("[AI 配置] 找到 {} 个 Provider", config.providers().len());
+    let configured_providers: Vec<ConfiguredProvider> = config
+        .providers()
+        .iter()
+        .map(|(provider_name, provider)| {
+            let api_key_masked = provider.api_key.as_deref().map(secrets::mask);
 
-            let models: Vec<ConfiguredModel> = models_array
-                .map(|arr| {
-                    arr.iter()
-                        .filter_map(|m| {
-                            let id = m.get("id")?.as_str()?.to_string();
-                            let name = m
-                                .get("name")
-                                .and_then(|v| v.as_str())
-                                .unwrap_or(&id)
-                                .to_string();
-                            let full_id = format!("{}/{}", provider_name, id);
-                            let is_primary = primary_model.as_ref() == Some(&full_id);
-
-                            info!(
-                                "[AI 配置] 解析模型: {} (is_primary: {})",
-                                full_id, is_primary
-                            );
-
-                            Some(ConfiguredModel {
-                                full_id,
-                                id,
-                                name,
-                                api_type: m
-                                    .get("api")
-                                    .and_then(|v| v.as_str())
-                                    .map(|s| s.to_string()),
-                                context_window: m
-                                    .get("contextWindow")
-                                    .and_then(|v| v.as_u64())
-                                    .map(|n| n as u32),
-                                max_tokens: m
-                                    .get("maxTokens")
-                                    .and_then(|v| v.as_u64())
-                                    .map(|n| n as u32),
-                                is_primary,
-                            })
-                        })
-                        .collect()
+            let models: Vec<ConfiguredModel> = provider
+                .models()
+                .iter()
+                .map(|m| {
+                    let full_id = format!("{}/{}", provider_name, m.id);
+                    let is_primary = primary_model.as_ref() == Some(&full_id);
+                    debug!(
+                        "[AI 配置] 解析模型: {} (is_primary: {})",
+                        full_id, is_primary
+                    );
+
+                    ConfiguredModel {
+                        full_id,
+                        id: m.id.clone(),
+                        name: m
+                            .name
+                            .clone()
+                            .filter(|n| !n.is_empty())
+                            .unwrap_or_else(|| m.id.clone()),
+                        api_type: m.api.clone(),
+                        context_window: m.context_window,
+                        max_tokens: m.max_tokens,
+                        is_primary,
+                    }
                 })
-                .unwrap_or_default();
+                .collect();
 
             info!(
                 "[AI 配置] Provider {} 解析完成: {} 个模型",
@@ -770,17 +735,15 @@ pub async fn get_ai_config() -> Result<AIConfigOverview, String> {
                 models.len()
             );
 
-            configured_providers.push(ConfiguredProvider {
+            ConfiguredProvider {
                 name: provider_name.clone(),
-                base_url,
+                base_url: provider.base_url.clone().unwrap_or_default(),
                 api_key_masked,
-                has_api_key: api_key.is_some(),
+                has_api_key: provider.api_key.is_some(),
                 models,
-            });
-        }
-    } else {
-        info!("[AI 配置] 未找到 providers 配置或格式不正确");
-    }
+            }
+        })
+        .collect();
 
     info!(
         "[AI 配置] ✓ 最终结果 - 主模型: {:?}, {} 个 Provider, {} 个可用模型",
@@ -810,119 +773,21 @@ pub async fn save_provider(
         provider_name,
         models.len()
     );
-
-    let mut config = load_openclaw_config()?;
-
-    // 确保路径存在
-    if config.get("models").is_none() {
-        config["models"] = json!({});
-    }
-    if config["models"].get("providers").is_none() {
-        config["models"]["providers"] = json!({});
-    }
-    if config.get("agents").is_none() {
-        config["agents"] = json!({});
-    }
-    if config["agents"].get("defaults").is_none() {
-        config["agents"]["defaults"] = json!({});
-    }
-    if config["agents"]["defaults"].get("models").is_none() {
-        config["agents"]["defaults"]["models"] = json!({});
-    }
-
-    // 构建模型配置
-    let models_json: Vec<Value> = models
-        .iter()
-        .map(|m| {
-            let mut model_obj = json!({
-                "id": m.id,
-                "name": m.name,
-                "api": m.api.clone().unwrap_or(api_type.clone()),
-                "input": if m.input.is_empty() { vec!["text".to_string()] } else { m.input.clone() },
-            });
-
-            if let Some(cw) = m.context_window {
-                model_obj["contextWindow"] = json!(cw);
-            }
-            if let Some(mt) = m.max_tokens {
-                model_obj["maxTokens"] = json!(mt);
-            }
-            if let Some(r) = m.reasoning {
-                model_obj["reasoning"] = json!(r);
-            }
-            if let Some(cost) = &m.cost {
-                model_obj["cost"] = json!({
-                    "input": cost.input,
-                    "output": cost.output,
-                    "cacheRead": cost.cache_read,
-                    "cacheWrite": cost.cache_write,
-                });
-            } else {
-                model_obj["cost"] = json!({
-                    "input": 0,
-                    "output": 0,
-                    "cacheRead": 0,
-                    "cacheWrite": 0,
-                });
-            }
-
-            model_obj
-        })
-        .collect();
-
-    // 构建 Provider 配置
-    let mut provider_config = json!({
-        "baseUrl": base_url,
-        "models": models_json,
-    });
-
-    // 处理 API Key：如果传入了新的非空 key，使用新的；否则保留原有的
-    if let Some(key) = api_key {
-        if !key.is_empty() {
-            // 使用新传入的 API Key
-            provider_config["apiKey"] = json!(key);
-            info!("[保存 Provider] 使用新的 API Key");
-        } else {
-            // 空字符串表示不更改，尝试保留原有的 API Key
-            if let Some(existing_key) = config
-                .pointer(&format!("/models/providers/{}/apiKey", provider_name))
-                .and_then(|v| v.as_str())
-            {
-                provider_config["apiKey"] = json!(existing_key);
-                info!("[保存 Provider] 保留原有的 API Key");
-            }
-        }
-    } else {
-        // None 表示不更改，尝试保留原有的 API Key
-        if let Some(existing_key) = config
-            .pointer(&format!("/models/providers/{}/apiKey", provider_name))
-            .and_then(|v| v.as_str())
-        {
-            provider_config["apiKey"] = json!(existing_key);
-            info!("[保存 Provider] 保留原有的 API Key");
-        }
-    }
-
-    // 保存 Provider 配置
-    config["models"]["providers"][&provider_name] = provider_config;
-
-    // 将模型添加到 agents.defaults.models
-    for model in &models {
-        let full_id = format!("{}/{}", provider_name, model.id);
-        config["agents"]["defaults"]["models"][&full_id] = json!({});
-    }
-
-    // 更新元数据
-    let now = chrono::Utc::now().to_rfc3339();
-    if config.get("meta").is_none() {
-        config["meta"] = json!({});
+    if api_key.as_deref().is_some_and(|key| !key.is_empty()) {
+        info!("[保存 Provider] 使用新的 API Key");
     }
-    config["meta"]["lastTouchedAt"] = json!(now);
 
-    save_openclaw_config(&config)?;
+    let change = ConfigMutation::SaveProvider {
+        provider_name: provider_name.clone(),
+        base_url,
+        api_key,
+        api_type,
+        models,
+    };
+    let message = commit_mutation(&change, None)?;
     info!("[保存 Provider] ✓ Provider {} 保存成功", provider_name);
 
-    Ok(format!("Provider {} 已保存", provider_name))
+    Ok(message)
 }
 
 /// 删除 Provider
@@ -930,46 +795,13 @@ pub async fn save_provider(
 pub async fn delete_provider(provider_name: String) -> Result<String, String> {
     info!("[删除 Provider] 删除 Provider: {}", provider_name);
 
-    let mut config = load_openclaw_config()?;
-
-    // 删除 Provider 配置
-    if let Some(providers) = config
-        .pointer_mut("/models/providers")
-        .and_then(|v| v.as_object_mut())
-    {
-        providers.remove(&provider_name);
-    }
-
-    // 删除相关模型
-    if let Some(models) = config
-        .pointer_mut("/agents/defaults/models")
-        .and_then(|v| v.as_object_mut())
-    {
-        let keys_to_remove: Vec<String> = models
-            .keys()
-            .filter(|k| k.starts_with(&format!("{}/", provider_name)))
-            .cloned()
-            .collect();
-
-        for key in keys_to_remove {
-            models.remove(&key);
-        }
-    }
-
-    // 如果主模型属于该 Provider，清除主模型
-    if let Some(primary) = config
-        .pointer("/agents/defaults/model/primary")
-        .and_then(|v| v.as_str())
-    {
-        if primary.starts_with(&format!("{}/", provider_name)) {
-            config["agents"]["defaults"]["model"]["primary"] = json!(null);
-        }
-    }
-
-    save_openclaw_config(&config)?;
+    let change = ConfigMutation::DeleteProvider {
+        provider_name: provider_name.clone(),
+    };
+    let message = commit_mutation(&change, None)?;
     info!("[删除 Provider] ✓ Provider {} 已删除", provider_name);
 
-    Ok(format!("Provider {} 已删除", provider_name))
+    Ok(message)
 }
 
 /// 设置主模型
@@ -977,26 +809,13 @@ pub async fn delete_provider(provider_name: String) -> Result<String, String> {
 pub async fn set_primary_model(model_id: String) -> Result<String, String> {
     info!("[设置主模型] 设置主模型: {}", model_id);
 
-    let mut config = load_openclaw_config()?;
-
-    // 确保路径存在
-    if config.get("agents").is_none() {
-        config["agents"] = json!({});
-    }
-    if config["agents"].get("defaults").is_none() {
-        config["agents"]["defaults"] = json!({});
-    }
-    if config["agents"]["defaults"].get("model").is_none() {
-        config["agents"]["defaults"]["model"] = json!({});
-    }
-
-    // 设置主模型
-    config["agents"]["defaults"]["model"]["primary"] = json!(model_id);
-
-    save_openclaw_config(&config)?;
+    let change = ConfigMutation::SetPrimaryModel {
+        model_id: model_id.clone(),
+    };
+    let message = commit_mutation(&change, None)?;
     info!("[设置主模型] ✓ 主模型已设置为: {}", model_id);
 
-    Ok(format!("主模型已设置为 {}", model_id))
+    Ok(message)
 }
 
 /// 添加模型到可用列表
@@ -1004,23 +823,10 @@ pub async fn set_primary_model(model_id: String) -> Result<String, String> {
 pub async fn add_available_model(model_id: String) -> Result<String, String> {
     info!("[添加模型] 添加模型到可用列表: {}", model_id);
 
-    let mut config = load_openclaw_config()?;
-
-    // 确保路径存在
-    if config.get("agents").is_none() {
-        config["agents"] = json!({});
-    }
-    if config["agents"].get("defaults").is_none() {
-        config["agents"]["defaults"] = json!({});
-    }
-    if config["agents"]["defaults"].get("models").is_none() {
-        config["agents"]["defaults"]["models"] = json!({});
-    }
-
-    // 添加模型
-    config["agents"]["defaults"]["models"][&model_id] = json!({});
-
-    save_openclaw_config(&config)?;
+    config_repo::update(|config| {
+        config.add_available_model(&model_id);
+        Ok(())
+    })?;
     info!("[添加模型] ✓ 模型 {} 已添加", model_id);
 
     Ok(format!("模型 {} 已添加", model_id))
@@ -1031,16 +837,10 @@ pub async fn add_available_model(model_id: String) -> Result<String, String> {
 pub async fn remove_available_model(model_id: String) -> Result<String, String> {
     info!("[移除模型] 从可用列表移除模型: {}", model_id);
 
-    let mut config = load_openclaw_config()?;
-
-    if let Some(models) = config
-        .pointer_mut("/agents/defaults/models")
-        .and_then(|v| v.as_object_mut())
-    {
-        models.remove(&model_id);
-    }
-
-    save_openclaw_config(&config)?;
+    config_repo::update(|config| {
+        config.remove_available_model(&model_id);
+        Ok(())
+    })?;
     info!("[移除模型] ✓ 模型 {} 已移除", model_id);
 
     Ok(format!("模型 {} 已移除", model_id))
@@ -1085,8 +885,8 @@ pub async fn get_ai_providers() -> Result<Vec<crate::models::AIProviderOption>,
 pub async fn get_channels_config() -> Result<Vec<ChannelConfig>, String> {
     info!("[渠道配置] 获取渠道配置列表...");
 
-    let config = load_openclaw_config()?;
-    let channels_obj = config.get("channels").cloned().unwrap_or(json!({}));
+    let config = config_repo::load()?;
+    let channels_obj = config.channels();
     let env_path = platform::get_env_file_path();
     debug!("[渠道配置] 环境文件路径: {}", env_path);
 
@@ -1141,7 +941,7 @@ pub async fn get_channels_config() -> Result<Vec<ChannelConfig>, String> {
                 channel_id.to_uppercase(),
                 field.to_uppercase()
             );
-            if let Some(value) = file::read_env_value(&env_path, &env_key) {
+            if let Some(value) = dotenv::read_value(&env_path, &env_key) {
                 config_map.insert(field.to_string(), json!(value));
             }
         }
@@ -1172,96 +972,13 @@ pub async fn save_channel_config(channel: ChannelConfig) -> Result<String, Strin
         channel.id, channel.channel_type
     );
 
-    let mut config = load_openclaw_config()?;
-    let env_path = platform::get_env_file_path();
-    debug!("[保存渠道配置] 环境文件路径: {}", env_path);
-
-    // 确保 channels 对象存在
-    if config.get("channels").is_none() {
-        config["channels"] = json!({});
-    }
-
-    // 确保 plugins 对象存在
-    if config.get("plugins").is_none() {
-        config["plugins"] = json!({
-            "allow": [],
-            "entries": {}
-        });
-    }
-    if config["plugins"].get("allow").is_none() {
-        config["plugins"]["allow"] = json!([]);
-    }
-    if config["plugins"].get("entries").is_none() {
-        config["plugins"]["entries"] = json!({});
-    }
-
-    // 这些字段只用于测试，不保存到 openclaw.json，而是保存到 env 文件
-    let test_only_fields = vec!["userId", "testChatId", "testChannelId"];
-
-    // 构建渠道配置
-    let mut channel_obj = json!({
-        "enabled": true
-    });
-
-    // 添加渠道特定配置
-    for (key, value) in &channel.config {
-        if test_only_fields.contains(&key.as_str()) {
-            // 保存到 env 文件
-            let env_key = format!(
-                "OPENCLAW_{}_{}",
-                channel.id.to_uppercase(),
-                key.to_uppercase()
-            );
-            if let Some(val_str) = value.as_str() {
-                let _ = file::set_env_value(&env_path, &env_key, val_str);
-            }
-        } else {
-            // 保存到 openclaw.json
-            channel_obj[key] = value.clone();
-        }
-    }
-
-    // 更新 channels 配置
-    config["channels"][&channel.id] = channel_obj;
-
-    // 更新 plugins.allow 数组 - 确保渠道在白名单中
-    if let Some(allow_arr) = config["plugins"]["allow"].as_array_mut() {
-        let channel_id_val = json!(&channel.id);
-        if !allow_arr.contains(&channel_id_val) {
-            allow_arr.push(channel_id_val);
-        }
-    }
-
-    // 更新 plugins.entries - 确保插件已启用
-    config["plugins"]["entries"][&channel.id] = json!({
-        "enabled": true
-    });
-
-    // 渠道迁移：wecom 保存后清理旧 wechat 键
-    if channel.id == "wecom" {
-        if let Some(channels) = config.get_mut("channels").and_then(|v| v.as_object_mut()) {
-            channels.remove("wechat");
-        }
-        if let Some(allow_arr) = config
-            .pointer_mut("/plugins/allow")
-            .and_then(|v| v.as_array_mut())
-        {
-            allow_arr.retain(|v| v.as_str() != Some("wechat"));
-        }
-        if let Some(entries) = config
-            .pointer_mut("/plugins/entries")
-            .and_then(|v| v.as_object_mut())
-        {
-            entries.remove("wechat");
-        }
-    }
-
-    // 保存配置
+    // 保存配置：更新 channels，并确保插件在 plugins.allow 中且已启用
     info!("[保存渠道配置] 写入配置文件...");
-    match save_openclaw_config(&config) {
-        Ok(_) => {
-            info!("[保存渠道配置] ✓ {} 配置保存成功", channel.channel_type);
-            Ok(format!("{} 配置已保存", channel.channel_type))
+    let channel_type = channel.channel_type.clone();
+    match commit_mutation(&ConfigMutation::SaveChannelConfig { channel }, None) {
+        Ok(message) => {
+            info!("[保存渠道配置] ✓ {} 配置保存成功", channel_type);
+            Ok(message)
         }
         Err(e) => {
             error!("[保存渠道配置] ✗ 保存失败: {}", e);
@@ -1275,72 +992,194 @@ pub async fn save_channel_config(channel: ChannelConfig) -> Result<String, Strin
 pub async fn clear_channel_config(channel_id: String) -> Result<String, String> {
     info!("[清空渠道配置] 清空渠道配置: {}", channel_id);
 
-    let mut config = load_openclaw_config()?;
-    let env_path = platform::get_env_file_path();
-    let mut channel_ids_to_remove = vec![channel_id.clone()];
+    let change = ConfigMutation::ClearChannelConfig {
+        channel_id: channel_id.clone(),
+    };
+    match commit_mutation(&change, None) {
+        Ok(message) => {
+            info!("[清空渠道配置] ✓ {} 配置已清空", channel_id);
+            Ok(message)
+        }
+        Err(e) => {
+            error!("[清空渠道配置] ✗ 清空失败: {}", e);
+            Err(e)
+        }
+    }
+}
+
+// ============ 配置修改预览 / 应用 ============
+
+/// 只用于测试的渠道字段，不保存到 openclaw.json，而是保存到 env 文件
+const TEST_ONLY_FIELDS: &[&str] = &["userId", "testChatId", "testChannelId"];
+
+/// 渠道测试字段对应的 env 变量名
+fn channel_env_key(channel_id: &str, field: &str) -> String {
+    format!(
+        "OPENCLAW_{}_{}",
+        channel_id.to_uppercase(),
+        field.to_uppercase()
+    )
+}
 
-    // 兼容迁移：清空 wecom 时一并清除旧 wechat 键
+/// 清空渠道时要删除的渠道 ID（兼容迁移：清空 wecom 时一并清除旧 wechat 键）
+fn channel_ids_to_clear(channel_id: &str) -> Vec<String> {
+    let mut ids = vec![channel_id.to_string()];
     if channel_id == "wecom" {
-        channel_ids_to_remove.push("wechat".to_string());
+        ids.push("wechat".to_string());
     }
+    ids
+}
 
-    // 从 channels 对象中删除该渠道
-    if let Some(channels) = config.get_mut("channels").and_then(|v| v.as_object_mut()) {
-        for id in &channel_ids_to_remove {
-            channels.remove(id);
-            info!("[清空渠道配置] 已从 channels 中删除: {}", id);
+/// 把一次修改应用到内存中的配置（不写入磁盘）
+fn apply_mutation(config: &mut OpenClawConfig, change: &ConfigMutation) -> Result<(), String> {
+    match change {
+        ConfigMutation::SaveConfig { config: value } => {
+            *config = config_repo::from_value(value.clone())?;
         }
-    }
+        ConfigMutation::SaveProvider {
+            provider_name,
+            base_url,
+            api_key,
+            api_type,
+            models,
+        } => {
+            // 构建模型配置
+            let models: Vec<ModelConfig> = models
+                .iter()
+                .cloned()
+                .map(|m| ModelConfig {
+                    api: m.api.or_else(|| Some(api_type.clone())),
+                    input: m
+                        .input
+                        .filter(|i| !i.is_empty())
+                        .or_else(|| Some(vec!["text".to_string()])),
+                    cost: Some(m.cost.unwrap_or_default().filled()),
+                    ..m
+                })
+                .collect();
 
-    // 从 plugins.allow 数组中删除
-    if let Some(allow_arr) = config
-        .pointer_mut("/plugins/allow")
-        .and_then(|v| v.as_array_mut())
-    {
-        allow_arr.retain(|v| {
-            if let Some(s) = v.as_str() {
-                !channel_ids_to_remove.iter().any(|id| id == s)
-            } else {
-                true
+            // 空字符串 / None 表示不更改 API Key，由 upsert_provider 保留原有的
+            config.upsert_provider(
+                provider_name,
+                ProviderConfig {
+                    base_url: Some(base_url.clone()),
+                    api_key: api_key.clone().filter(|key| !key.is_empty()),
+                    models: Some(models),
+                    ..Default::default()
+                },
+            );
+            config.touch();
+        }
+        ConfigMutation::DeleteProvider { provider_name } => {
+            config.remove_provider(provider_name);
+        }
+        ConfigMutation::SetPrimaryModel { model_id } => {
+            config.set_primary_model(Some(model_id.clone()));
+        }
+        ConfigMutation::SaveChannelConfig { channel } => {
+            let mut channel_obj = json!({
+                "enabled": true
+            });
+            for (key, value) in &channel.config {
+                if !TEST_ONLY_FIELDS.contains(&key.as_str()) {
+                    channel_obj[key] = value.clone();
+                }
             }
-        });
-        info!(
-            "[清空渠道配置] 已从 plugins.allow 中删除: {:?}",
-            channel_ids_to_remove
-        );
+            config.upsert_channel(&channel.id, channel_obj);
+
+            // 渠道迁移：wecom 保存后清理旧 wechat 键
+            if channel.id == "wecom" {
+                config.remove_channel("wechat");
+            }
+        }
+        ConfigMutation::ClearChannelConfig { channel_id } => {
+            for id in channel_ids_to_clear(channel_id) {
+                config.remove_channel(&id);
+            }
+        }
     }
+    Ok(())
+}
 
-    // 从 plugins.entries 中删除
-    if let Some(entries) = config
-        .pointer_mut("/plugins/entries")
-        .and_then(|v| v.as_object_mut())
-    {
-        for id in &channel_ids_to_remove {
-            entries.remove(id);
-            info!("[清空渠道配置] 已从 plugins.entries 中删除: {}", id);
+/// openclaw.json 写入后执行：渠道测试字段保存到 / 移出 env 文件
+fn apply_env_changes(change: &ConfigMutation) {
+    let env_path = platform::get_env_file_path();
+    match change {
+        ConfigMutation::SaveChannelConfig { channel } => {
+            for (key, value) in &channel.config {
+                if !TEST_ONLY_FIELDS.contains(&key.as_str()) {
+                    continue;
+                }
+                if let Some(val_str) = value.as_str() {
+                    let env_key = channel_env_key(&channel.id, key);
+                    let _ = dotenv::set_value(&env_path, &env_key, val_str);
+                }
+            }
         }
+        ConfigMutation::ClearChannelConfig { channel_id } => {
+            for id in channel_ids_to_clear(channel_id) {
+                for field in TEST_ONLY_FIELDS {
+                    let _ = dotenv::remove_value(&env_path, &channel_env_key(&id, field));
+                }
+                info!("[清空渠道配置] 已删除渠道及插件启用记录: {}", id);
+            }
+        }
+        _ => {}
     }
+}
 
-    // 清除相关的环境变量
-    for id in &channel_ids_to_remove {
-        let env_prefixes = vec![
-            format!("OPENCLAW_{}_USERID", id.to_uppercase()),
-            format!("OPENCLAW_{}_TESTCHATID", id.to_uppercase()),
-            format!("OPENCLAW_{}_TESTCHANNELID", id.to_uppercase()),
-        ];
-        for env_key in env_prefixes {
-            let _ = file::remove_env_value(&env_path, &env_key);
+/// 修改成功后返回给前端的提示
+fn mutation_message(change: &ConfigMutation) -> String {
+    match change {
+        ConfigMutation::SaveConfig { .. } => "配置已保存".to_string(),
+        ConfigMutation::SaveProvider { provider_name, .. } => {
+            format!("Provider {} 已保存", provider_name)
         }
+        ConfigMutation::DeleteProvider { provider_name } => {
+            format!("Provider {} 已删除", provider_name)
+        }
+        ConfigMutation::SetPrimaryModel { model_id } => format!("主模型已设置为 {}", model_id),
+        ConfigMutation::SaveChannelConfig { channel } => {
+            format!("{} 配置已保存", channel.channel_type)
+        }
+        ConfigMutation::ClearChannelConfig { channel_id } => format!("{} 配置已清空", channel_id),
     }
+}
 
-    // 保存配置
-    match save_openclaw_config(&config) {
-        Ok(_) => {
-            info!("[清空渠道配置] ✓ {} 配置已清空", channel_id);
-            Ok(format!("{} 配置已清空", channel_id))
+/// 写入修改；base_hash 不为空时要求 openclaw.json 自预览后未被改动
+fn commit_mutation(change: &ConfigMutation, base_hash: Option<&str>) -> Result<String, String> {
+    let apply = |config: &mut OpenClawConfig| apply_mutation(config, change);
+    match base_hash {
+        Some(hash) => config_repo::update_checked(hash, apply)?,
+        None => config_repo::update(apply)?,
+    }
+    apply_env_changes(change);
+    Ok(mutation_message(change))
+}
+
+/// 预览配置修改（dry-run）：返回将要发生的变化（密钥已脱敏），不写入磁盘
+#[command]
+pub async fn preview_config_change(change: ConfigMutation) -> Result<ConfigPreview, String> {
+    info!("[配置预览] 预览配置修改...");
+    let preview = config_repo::preview(|config| apply_mutation(config, &change))?;
+    info!("[配置预览] ✓ 共 {} 处变化", preview.changes.len());
+    Ok(preview)
+}
+
+/// 应用预览过的修改：openclaw.json 在预览之后被改动过时拒绝写入
+#[command]
+pub async fn apply_config_change(
+    change: ConfigMutation,
+    base_hash: String,
+) -> Result<String, String> {
+    info!("[配置预览] 应用配置修改...");
+    match commit_mutation(&change, Some(&base_hash)) {
+        Ok(message) => {
+            info!("[配置预览] ✓ {}", message);
+            Ok(message)
         }
         Err(e) => {
-            error!("[清空渠道配置] ✗ 清空失败: {}", e);
+            error!("[配置预览] ✗ 应用失败: {}", e);
             Err(e)
         }
     }
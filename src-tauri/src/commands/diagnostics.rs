@@ -1,35 +1,11 @@
-use crate::models::{AITestResult, ChannelTestResult, DiagnosticResult, SystemInfo};
-use crate::utils::{file, platform, shell};
+use crate::models::{AITestResult, ChannelTestResult, DiagnosticResult, IssueSeverity, SystemInfo};
+use crate::utils::logfile::strip_ansi_codes;
+use crate::utils::{config_repo, dotenv, platform, schema, shell};
 use log::{debug, info, warn};
+use serde_json::json;
 use std::path::PathBuf;
 use tauri::command;
 
-/// 去除 ANSI 转义序列（颜色代码等）
-fn strip_ansi_codes(input: &str) -> String {
-    // 匹配 ANSI 转义序列: ESC[ ... m 或 ESC[ ... 其他控制字符
-    let mut result = String::with_capacity(input.len());
-    let mut chars = input.chars().peekable();
-
-    while let Some(c) = chars.next() {
-        if c == '\x1b' {
-            // 跳过 ESC[...m 序列
-            if chars.peek() == Some(&'[') {
-                chars.next(); // 跳过 '['
-                              // 跳过直到遇到字母
-                while let Some(&next) = chars.peek() {
-                    chars.next();
-                    if next.is_ascii_alphabetic() {
-                        break;
-                    }
-                }
-            }
-        } else {
-            result.push(c);
-        }
-    }
-    result
-}
-
 /// 从混合输出中提取 JSON 内容
 fn extract_json_from_output(output: &str) -> Option<String> {
     // 先去除 ANSI 颜色代码
@@ -169,6 +145,53 @@ pub async fn run_doctor() -> Result<Vec<DiagnosticResult>, String> {
         },
     });
 
+    // 校验配置文件结构
+    if config_exists {
+        match schema::validate() {
+            Ok(report) => {
+                let errors = report
+                    .issues
+                    .iter()
+                    .filter(|i| i.severity == IssueSeverity::Error)
+                    .count();
+                let warnings = report.issues.len() - errors;
+                let details: Vec<String> = report
+                    .issues
+                    .iter()
+                    .take(5)
+                    .map(|i| {
+                        let location = match (i.line, i.column) {
+                            (Some(line), Some(column)) => format!("第 {} 行第 {} 列", line, column),
+                            _ => "未知位置".to_string(),
+                        };
+                        let fix = i
+                            .fix
+                            .as_deref()
+                            .map(|f| format!("（{}）", f))
+                            .unwrap_or_default();
+                        format!("{} {}: {}{}", location, i.pointer, i.message, fix)
+                    })
+                    .collect();
+                results.push(DiagnosticResult {
+                    name: "配置校验".to_string(),
+                    passed: report.valid,
+                    message: if report.issues.is_empty() {
+                        "openclaw.json 结构正确".to_string()
+                    } else {
+                        format!("发现 {} 个错误、{} 个警告", errors, warnings)
+                    },
+                    suggestion: (!details.is_empty()).then(|| details.join("\n")),
+                });
+            }
+            Err(e) => results.push(DiagnosticResult {
+                name: "配置校验".to_string(),
+                passed: false,
+                message: e,
+                suggestion: None,
+            }),
+        }
+    }
+
     Ok(results)
 }
 
@@ -255,7 +278,7 @@ fn get_channel_test_target(channel_type: &str) -> Option<String> {
         _ => return None,
     };
 
-    crate::utils::file::read_env_value(&env_path, env_key)
+    dotenv::read_value(&env_path, env_key)
 }
 
 /// 检查渠道是否需要发送测试消息
@@ -271,15 +294,8 @@ fn channel_needs_send_test(channel_type: &str) -> bool {
 
 /// 从 openclaw.json 检查插件渠道是否已配置（当 channels status 未列出该渠道时使用）
 fn is_plugin_channel_configured_in_config(channel_id: &str) -> Option<String> {
-    let config_path = platform::get_config_file_path();
-    if !file::file_exists(&config_path) {
-        return None;
-    }
-    let content = file::read_file(&config_path).ok()?;
-    let config: serde_json::Value = serde_json::from_str(&content).ok()?;
-    let channels = config.get("channels")?.as_object()?;
-    let ch = channels.get(channel_id)?;
-    let ch_obj = ch.as_object()?;
+    let config = config_repo::load().ok()?;
+    let ch_obj = config.channels().get(channel_id)?.as_object()?;
     match channel_id {
         "qqbot" => {
             let app_id = ch_obj
@@ -764,6 +780,22 @@ pub async fn start_channel_login(channel_type: String) -> Result<String, String>
             info!("[渠道登录] 启用 whatsapp 插件...");
             let _ = shell::run_openclaw(&["plugins", "enable", "whatsapp"]);
 
+            // 确保 whatsapp 在 plugins.allow 中并已启用
+            // （channels.whatsapp 不设置 enabled，WhatsApp 不支持这个键）
+            let updated = config_repo::update(|config| {
+                config.enable_plugin("whatsapp");
+                config
+                    .channels_mut()
+                    .entry("whatsapp".to_string())
+                    .or_insert_with(
+                        || json!({ "dmPolicy": "pairing", "groupPolicy": "allowlist" }),
+                    );
+                Ok(())
+            });
+            if let Err(e) = updated {
+                warn!("[渠道登录] 更新 whatsapp 插件配置失败: {}", e);
+            }
+
             #[cfg(target_os = "macos")]
             {
                 let env_path = platform::get_env_file_path();
@@ -781,44 +813,6 @@ echo ""
 echo "步骤 1/3: 启用 WhatsApp 插件..."
 openclaw plugins enable whatsapp 2>/dev/null || true
 
-# 确保 whatsapp 在 plugins.allow 数组中
-python3 << 'PYEOF'
-import json
-import os
-
-config_path = os.path.expanduser("~/.openclaw/openclaw.json")
-plugin_id = "whatsapp"
-
-try:
-    with open(config_path, 'r') as f:
-        config = json.load(f)
-    
-    # 设置 plugins.allow 和 plugins.entries
-    if 'plugins' not in config:
-        config['plugins'] = {{'allow': [], 'entries': {{}}}}
-    if 'allow' not in config['plugins']:
-        config['plugins']['allow'] = []
-    if 'entries' not in config['plugins']:
-        config['plugins']['entries'] = {{}}
-    
-    if plugin_id not in config['plugins']['allow']:
-        config['plugins']['allow'].append(plugin_id)
-    
-    config['plugins']['entries'][plugin_id] = {{'enabled': True}}
-    
-    # 确保 channels.whatsapp 存在（但不设置 enabled，WhatsApp 不支持这个键）
-    if 'channels' not in config:
-        config['channels'] = {{}}
-    if plugin_id not in config['channels']:
-        config['channels'][plugin_id] = {{'dmPolicy': 'pairing', 'groupPolicy': 'allowlist'}}
-    
-    with open(config_path, 'w') as f:
-        json.dump(config, f, indent=2, ensure_ascii=False)
-    print("配置已更新")
-except Exception as e:
-    print(f"Warning: {{e}}")
-PYEOF
-
 echo "✅ 插件已启用"
 echo ""
 
@@ -827,7 +821,7 @@ echo "步骤 2/3: 重启 Gateway 使插件生效..."
 openclaw gateway stop 2>/dev/null || true
 sleep 2
 # 启动 gateway 服务
-openclaw gateway start 2>/dev/null || openclaw gateway --port 18789 &
+openclaw gateway start 2>/dev/null || openclaw gateway --port {} &
 sleep 3
 echo "✅ Gateway 已重启"
 echo ""
@@ -842,7 +836,8 @@ echo "登录完成！"
 echo ""
 read -p "按回车键关闭此窗口..."
 "#,
-                    env_path
+                    env_path,
+                    crate::utils::gateway::get_gateway_port()
                 );
 
                 let script_path = "/tmp/openclaw_whatsapp_login.command";
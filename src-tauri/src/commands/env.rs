@@ -0,0 +1,149 @@
+use crate::models::{EffectiveEnv, EffectiveEnvVar, EnvConfig, EnvEntry, EnvSource};
+use crate::utils::dotenv::{self, EnvFile};
+use crate::utils::{platform, secrets, shell, systemd};
+use log::{error, info};
+use std::collections::BTreeMap;
+use tauri::command;
+
+fn masked(key: &str, value: &str) -> (String, bool) {
+    if secrets::is_secret_env_key(key) && !value.is_empty() {
+        (secrets::mask(value), true)
+    } else {
+        (value.to_string(), false)
+    }
+}
+
+fn entries(env: &EnvFile) -> Vec<EnvEntry> {
+    env.vars()
+        .into_iter()
+        .map(|(key, value)| {
+            let (value, secret) = masked(&key, &value);
+            EnvEntry { key, value, secret }
+        })
+        .collect()
+}
+
+fn load() -> Result<EnvFile, String> {
+    EnvFile::load(&platform::get_env_file_path()).map_err(|e| format!("读取 env 文件失败: {}", e))
+}
+
+fn save(env: &EnvFile) -> Result<(), String> {
+    env.save(&platform::get_env_file_path())
+        .map_err(|e| format!("写入 env 文件失败: {}", e))
+}
+
+/// 列出 ~/.openclaw/env 中的全部变量（密钥已脱敏）
+#[command]
+pub async fn list_env_vars() -> Result<Vec<EnvEntry>, String> {
+    Ok(entries(&load()?))
+}
+
+/// 批量新增或修改变量（任一变量名无效时不做任何修改）
+#[command]
+pub async fn upsert_env_vars(vars: Vec<EnvConfig>) -> Result<Vec<EnvEntry>, String> {
+    if let Some(invalid) = vars.iter().find(|v| !dotenv::is_valid_key(&v.key)) {
+        return Err(format!(
+            "无效的环境变量名: {}（只能包含字母、数字和下划线，且不能以数字开头）",
+            invalid.key
+        ));
+    }
+    let mut env = load()?;
+    for var in &vars {
+        env.set(&var.key, &var.value).map_err(|e| e.to_string())?;
+    }
+    save(&env)?;
+    let keys: Vec<&str> = vars.iter().map(|v| v.key.as_str()).collect();
+    info!("[环境变量] ✓ 已保存: {:?}", keys);
+    Ok(entries(&env))
+}
+
+/// 删除变量
+#[command]
+pub async fn delete_env_vars(keys: Vec<String>) -> Result<Vec<EnvEntry>, String> {
+    let mut env = load()?;
+    let removed: Vec<&String> = keys.iter().filter(|key| env.remove(key)).collect();
+    if !removed.is_empty() {
+        save(&env)?;
+        info!("[环境变量] ✓ 已删除: {:?}", removed);
+    }
+    Ok(entries(&env))
+}
+
+/// 重命名变量（新名称已存在时拒绝）
+#[command]
+pub async fn rename_env_var(from: String, to: String) -> Result<Vec<EnvEntry>, String> {
+    let mut env = load()?;
+    if let Err(e) = env.rename(&from, &to) {
+        error!("[环境变量] ✗ 重命名 {} -> {} 失败: {}", from, to, e);
+        return Err(e.to_string());
+    }
+    save(&env)?;
+    info!("[环境变量] ✓ 已重命名: {} -> {}", from, to);
+    Ok(entries(&env))
+}
+
+/// Gateway 启动时实际收到的环境变量：Manager 进程环境 + env 文件 + Manager 设置的 PATH
+/// （由 systemd 用户服务启动时不继承 Manager 进程的环境变量，且只收到安装服务时写入单元文件的变量）
+#[command]
+pub async fn get_effective_env() -> Result<EffectiveEnv, String> {
+    let unit = systemd::status();
+    let via_systemd = unit.supported && unit.installed;
+
+    let mut merged: BTreeMap<String, (String, EnvSource, Option<EnvSource>)> = BTreeMap::new();
+    let mut apply = |key: String, value: String, source: EnvSource| {
+        let overrides = merged.get(&key).map(|(_, s, _)| *s);
+        merged.insert(key, (value, source, overrides));
+    };
+    if !via_systemd {
+        for (key, value) in std::env::vars() {
+            apply(key, value, EnvSource::Process);
+        }
+    }
+    for (key, value) in load()?.vars() {
+        apply(key, value, EnvSource::EnvFile);
+    }
+    apply(
+        "PATH".to_string(),
+        shell::get_extended_path(),
+        EnvSource::Manager,
+    );
+
+    // systemd 模式下运行中的 Gateway 使用单元文件里的快照，与当前配置不同的变量需重新安装服务并重启后才生效
+    let running: Option<BTreeMap<String, String>> = via_systemd
+        .then(systemd::unit_environment)
+        .flatten()
+        .map(|vars| vars.into_iter().collect());
+    // env 文件中已删除、但单元文件中仍在使用的变量
+    let removed: Vec<String> = running
+        .iter()
+        .flat_map(BTreeMap::keys)
+        .filter(|k| !merged.contains_key(*k))
+        .cloned()
+        .collect();
+    for key in &removed {
+        merged.insert(key.clone(), (String::new(), EnvSource::EnvFile, None));
+    }
+
+    let vars = merged
+        .into_iter()
+        .map(|(key, (value, source, overrides))| {
+            let (value, pending_restart) = match &running {
+                Some(running) => match running.get(&key) {
+                    Some(current) => (current.clone(), removed.contains(&key) || *current != value),
+                    None => (value, true),
+                },
+                None => (value, false),
+            };
+            let (value, secret) = masked(&key, &value);
+            EffectiveEnvVar {
+                key,
+                value,
+                secret,
+                source,
+                overrides,
+                pending_restart,
+            }
+        })
+        .collect();
+    Ok(EffectiveEnv { via_systemd, vars })
+}
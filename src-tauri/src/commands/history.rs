@@ -0,0 +1,46 @@
+use crate::models::{ServiceEventRecord, UptimeReport};
+use crate::utils::history;
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use tauri::command;
+
+/// 默认返回的事件条数
+const DEFAULT_EVENT_LIMIT: usize = 200;
+
+/// 默认统计窗口（小时）
+const DEFAULT_WINDOW_HOURS: u32 = 24;
+
+fn parse_time(value: Option<String>, name: &str) -> Result<Option<DateTime<Local>>, String> {
+    value
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| {
+            DateTime::parse_from_rfc3339(v.trim())
+                .map(|t| t.with_timezone(&Local))
+                .map_err(|e| format!("{} 时间格式无效（应为 RFC 3339）: {}", name, e))
+        })
+        .transpose()
+}
+
+/// 查询服务事件历史（最新的在前），since / until 为 RFC 3339 时间
+#[command]
+pub async fn get_service_events(
+    since: Option<String>,
+    until: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<ServiceEventRecord>, String> {
+    let since = parse_time(since, "since")?;
+    let until = parse_time(until, "until")?;
+    Ok(history::events(
+        since,
+        until,
+        limit.unwrap_or(DEFAULT_EVENT_LIMIT),
+    ))
+}
+
+/// 统计最近 window_hours 小时（默认 24）的可用率与状态时间线
+#[command]
+pub async fn get_uptime_report(window_hours: Option<u32>) -> Result<UptimeReport, String> {
+    let hours = window_hours.unwrap_or(DEFAULT_WINDOW_HOURS).max(1);
+    let until = Local::now();
+    let since = until - ChronoDuration::hours(hours as i64);
+    Ok(history::uptime(since, until))
+}
@@ -0,0 +1,95 @@
+use crate::models::GatewayLogEntry;
+use crate::utils::logfile::{self, LogFilter};
+use crate::utils::{logstream, platform};
+use chrono::{DateTime, FixedOffset};
+use log::{debug, info};
+use tauri::command;
+
+/// 默认返回的日志行数
+const DEFAULT_LOG_LINES: usize = 200;
+
+/// 单次最多返回的日志行数
+const MAX_LOG_LINES: usize = 5000;
+
+fn parse_time(name: &str, value: Option<String>) -> Result<Option<DateTime<FixedOffset>>, String> {
+    match value.filter(|v| !v.trim().is_empty()) {
+        Some(v) => DateTime::parse_from_rfc3339(v.trim())
+            .map(Some)
+            .map_err(|e| format!("{} 时间格式无效（需要 RFC 3339）: {}", name, e)),
+        None => Ok(None),
+    }
+}
+
+fn parse_level(level: Option<String>) -> Result<Option<usize>, String> {
+    match level.filter(|l| !l.trim().is_empty()) {
+        Some(l) => logfile::level_rank(l.trim()).map(Some).ok_or_else(|| {
+            format!(
+                "不支持的日志级别: {}（可选: {}）",
+                l,
+                logfile::LOG_LEVELS.join(" / ")
+            )
+        }),
+        None => Ok(None),
+    }
+}
+
+/// 获取 Gateway 日志
+/// lines: 返回最近的行数；level: 最低级别；since / until: RFC 3339 时间范围
+#[command]
+pub async fn get_gateway_logs(
+    lines: Option<usize>,
+    level: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+) -> Result<Vec<GatewayLogEntry>, String> {
+    let limit = lines.unwrap_or(DEFAULT_LOG_LINES).clamp(1, MAX_LOG_LINES);
+    let filter = LogFilter {
+        min_level: parse_level(level)?,
+        since: parse_time("since", since)?,
+        until: parse_time("until", until)?,
+        query: None,
+    };
+    debug!("[日志] 读取 Gateway 日志: limit={}, filter={:?}", limit, filter);
+
+    Ok(logfile::read_entries(limit, &filter))
+}
+
+/// 搜索 Gateway 日志（不区分大小写，包含已轮转的历史文件）
+#[command]
+pub async fn search_gateway_logs(
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<GatewayLogEntry>, String> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Err("搜索关键字不能为空".to_string());
+    }
+    let limit = limit.unwrap_or(DEFAULT_LOG_LINES).clamp(1, MAX_LOG_LINES);
+    info!("[日志] 搜索 Gateway 日志: {:?}", query);
+
+    let filter = LogFilter {
+        query: Some(query),
+        ..Default::default()
+    };
+    let entries = logfile::read_entries(limit, &filter);
+    info!("[日志] 找到 {} 条匹配记录", entries.len());
+    Ok(entries)
+}
+
+/// 获取 Gateway 日志文件路径
+#[command]
+pub async fn get_gateway_log_path() -> Result<String, String> {
+    Ok(platform::get_log_file_path())
+}
+
+/// 开始实时推送 Gateway 日志（通过 gateway-log 事件），返回订阅 ID
+#[command]
+pub async fn start_log_stream(app: tauri::AppHandle) -> Result<u64, String> {
+    Ok(logstream::subscribe(app))
+}
+
+/// 停止实时推送 Gateway 日志
+#[command]
+pub async fn stop_log_stream(subscription_id: u64) -> Result<bool, String> {
+    Ok(logstream::unsubscribe(subscription_id))
+}
@@ -1,5 +1,12 @@
+pub mod backup;
+pub mod bundle;
 pub mod config;
 pub mod diagnostics;
+pub mod env;
+pub mod history;
 pub mod installer;
+pub mod logs;
 pub mod process;
+pub mod schedule;
 pub mod service;
+pub mod tls;
@@ -1,4 +1,5 @@
-use crate::utils::shell;
+use crate::models::HealthState;
+use crate::utils::{gateway, health, shell};
 use log::{debug, info};
 use tauri::command;
 
@@ -38,16 +39,21 @@ pub async fn get_openclaw_version() -> Result<Option<String>, String> {
 pub async fn check_port_in_use(port: u16) -> Result<bool, String> {
     info!("[进程检查] 检查端口 {} 是否被占用...", port);
 
-    // 使用 openclaw health 检查 gateway 是否在运行
-    // 如果 port 是默认的 18789，直接使用 openclaw health
-    if port == 18789 {
-        debug!("[进程检查] 使用 openclaw health 检查端口 18789...");
-        let result = shell::run_openclaw(&["health", "--timeout", "2000"]);
-        // 如果 health 命令成功，说明端口被 gateway 占用
-        let in_use = result.is_ok();
+    // 如果 port 是 Gateway 当前端口，直接探测 Gateway 的 HTTP 服务
+    let gateway_port = gateway::get_gateway_port();
+    if port == gateway_port {
+        debug!("[进程检查] 探测 Gateway 健康状态 (端口 {})...", port);
+        let bind = gateway::get_listen_info().bind;
+        let result = tauri::async_runtime::spawn_blocking(move || health::probe(port, &bind))
+            .await
+            .map_err(|e| format!("健康检查失败: {}", e))?;
+        let in_use = result.state != HealthState::Unreachable;
         info!(
-            "[进程检查] 端口 18789 状态: {}",
-            if in_use { "被占用" } else { "空闲" }
+            "[进程检查] 端口 {} 状态: {} ({:?}, {})",
+            port,
+            if in_use { "被占用" } else { "空闲" },
+            result.state,
+            result.message
         );
         return Ok(in_use);
     }
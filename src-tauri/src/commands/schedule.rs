@@ -0,0 +1,47 @@
+use crate::models::{ScheduleSettings, ScheduleStatus, ScheduleTransition};
+use crate::utils::{scheduler, settings};
+use chrono::Local;
+use log::info;
+use tauri::command;
+
+/// 状态中默认返回的后续启停条数
+const DEFAULT_UPCOMING: usize = 5;
+
+/// 获取定时启停配置
+#[command]
+pub async fn get_schedule_settings() -> Result<ScheduleSettings, String> {
+    Ok(settings::load().schedule)
+}
+
+/// 保存定时启停配置（保存到 manager.json），下一次检查时（30 秒内）按新计划启停
+#[command]
+pub async fn save_schedule_settings(schedule: ScheduleSettings) -> Result<ScheduleStatus, String> {
+    info!(
+        "[定时计划] 保存定时计划: enabled={}, {} 条规则",
+        schedule.enabled,
+        schedule.rules.len()
+    );
+    scheduler::validate(&schedule)?;
+    settings::update(|s| s.schedule = schedule)?;
+    scheduler::reload();
+    Ok(scheduler::status(DEFAULT_UPCOMING))
+}
+
+/// 获取定时计划状态（当前是否在运行窗口内、最近一次动作、后续启停）
+#[command]
+pub async fn get_schedule_status() -> Result<ScheduleStatus, String> {
+    Ok(scheduler::status(DEFAULT_UPCOMING))
+}
+
+/// 列出后续的定时启停，limit 默认 10
+#[command]
+pub async fn get_upcoming_transitions(
+    limit: Option<usize>,
+) -> Result<Vec<ScheduleTransition>, String> {
+    let schedule = settings::load().schedule;
+    Ok(scheduler::upcoming(
+        &schedule,
+        Local::now(),
+        limit.unwrap_or(10),
+    ))
+}
@@ -1,261 +1,348 @@
-use crate::models::ServiceStatus;
-use crate::utils::shell;
-use log::{debug, info};
-use std::process::Command;
+use crate::models::{
+    ConfigWatchStatus, EventTrigger, GatewayAccessSettings, GatewayAuthMode, GatewayHealth,
+    GatewayListenInfo, PreflightReport, ServiceStatus, StartError, StopResult, SupervisorStatus,
+    SystemdServiceStatus,
+};
+use crate::utils::{
+    config_repo, config_watch, gateway, health, preflight, secrets, service_watch, settings,
+    supervisor, systemd,
+};
+use log::{info, warn};
 use tauri::command;
 
-#[cfg(windows)]
-use std::os::windows::process::CommandExt;
+/// 获取服务状态（检查端口占用，并读取监听进程的资源占用）
+#[command]
+pub async fn get_service_status() -> Result<ServiceStatus, String> {
+    let mut status = gateway::collect_status_async().await?;
+    status.state = service_watch::classify(&status).0;
+    Ok(status)
+}
 
-/// Windows CREATE_NO_WINDOW 标志，用于隐藏控制台窗口
-#[cfg(windows)]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
+/// 启动服务
+/// 启动前校验 openclaw.json，有阻止启动的问题时返回问题列表；force 为 true 时跳过校验
+#[command]
+pub async fn start_service(force: Option<bool>) -> Result<String, StartError> {
+    supervisor::start_with_reason(EventTrigger::User, "手动启动", !force.unwrap_or(false)).await
+}
 
-const SERVICE_PORT: u16 = 18789;
+/// 停止服务
+/// 逐级升级停止方式（每级等待时间可在 manager.json 的 stop 中配置），
+/// 通过 service-stop-progress 事件推送进度，返回成功停止的阶段
+#[command]
+pub async fn stop_service(app: tauri::AppHandle) -> Result<StopResult, String> {
+    supervisor::stop_with_reason(&app, EventTrigger::User, "手动停止").await
+}
 
-/// 检测端口是否有服务在监听，返回 PID
-/// 简单直接：端口被占用 = 服务运行中
-fn check_port_listening(port: u16) -> Option<u32> {
-    #[cfg(unix)]
-    {
-        let output = Command::new("lsof")
-            .args(["-ti", &format!(":{}", port)])
-            .output()
-            .ok()?;
-
-        if output.status.success() {
-            String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .next()
-                .and_then(|line| line.trim().parse::<u32>().ok())
-        } else {
-            None
-        }
-    }
+/// 重启服务
+#[command]
+pub async fn restart_service(app: tauri::AppHandle) -> Result<String, String> {
+    supervisor::restart_with_reason(&app, EventTrigger::User).await
+}
 
-    #[cfg(windows)]
-    {
-        let mut cmd = Command::new("netstat");
-        cmd.args(["-ano"]);
-        cmd.creation_flags(CREATE_NO_WINDOW);
-
-        let output = cmd.output().ok()?;
-
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                if line.contains(&format!(":{}", port)) && line.contains("LISTENING") {
-                    if let Some(pid_str) = line.split_whitespace().last() {
-                        if let Ok(pid) = pid_str.parse::<u32>() {
-                            return Some(pid);
-                        }
-                    }
-                }
-            }
-        }
-        None
-    }
+/// 启动前配置校验（主模型、渠道凭据、插件安装情况）
+#[command]
+pub async fn check_gateway_config() -> Result<PreflightReport, String> {
+    tauri::async_runtime::spawn_blocking(preflight::check)
+        .await
+        .map_err(|e| format!("配置校验失败: {}", e))
 }
 
-/// 获取服务状态（简单版：直接检查端口占用）
+/// 获取配置变化检测状态（openclaw.json / env 修改后是否需要重启 Gateway）
 #[command]
-pub async fn get_service_status() -> Result<ServiceStatus, String> {
-    // 简单直接：检查端口是否被占用
-    let pid = check_port_listening(SERVICE_PORT);
-    let running = pid.is_some();
-
-    Ok(ServiceStatus {
-        running,
-        pid,
-        port: SERVICE_PORT,
-        uptime_seconds: None,
-        memory_mb: None,
-        cpu_percent: None,
-    })
+pub async fn get_config_watch_status() -> Result<ConfigWatchStatus, String> {
+    Ok(config_watch::status())
 }
 
-/// 启动服务
+/// 保存配置变化检测设置：检测到需要重启的修改后是否自动重启，以及等待配置稳定的秒数
 #[command]
-pub async fn start_service() -> Result<String, String> {
-    info!("[服务] 启动服务...");
-
-    // 检查是否已经运行
-    let status = get_service_status().await?;
-    if status.running {
-        info!("[服务] 服务已在运行中");
-        return Err("服务已在运行中".to_string());
-    }
+pub async fn save_config_watch_settings(
+    auto_restart: bool,
+    debounce_secs: u64,
+) -> Result<ConfigWatchStatus, String> {
+    info!(
+        "[服务] 保存配置变化检测设置: auto_restart={}, debounce_secs={}",
+        auto_restart, debounce_secs
+    );
+    settings::update(|s| {
+        s.config_watch.auto_restart = auto_restart;
+        s.config_watch.debounce_secs = debounce_secs;
+    })?;
+    Ok(config_watch::status())
+}
 
-    // 检查 openclaw 命令是否存在
-    let openclaw_path = shell::get_openclaw_path();
-    if openclaw_path.is_none() {
-        info!("[服务] 找不到 openclaw 命令");
-        return Err(
-            "找不到 openclaw 命令，请先通过 npm install -g openclaw@latest 安装"
-                .to_string(),
-        );
-    }
-    info!("[服务] openclaw 路径: {:?}", openclaw_path);
-
-    // 直接后台启动 gateway（不等待 doctor，避免阻塞）
-    info!("[服务] 后台启动 gateway...");
-    shell::spawn_openclaw_gateway().map_err(|e| format!("启动服务失败: {}", e))?;
-
-    // 轮询等待端口开始监听（最多 15 秒）
-    info!("[服务] 等待端口 {} 开始监听...", SERVICE_PORT);
-    for i in 1..=15 {
-        std::thread::sleep(std::time::Duration::from_secs(1));
-        if let Some(pid) = check_port_listening(SERVICE_PORT) {
-            info!("[服务] ✓ 启动成功 ({}秒), PID: {}", i, pid);
-            return Ok(format!("服务已启动，PID: {}", pid));
-        }
-        if i % 3 == 0 {
-            debug!("[服务] 等待中... ({}秒)", i);
-        }
-    }
+/// 暂不重启：把当前磁盘上的配置视为已应用，清除重启提示
+#[command]
+pub async fn dismiss_config_restart() -> Result<ConfigWatchStatus, String> {
+    Ok(config_watch::dismiss())
+}
 
-    info!("[服务] 等待超时，端口仍未监听");
-    Err("服务启动超时（15秒），请检查 openclaw 日志".to_string())
+/// 探测 Gateway HTTP 健康状态（就绪 / 未就绪 / 渠道异常），并测量延迟
+#[command]
+pub async fn probe_gateway_health() -> Result<GatewayHealth, String> {
+    let bind = gateway::get_listen_info().bind;
+    tauri::async_runtime::spawn_blocking(move || health::probe(gateway::get_gateway_port(), &bind))
+        .await
+        .map_err(|e| format!("健康检查失败: {}", e))
 }
 
-/// 停止服务
+/// 获取 Gateway 守护状态（自动重启次数、退出历史、最近退出原因）
 #[command]
-pub async fn stop_service() -> Result<String, String> {
-    info!("[服务] 停止服务...");
-
-    // 先获取当前 PID
-    let initial_status = get_service_status().await?;
-    if !initial_status.running {
-        info!("[服务] 服务未运行");
-        return Ok("服务已停止".to_string());
-    }
+pub async fn get_supervisor_status() -> Result<SupervisorStatus, String> {
+    Ok(supervisor::status())
+}
 
-    let pid = initial_status.pid;
-    info!("[服务] 检测到服务运行中, PID: {:?}", pid);
+/// 获取 Gateway 监听配置
+#[command]
+pub async fn get_gateway_listen_settings() -> Result<GatewayListenInfo, String> {
+    Ok(gateway::get_listen_info())
+}
 
-    // 第一次尝试：使用 openclaw gateway stop
-    info!("[服务] 尝试优雅停止 (openclaw gateway stop)...");
-    match shell::run_openclaw(&["gateway", "stop"]) {
-        Ok(output) => info!("[服务] stop 命令输出: {}", output),
-        Err(e) => info!("[服务] stop 命令失败: {}", e),
-    }
-    std::thread::sleep(std::time::Duration::from_secs(1));
+/// 保存 Gateway 监听配置（保存到 manager.json，传 None 表示回退到 openclaw.json）
+/// 修改后需重启服务生效；绑定到非本机地址且存在安全风险时需要 confirm 为 true
+#[command]
+pub async fn save_gateway_listen_settings(
+    port: Option<u16>,
+    bind: Option<String>,
+    auto_port: bool,
+    confirm: Option<bool>,
+) -> Result<GatewayListenInfo, String> {
+    info!(
+        "[服务] 保存 Gateway 监听配置: port={:?}, bind={:?}, auto_port={}",
+        port, bind, auto_port
+    );
 
-    let status = get_service_status().await?;
-    if !status.running {
-        info!("[服务] ✓ 已停止");
-        return Ok("服务已停止".to_string());
+    if port == Some(0) {
+        return Err("端口不能为 0".to_string());
     }
+    let bind = bind.map(|b| b.trim().to_lowercase()).filter(|b| !b.is_empty());
+    if let Some(b) = &bind {
+        if !gateway::GATEWAY_BIND_MODES.contains(&b.as_str()) {
+            return Err(format!(
+                "不支持的绑定模式: {}（可选: {}）",
+                b,
+                gateway::GATEWAY_BIND_MODES.join(" / ")
+            ));
+        }
+        let config = config_repo::load()?;
+        let risks = gateway::access_risks(
+            config.gateway_auth_mode(),
+            b,
+            config.gateway_tls_enabled(),
+            config.gateway_allowed_origins(),
+        );
+        ensure_confirmed(&risks, confirm.unwrap_or(false))?;
+    }
+
+    settings::update(|s| {
+        s.gateway.port = port;
+        s.gateway.bind = bind;
+        s.gateway.auto_port = auto_port;
+    })?;
+
+    sync_systemd_unit();
+
+    let listen = gateway::get_listen_info();
+    info!(
+        "[服务] ✓ Gateway 监听配置已保存: {}:{} (来源: {})",
+        listen.bind, listen.port, listen.source
+    );
+    Ok(listen)
+}
 
-    // 第二次尝试：使用 --force 参数
-    info!("[服务] 尝试强制停止 (openclaw gateway stop --force)...");
-    match shell::run_openclaw(&["gateway", "stop", "--force"]) {
-        Ok(output) => info!("[服务] stop --force 命令输出: {}", output),
-        Err(e) => info!("[服务] stop --force 命令失败: {}", e),
+/// 存在安全风险且未确认时拒绝保存
+fn ensure_confirmed(risks: &[String], confirm: bool) -> Result<(), String> {
+    if risks.is_empty() || confirm {
+        return Ok(());
     }
-    std::thread::sleep(std::time::Duration::from_secs(1));
+    let list: Vec<String> = risks.iter().map(|r| format!("- {}", r)).collect();
+    Err(format!(
+        "以下设置存在安全风险，确认后才能保存：\n{}",
+        list.join("\n")
+    ))
+}
+
+/// 获取 Gateway 认证方式、绑定模式和允许的网页来源
+#[command]
+pub async fn get_gateway_access_settings() -> Result<GatewayAccessSettings, String> {
+    gateway::get_access_settings()
+}
+
+/// 保存 Gateway 认证与网络暴露设置（写入 openclaw.json，修改后需重启服务生效）
+/// password 为空时保留已有密码；存在安全风险（如局域网监听但不认证）时需要 confirm 为 true
+#[command]
+pub async fn save_gateway_access_settings(
+    auth_mode: GatewayAuthMode,
+    password: Option<String>,
+    bind: String,
+    allowed_origins: Vec<String>,
+    confirm: bool,
+) -> Result<GatewayAccessSettings, String> {
+    let bind = bind.trim().to_lowercase();
+    info!(
+        "[服务] 保存 Gateway 访问设置: auth={}, bind={}, origins={:?}",
+        auth_mode.as_str(),
+        bind,
+        allowed_origins
+    );
 
-    let status = get_service_status().await?;
-    if !status.running {
-        info!("[服务] ✓ 已停止");
-        return Ok("服务已停止".to_string());
+    if !gateway::GATEWAY_BIND_MODES.contains(&bind.as_str()) {
+        return Err(format!(
+            "不支持的绑定模式: {}（可选: {}）",
+            bind,
+            gateway::GATEWAY_BIND_MODES.join(" / ")
+        ));
     }
-    
-    // 第三次尝试：直接 kill 进程
-    if let Some(pid) = pid {
-        info!("[服务] 使用系统命令终止进程 PID: {}", pid);
-        let kill_result = kill_process(pid);
-        match kill_result {
-            Ok(_) => info!("[服务] kill 命令执行成功"),
-            Err(e) => info!("[服务] kill 命令失败: {}", e),
-        }
-        std::thread::sleep(std::time::Duration::from_secs(1));
-        
-        let status = get_service_status().await?;
-        if !status.running {
-            info!("[服务] ✓ 已停止 (通过 kill 命令)");
-            return Ok("服务已停止".to_string());
-        }
-        
-        // 最后尝试：强制 kill -9
-        info!("[服务] 使用 kill -9 强制终止进程 PID: {}", pid);
-        let force_kill_result = force_kill_process(pid);
-        match force_kill_result {
-            Ok(_) => info!("[服务] kill -9 命令执行成功"),
-            Err(e) => info!("[服务] kill -9 命令失败: {}", e),
-        }
-        std::thread::sleep(std::time::Duration::from_secs(1));
-        
-        let status = get_service_status().await?;
-        if !status.running {
-            info!("[服务] ✓ 已停止 (通过 kill -9)");
-            return Ok("服务已停止".to_string());
+    let mut origins: Vec<String> = Vec::new();
+    for origin in &allowed_origins {
+        let origin = gateway::normalize_origin(origin)?;
+        if !origins.contains(&origin) {
+            origins.push(origin);
         }
     }
-    
-    Err(format!("无法停止服务，PID: {:?}", pid))
-}
-
-/// 终止进程 (SIGTERM)
-fn kill_process(pid: u32) -> Result<(), String> {
-    #[cfg(unix)]
+    let password = password.filter(|p| !p.is_empty());
+    if password
+        .as_ref()
+        .is_some_and(|p| p.chars().count() < gateway::MIN_GATEWAY_PASSWORD_LEN)
     {
-        Command::new("kill")
-            .arg(pid.to_string())
-            .output()
-            .map_err(|e| format!("kill 命令失败: {}", e))?;
-        Ok(())
+        return Err(format!(
+            "密码至少需要 {} 个字符",
+            gateway::MIN_GATEWAY_PASSWORD_LEN
+        ));
     }
-    
-    #[cfg(windows)]
+    let current = config_repo::load()?;
+    if auth_mode == GatewayAuthMode::Password
+        && password.is_none()
+        && current.gateway_password().is_none()
     {
-        let mut cmd = Command::new("taskkill");
-        cmd.args(["/PID", &pid.to_string()]);
-        cmd.creation_flags(CREATE_NO_WINDOW);
-        cmd.output()
-            .map_err(|e| format!("taskkill 命令失败: {}", e))?;
+        return Err("使用密码认证时需要设置密码".to_string());
+    }
+    let tls = current.gateway_tls_enabled();
+    ensure_confirmed(
+        &gateway::access_risks(auth_mode, &bind, tls, &origins),
+        confirm,
+    )?;
+
+    config_repo::update(|config| {
+        if auth_mode == GatewayAuthMode::Token && config.gateway_token().is_none() {
+            config.set_gateway_token(secrets::generate_token()?);
+        }
+        let gateway = config.gateway_mut();
+        let auth = gateway.auth.get_or_insert_with(Default::default);
+        auth.mode = Some(auth_mode.as_str().to_string());
+        if let Some(password) = &password {
+            auth.password = Some(password.clone());
+        }
+        gateway.bind = Some(bind.clone());
+        if origins.is_empty() {
+            if let Some(ui) = gateway.control_ui.as_mut() {
+                ui.allowed_origins = None;
+            }
+        } else {
+            gateway
+                .control_ui
+                .get_or_insert_with(Default::default)
+                .allowed_origins = Some(origins.clone());
+        }
         Ok(())
+    })?;
+
+    // Manager 中保存的绑定模式优先于 openclaw.json，清除后以这里的设置为准
+    if settings::load().gateway.bind.is_some() {
+        settings::update(|s| s.gateway.bind = None)?;
     }
+
+    sync_systemd_unit();
+
+    let access = gateway::get_access_settings()?;
+    info!("[服务] ✓ Gateway 访问设置已保存");
+    Ok(access)
 }
 
-/// 强制终止进程 (SIGKILL / taskkill /F)
-fn force_kill_process(pid: u32) -> Result<(), String> {
-    #[cfg(unix)]
-    {
-        Command::new("kill")
-            .args(["-9", &pid.to_string()])
-            .output()
-            .map_err(|e| format!("kill -9 命令失败: {}", e))?;
-        Ok(())
+/// 监听 / 访问设置已保存，同步 systemd 单元文件中的端口和绑定模式（失败不影响保存，状态中会提示需要重新安装）
+fn sync_systemd_unit() {
+    match systemd::sync_unit() {
+        Ok(true) => info!("[服务] ✓ 已按新设置更新 systemd 单元文件"),
+        Ok(false) => {}
+        Err(e) => warn!(
+            "[服务] ✗ 更新 systemd 单元文件失败，需要重新安装服务: {}",
+            e
+        ),
     }
-    
-    #[cfg(windows)]
-    {
-        let mut cmd = Command::new("taskkill");
-        cmd.args(["/F", "/PID", &pid.to_string()]);
-        cmd.creation_flags(CREATE_NO_WINDOW);
-        cmd.output()
-            .map_err(|e| format!("taskkill /F 命令失败: {}", e))?;
+}
+
+fn ensure_systemd_supported() -> Result<(), String> {
+    if systemd::is_supported() {
         Ok(())
+    } else {
+        Err("当前系统不支持 systemd 用户服务（仅支持 Linux）".to_string())
     }
 }
 
-/// 重启服务
+/// 获取 systemd 用户服务状态
+#[command]
+pub async fn get_systemd_service_status() -> Result<SystemdServiceStatus, String> {
+    Ok(systemd::status())
+}
+
+/// 预览将要安装的 systemd 单元文件
 #[command]
-pub async fn restart_service() -> Result<String, String> {
-    info!("[服务] 重启服务...");
+pub async fn generate_systemd_unit() -> Result<String, String> {
+    ensure_systemd_supported()?;
+    systemd::generate_unit()
+}
 
-    let _ = shell::run_openclaw(&["gateway", "restart"]);
-    std::thread::sleep(std::time::Duration::from_secs(2));
+/// 安装 systemd 用户服务（写入单元文件，不启动）
+#[command]
+pub async fn install_systemd_service() -> Result<SystemdServiceStatus, String> {
+    info!("[服务] 安装 systemd 用户服务...");
+    ensure_systemd_supported()?;
+    systemd::install()?;
+    Ok(systemd::status())
+}
 
-    let status = get_service_status().await?;
-    if status.running {
-        info!("[服务] ✓ 重启成功, PID: {:?}", status.pid);
-        Ok(format!("服务已重启，PID: {:?}", status.pid))
-    } else {
-        // 手动停止再启动
-        let _ = stop_service().await;
-        std::thread::sleep(std::time::Duration::from_secs(1));
-        start_service().await
+/// 设置 systemd 用户服务自启，start_now 为 true 时立即启动
+/// 如果 Gateway 正由本应用运行，会先停止再交给 systemd
+#[command]
+pub async fn enable_systemd_service(
+    app: tauri::AppHandle,
+    start_now: bool,
+) -> Result<SystemdServiceStatus, String> {
+    info!("[服务] 启用 systemd 用户服务 (start_now={})...", start_now);
+    ensure_systemd_supported()?;
+    if !systemd::unit_path().exists() {
+        systemd::install()?;
+    }
+
+    if start_now {
+        let status = gateway::collect_status_async().await?;
+        if status.running && status.managed_by.as_deref() == Some("manager") {
+            info!("[服务] Gateway 正由 Manager 运行，先停止再交给 systemd");
+            supervisor::stop_with_reason(&app, EventTrigger::User, "交由 systemd 管理").await?;
+        }
     }
+
+    systemd::enable(start_now)?;
+    service_watch::notify(Some("已交由 systemd 管理".to_string()));
+    info!("[服务] ✓ systemd 用户服务已启用");
+    Ok(systemd::status())
+}
+
+/// 取消 systemd 用户服务自启并停止
+#[command]
+pub async fn disable_systemd_service() -> Result<SystemdServiceStatus, String> {
+    info!("[服务] 禁用 systemd 用户服务...");
+    ensure_systemd_supported()?;
+    systemd::disable()?;
+    service_watch::notify(None);
+    Ok(systemd::status())
+}
+
+/// 删除 systemd 用户服务（停止、禁用并删除单元文件）
+#[command]
+pub async fn remove_systemd_service() -> Result<SystemdServiceStatus, String> {
+    info!("[服务] 删除 systemd 用户服务...");
+    ensure_systemd_supported()?;
+    systemd::remove()?;
+    service_watch::notify(None);
+    Ok(systemd::status())
 }
@@ -0,0 +1,45 @@
+use crate::models::GatewayTlsStatus;
+use crate::utils::tls;
+use log::error;
+use std::path::PathBuf;
+use tauri::command;
+
+/// 获取 Gateway HTTPS 状态（本地 CA、服务器证书和 HTTPS 访问地址）
+#[command]
+pub async fn get_gateway_tls_status() -> Result<GatewayTlsStatus, String> {
+    Ok(tls::status())
+}
+
+/// 启用 Gateway HTTPS：按需生成本地 CA 并签发服务器证书，修改后需重启服务生效
+#[command]
+pub async fn enable_gateway_tls() -> Result<GatewayTlsStatus, String> {
+    let result = tls::enable();
+    if let Err(e) = &result {
+        error!("[HTTPS] ✗ 启用失败: {}", e);
+    }
+    result
+}
+
+/// 停用 Gateway HTTPS（保留证书），修改后需重启服务生效
+#[command]
+pub async fn disable_gateway_tls() -> Result<GatewayTlsStatus, String> {
+    tls::disable()
+}
+
+/// 重新签发服务器证书（本机 IP 或主机名变化、证书即将过期时），CA 不变，客户端无需重新安装
+#[command]
+pub async fn renew_gateway_certificate() -> Result<GatewayTlsStatus, String> {
+    let result = tls::issue_certificate();
+    if let Err(e) = &result {
+        error!("[HTTPS] ✗ 签发证书失败: {}", e);
+    }
+    result
+}
+
+/// 导出本地 CA 证书，安装到手机等客户端设备后即可信任 Gateway 的 HTTPS 证书
+/// 返回导出的文件路径
+#[command]
+pub async fn export_gateway_ca(path: String) -> Result<String, String> {
+    tls::export_ca(&PathBuf::from(&path))?;
+    Ok(path)
+}
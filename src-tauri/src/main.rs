@@ -8,7 +8,10 @@ mod commands;
 mod models;
 mod utils;
 
-use commands::{config, diagnostics, installer, process, service};
+use commands::{
+    backup, bundle, config, diagnostics, env, history, installer, logs, process, schedule, service,
+    tls,
+};
 
 fn main() {
     // 初始化日志 - 默认显示 info 级别日志
@@ -24,12 +27,58 @@ fn main() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_notification::init())
+        .setup(|app| {
+            // 后台检测 Gateway 状态变化，通过 service-state-changed 事件推送给前端
+            utils::service_watch::start(app.handle().clone());
+            // 按定时计划启停 Gateway
+            utils::scheduler::start(app.handle().clone());
+            // 检测 openclaw.json / env 变化，提示或自动重启 Gateway
+            utils::config_watch::start(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // 服务管理
             service::get_service_status,
             service::start_service,
             service::stop_service,
             service::restart_service,
+            service::get_supervisor_status,
+            service::probe_gateway_health,
+            service::check_gateway_config,
+            service::get_config_watch_status,
+            service::save_config_watch_settings,
+            service::dismiss_config_restart,
+            service::get_gateway_listen_settings,
+            service::save_gateway_listen_settings,
+            service::get_gateway_access_settings,
+            service::save_gateway_access_settings,
+            // Gateway HTTPS
+            tls::get_gateway_tls_status,
+            tls::enable_gateway_tls,
+            tls::disable_gateway_tls,
+            tls::renew_gateway_certificate,
+            tls::export_gateway_ca,
+            // systemd 用户服务 (Linux)
+            service::get_systemd_service_status,
+            service::generate_systemd_unit,
+            service::install_systemd_service,
+            service::enable_systemd_service,
+            service::disable_systemd_service,
+            service::remove_systemd_service,
+            // 服务事件历史
+            history::get_service_events,
+            history::get_uptime_report,
+            // 定时启停
+            schedule::get_schedule_settings,
+            schedule::save_schedule_settings,
+            schedule::get_schedule_status,
+            schedule::get_upcoming_transitions,
+            // Gateway 日志
+            logs::get_gateway_logs,
+            logs::search_gateway_logs,
+            logs::get_gateway_log_path,
+            logs::start_log_stream,
+            logs::stop_log_stream,
             // 进程管理
             process::check_openclaw_installed,
             process::get_openclaw_version,
@@ -39,14 +88,23 @@ fn main() {
             config::check_manager_update_from_latest,
             config::get_config,
             config::save_config,
+            config::validate_openclaw_config,
             config::get_env_value,
             config::save_env_value,
+            env::list_env_vars,
+            env::upsert_env_vars,
+            env::delete_env_vars,
+            env::rename_env_var,
+            env::get_effective_env,
             config::get_ai_providers,
             config::get_channels_config,
             config::save_channel_config,
             config::clear_channel_config,
+            config::preview_config_change,
+            config::apply_config_change,
             // Gateway Token
             config::get_or_create_gateway_token,
+            config::rotate_gateway_token,
             config::get_dashboard_url,
             // AI 配置管理
             config::get_recommended_providers,
@@ -57,6 +115,16 @@ fn main() {
             config::set_primary_model,
             config::add_available_model,
             config::remove_available_model,
+            // openclaw.json 备份
+            backup::list_config_backups,
+            backup::diff_config_backup,
+            backup::restore_config_backup,
+            backup::get_backup_settings,
+            backup::save_backup_settings,
+            // 配置包导出 / 导入
+            bundle::export_setup_bundle,
+            bundle::preview_setup_bundle,
+            bundle::import_setup_bundle,
             // 飞书插件管理
             config::check_feishu_plugin,
             config::install_feishu_plugin,
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 一个 openclaw.json 备份
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigBackup {
+    /// 备份 ID（备份目录中的文件名）
+    pub id: String,
+    /// 备份时间（RFC 3339）
+    pub created_at: String,
+    /// 文件大小（字节）
+    pub size: u64,
+}
+
+/// JSON 变化类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// 两份 JSON 之间的一处变化
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonChange {
+    /// 变化的路径（如 models.providers.openai.apiKey）
+    pub path: String,
+    pub kind: ChangeKind,
+    /// 旧值（新增时为空）
+    pub old: Option<Value>,
+    /// 新值（删除时为空）
+    pub new: Option<Value>,
+}
@@ -0,0 +1,97 @@
+use crate::models::{ChangeKind, JsonChange};
+use serde::{Deserialize, Serialize};
+
+/// 导出时如何处理密钥（apiKey / token / secret 等字段及同名环境变量）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretMode {
+    /// 原样导出
+    #[default]
+    Include,
+    /// 移除，导入时保留目标机器上已有的值
+    Strip,
+    /// 用密码加密后单独存放
+    Encrypt,
+}
+
+/// 密钥加密参数（PBKDF2-HMAC-SHA256 派生密钥，AES-256-GCM 加密）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleEncryption {
+    pub iterations: u32,
+    /// 盐（Base64）
+    pub salt: String,
+    /// 随机数（Base64）
+    pub nonce: String,
+}
+
+/// 导出时已安装的插件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundlePlugin {
+    pub id: String,
+    /// 安装来源，如 npm 包名
+    pub spec: Option<String>,
+    pub version: Option<String>,
+}
+
+/// 配置包清单（manifest.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    /// 导出时间（RFC 3339）
+    pub created_at: String,
+    /// 导出时的 Manager 版本
+    pub manager_version: String,
+    /// 导出时的操作系统
+    pub os: String,
+    pub secret_mode: SecretMode,
+    #[serde(default)]
+    pub encryption: Option<BundleEncryption>,
+    /// 被移出的密钥位置：openclaw.json#<JSON Pointer> 或 env#<变量名>
+    #[serde(default)]
+    pub secret_locations: Vec<String>,
+    #[serde(default)]
+    pub plugins: Vec<BundlePlugin>,
+    /// 包内的文件
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+/// 导入时会写入的一个文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleFileChange {
+    /// 相对 ~/.openclaw 的路径
+    pub path: String,
+    /// added = 新建，modified = 覆盖已有文件（内容相同的文件不列出）
+    pub kind: ChangeKind,
+}
+
+/// 导入预览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleImportPreview {
+    pub manifest: BundleManifest,
+    /// openclaw.json 的变化（密钥已脱敏）
+    pub config_changes: Vec<JsonChange>,
+    /// env 文件的变化（密钥已脱敏）
+    pub env_changes: Vec<JsonChange>,
+    /// 会新建或覆盖的文件
+    pub files: Vec<BundleFileChange>,
+    /// 本机未安装、导入后需要安装的插件（spec 为实际安装时使用的规格，无效或缺失时为空）
+    pub missing_plugins: Vec<BundlePlugin>,
+    /// 导入后仍然缺失、需要重新填写的密钥位置
+    pub missing_secrets: Vec<String>,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleImportResult {
+    /// 导入前当前配置的完整快照（配置包路径）
+    pub snapshot_path: String,
+    /// 已写入的文件
+    pub files_written: Vec<String>,
+    /// 已安装的插件
+    pub plugins_installed: Vec<String>,
+    /// 安装失败的插件及原因
+    pub plugin_errors: Vec<String>,
+    /// 仍然缺失、需要重新填写的密钥位置
+    pub missing_secrets: Vec<String>,
+}
@@ -1,171 +1,598 @@
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use crate::models::JsonChange;
+use chrono::Utc;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// 未建模的字段，读取后原样写回，避免丢失新版 OpenClaw 写入的配置
+pub type ExtraFields = Map<String, Value>;
+
+// 文件中可能不存在的节点都用 Option 表示：读取时不存在的节点写回时同样不存在，
+// 显式写出的空对象 / 空数组 / 空字符串也会原样保留
+
+static EMPTY_VALUES: BTreeMap<String, Value> = BTreeMap::new();
+static EMPTY_PROVIDERS: BTreeMap<String, ProviderConfig> = BTreeMap::new();
+
+/// 整数写成整数（0 而不是 0.0），避免仅因数字格式不同被判定为配置变化
+fn serialize_number<S: Serializer>(value: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(v) if v.fract() == 0.0 && v.abs() < 1e15 => serializer.serialize_i64(*v as i64),
+        Some(v) => serializer.serialize_f64(*v),
+        None => serializer.serialize_none(),
+    }
+}
 
 /// OpenClaw 完整配置 - 对应 openclaw.json 结构
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct OpenClawConfig {
     /// Agent 配置
-    #[serde(default)]
-    pub agents: AgentsConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agents: Option<AgentsConfig>,
     /// 模型配置
-    #[serde(default)]
-    pub models: ModelsConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub models: Option<ModelsConfig>,
     /// 网关配置
-    #[serde(default)]
-    pub gateway: GatewayConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gateway: Option<GatewayConfig>,
     /// 渠道配置
-    #[serde(default)]
-    pub channels: HashMap<String, serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channels: Option<BTreeMap<String, Value>>,
     /// 插件配置
-    #[serde(default)]
-    pub plugins: PluginsConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugins: Option<PluginsConfig>,
     /// 元数据
-    #[serde(default)]
-    pub meta: MetaConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<MetaConfig>,
+    /// 其他顶层字段
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 /// Agent 配置
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct AgentsConfig {
     /// 默认配置
-    #[serde(default)]
-    pub defaults: AgentDefaults,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defaults: Option<AgentDefaults>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 /// Agent 默认配置
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct AgentDefaults {
     /// 模型配置
-    #[serde(default)]
-    pub model: AgentModelConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<AgentModelConfig>,
     /// 可用模型列表 (provider/model -> {})
-    #[serde(default)]
-    pub models: HashMap<String, serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub models: Option<BTreeMap<String, Value>>,
     /// 压缩配置
-    #[serde(default)]
-    pub compaction: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compaction: Option<Value>,
     /// 上下文裁剪
-    #[serde(rename = "contextPruning", default)]
-    pub context_pruning: Option<serde_json::Value>,
+    #[serde(
+        rename = "contextPruning",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub context_pruning: Option<Value>,
     /// 心跳配置
-    #[serde(default)]
-    pub heartbeat: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heartbeat: Option<Value>,
     /// 最大并发数
-    #[serde(rename = "maxConcurrent", default)]
+    #[serde(
+        rename = "maxConcurrent",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
     pub max_concurrent: Option<u32>,
     /// 子代理配置
-    #[serde(default)]
-    pub subagents: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subagents: Option<Value>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 /// Agent 模型配置
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct AgentModelConfig {
     /// 主模型 (格式: provider/model-id)
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub primary: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 /// 模型配置
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct ModelsConfig {
     /// Provider 配置映射
-    #[serde(default)]
-    pub providers: HashMap<String, ProviderConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub providers: Option<BTreeMap<String, ProviderConfig>>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 /// Provider 配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct ProviderConfig {
     /// API 地址
-    #[serde(rename = "baseUrl")]
-    pub base_url: String,
+    #[serde(rename = "baseUrl", default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
     /// API Key
-    #[serde(rename = "apiKey")]
+    #[serde(rename = "apiKey", default, skip_serializing_if = "Option::is_none")]
     pub api_key: Option<String>,
     /// 模型列表
-    #[serde(default)]
-    pub models: Vec<ModelConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub models: Option<Vec<ModelConfig>>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
-/// 模型配置详情
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 模型配置详情（前端提交时字段为 snake_case，通过 alias 兼容）
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct ModelConfig {
     /// 模型 ID
     pub id: String,
     /// 显示名称
-    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     /// API 类型 (anthropic-messages / openai-completions)
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub api: Option<String>,
     /// 支持的输入类型
-    #[serde(default)]
-    pub input: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<Vec<String>>,
     /// 上下文窗口大小
-    #[serde(rename = "contextWindow", default)]
+    #[serde(
+        rename = "contextWindow",
+        alias = "context_window",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
     pub context_window: Option<u32>,
     /// 最大输出 Token
-    #[serde(rename = "maxTokens", default)]
+    #[serde(
+        rename = "maxTokens",
+        alias = "max_tokens",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
     pub max_tokens: Option<u32>,
     /// 是否支持推理模式
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub reasoning: Option<bool>,
     /// 成本配置
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub cost: Option<ModelCostConfig>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 /// 模型成本配置
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct ModelCostConfig {
-    #[serde(default)]
-    pub input: f64,
-    #[serde(default)]
-    pub output: f64,
-    #[serde(rename = "cacheRead", default)]
-    pub cache_read: f64,
-    #[serde(rename = "cacheWrite", default)]
-    pub cache_write: f64,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_number"
+    )]
+    pub input: Option<f64>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_number"
+    )]
+    pub output: Option<f64>,
+    #[serde(
+        rename = "cacheRead",
+        alias = "cache_read",
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_number"
+    )]
+    pub cache_read: Option<f64>,
+    #[serde(
+        rename = "cacheWrite",
+        alias = "cache_write",
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_number"
+    )]
+    pub cache_write: Option<f64>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 /// 网关配置
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct GatewayConfig {
     /// 模式：local 或 cloud
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub mode: Option<String>,
+    /// 绑定模式：loopback / lan / tailnet / auto
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bind: Option<String>,
     /// 认证配置
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub auth: Option<GatewayAuthConfig>,
+    /// 控制台配置
+    #[serde(rename = "controlUi", default, skip_serializing_if = "Option::is_none")]
+    pub control_ui: Option<GatewayControlUiConfig>,
+    /// HTTPS 配置
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<GatewayTlsConfig>,
+    /// 其他字段（port 等）
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 /// 网关认证配置
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct GatewayAuthConfig {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub mode: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// 网关控制台配置
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct GatewayControlUiConfig {
+    /// 允许连接 Gateway 的网页来源（如 https://192.168.1.10:18789）
+    #[serde(
+        rename = "allowedOrigins",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub allowed_origins: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// 网关 HTTPS 配置
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct GatewayTlsConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// 服务器证书（PEM，含证书链）
+    #[serde(rename = "certPath", default, skip_serializing_if = "Option::is_none")]
+    pub cert_path: Option<String>,
+    /// 服务器私钥（PEM）
+    #[serde(rename = "keyPath", default, skip_serializing_if = "Option::is_none")]
+    pub key_path: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 /// 插件配置
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct PluginsConfig {
-    #[serde(default)]
-    pub allow: Vec<String>,
-    #[serde(default)]
-    pub entries: HashMap<String, serde_json::Value>,
-    #[serde(default)]
-    pub installs: HashMap<String, serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entries: Option<BTreeMap<String, Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installs: Option<BTreeMap<String, Value>>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 /// 元数据配置
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct MetaConfig {
-    #[serde(rename = "lastTouchedAt", default)]
+    #[serde(
+        rename = "lastTouchedAt",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
     pub last_touched_at: Option<String>,
-    #[serde(rename = "lastTouchedVersion", default)]
+    #[serde(
+        rename = "lastTouchedVersion",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
     pub last_touched_version: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl OpenClawConfig {
+    /// agents.defaults（未配置时为 None）
+    pub fn agent_defaults(&self) -> Option<&AgentDefaults> {
+        self.agents.as_ref().and_then(|a| a.defaults.as_ref())
+    }
+
+    /// agents.defaults，不存在时创建
+    fn agent_defaults_mut(&mut self) -> &mut AgentDefaults {
+        self.agents
+            .get_or_insert_with(Default::default)
+            .defaults
+            .get_or_insert_with(Default::default)
+    }
+
+    /// models.providers（未配置时为空）
+    pub fn providers(&self) -> &BTreeMap<String, ProviderConfig> {
+        self.models
+            .as_ref()
+            .and_then(|m| m.providers.as_ref())
+            .unwrap_or(&EMPTY_PROVIDERS)
+    }
+
+    /// models.providers，不存在时创建
+    fn providers_mut(&mut self) -> &mut BTreeMap<String, ProviderConfig> {
+        self.models
+            .get_or_insert_with(Default::default)
+            .providers
+            .get_or_insert_with(Default::default)
+    }
+
+    /// 渠道配置（未配置时为空）
+    pub fn channels(&self) -> &BTreeMap<String, Value> {
+        self.channels.as_ref().unwrap_or(&EMPTY_VALUES)
+    }
+
+    /// 渠道配置，不存在时创建
+    pub fn channels_mut(&mut self) -> &mut BTreeMap<String, Value> {
+        self.channels.get_or_insert_with(Default::default)
+    }
+
+    /// gateway 配置，不存在时创建
+    pub fn gateway_mut(&mut self) -> &mut GatewayConfig {
+        self.gateway.get_or_insert_with(Default::default)
+    }
+
+    /// plugins.allow（未配置时为空）
+    pub fn plugins_allow(&self) -> &[String] {
+        self.plugins
+            .as_ref()
+            .and_then(|p| p.allow.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// plugins.installs（未配置时为空）
+    pub fn plugin_installs(&self) -> &BTreeMap<String, Value> {
+        self.plugins
+            .as_ref()
+            .and_then(|p| p.installs.as_ref())
+            .unwrap_or(&EMPTY_VALUES)
+    }
+
+    /// 主模型（provider/model-id）
+    pub fn primary_model(&self) -> Option<&str> {
+        self.agent_defaults()
+            .and_then(|d| d.model.as_ref())
+            .and_then(|m| m.primary.as_deref())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// 设置或清除主模型
+    pub fn set_primary_model(&mut self, model: Option<String>) {
+        match model {
+            Some(model) => {
+                self.agent_defaults_mut()
+                    .model
+                    .get_or_insert_with(Default::default)
+                    .primary = Some(model);
+            }
+            None => {
+                if let Some(m) = self
+                    .agents
+                    .as_mut()
+                    .and_then(|a| a.defaults.as_mut())
+                    .and_then(|d| d.model.as_mut())
+                {
+                    m.primary = None;
+                }
+            }
+        }
+    }
+
+    /// 可用模型列表
+    pub fn available_models(&self) -> Vec<String> {
+        self.agent_defaults()
+            .and_then(|d| d.models.as_ref())
+            .map(|m| m.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// 添加可用模型（已存在时保留原有的模型参数）
+    pub fn add_available_model(&mut self, full_id: &str) {
+        self.agent_defaults_mut()
+            .models
+            .get_or_insert_with(Default::default)
+            .entry(full_id.to_string())
+            .or_insert_with(|| json!({}));
+    }
+
+    /// 移除可用模型
+    pub fn remove_available_model(&mut self, full_id: &str) -> bool {
+        self.agents
+            .as_mut()
+            .and_then(|a| a.defaults.as_mut())
+            .and_then(|d| d.models.as_mut())
+            .is_some_and(|m| m.remove(full_id).is_some())
+    }
+
+    /// 添加或更新 Provider，并把它的模型加入可用列表
+    /// - api_key 为 None 时保留原有的 API Key
+    /// - 原有 Provider / 模型中未建模的字段会合并保留
+    pub fn upsert_provider(&mut self, name: &str, mut provider: ProviderConfig) {
+        if let Some(existing) = self.providers_mut().remove(name) {
+            if provider.api_key.is_none() {
+                provider.api_key = existing.api_key;
+            }
+            let old_models = existing.models.as_deref().unwrap_or_default();
+            for model in provider.models.iter_mut().flatten() {
+                if let Some(old) = old_models.iter().find(|m| m.id == model.id) {
+                    for (key, value) in &old.extra {
+                        model.extra.entry(key.clone()).or_insert(value.clone());
+                    }
+                }
+            }
+            for (key, value) in existing.extra {
+                provider.extra.entry(key).or_insert(value);
+            }
+        }
+
+        for model in provider.models() {
+            self.add_available_model(&format!("{}/{}", name, model.id));
+        }
+        self.providers_mut().insert(name.to_string(), provider);
+    }
+
+    /// 删除 Provider，同时移除它的可用模型；主模型属于该 Provider 时一并清除
+    pub fn remove_provider(&mut self, name: &str) -> bool {
+        let prefix = format!("{}/", name);
+        if let Some(models) = self
+            .agents
+            .as_mut()
+            .and_then(|a| a.defaults.as_mut())
+            .and_then(|d| d.models.as_mut())
+        {
+            models.retain(|id, _| !id.starts_with(&prefix));
+        }
+        if self.primary_model().is_some_and(|p| p.starts_with(&prefix)) {
+            self.set_primary_model(None);
+        }
+        self.models
+            .as_mut()
+            .and_then(|m| m.providers.as_mut())
+            .is_some_and(|p| p.remove(name).is_some())
+    }
+
+    /// 保存渠道配置，并在插件白名单中启用同名插件
+    pub fn upsert_channel(&mut self, id: &str, config: Value) {
+        self.channels_mut().insert(id.to_string(), config);
+        self.enable_plugin(id);
+    }
+
+    /// 删除渠道配置及同名插件的启用记录
+    pub fn remove_channel(&mut self, id: &str) -> bool {
+        self.disable_plugin(id);
+        self.channels
+            .as_mut()
+            .is_some_and(|c| c.remove(id).is_some())
+    }
+
+    /// 把插件加入 plugins.allow 并在 plugins.entries 中启用（保留 entry 的其他字段）
+    pub fn enable_plugin(&mut self, id: &str) {
+        let plugins = self.plugins.get_or_insert_with(Default::default);
+        let allow = plugins.allow.get_or_insert_with(Default::default);
+        if !allow.iter().any(|a| a == id) {
+            allow.push(id.to_string());
+        }
+        let entry = plugins
+            .entries
+            .get_or_insert_with(Default::default)
+            .entry(id.to_string())
+            .or_insert_with(|| json!({}));
+        match entry.as_object_mut() {
+            Some(obj) => {
+                obj.insert("enabled".to_string(), json!(true));
+            }
+            None => *entry = json!({ "enabled": true }),
+        }
+    }
+
+    /// 从 plugins.allow 和 plugins.entries 中移除插件（不影响 plugins.installs）
+    pub fn disable_plugin(&mut self, id: &str) {
+        let Some(plugins) = self.plugins.as_mut() else {
+            return;
+        };
+        if let Some(allow) = plugins.allow.as_mut() {
+            allow.retain(|a| a != id);
+        }
+        if let Some(entries) = plugins.entries.as_mut() {
+            entries.remove(id);
+        }
+    }
+
+    /// gateway.auth（未配置时为 None）
+    fn gateway_auth(&self) -> Option<&GatewayAuthConfig> {
+        self.gateway.as_ref().and_then(|g| g.auth.as_ref())
+    }
+
+    /// Gateway 认证 Token（未设置或为空时返回 None）
+    pub fn gateway_token(&self) -> Option<&str> {
+        self.gateway_auth()
+            .and_then(|a| a.token.as_deref())
+            .filter(|t| !t.is_empty())
+    }
+
+    /// 设置 Gateway Token，并切换为本地模式（未指定认证方式时使用 token 认证）
+    pub fn set_gateway_token(&mut self, token: String) {
+        let gateway = self.gateway_mut();
+        let auth = gateway.auth.get_or_insert_with(Default::default);
+        auth.token = Some(token);
+        auth.mode.get_or_insert_with(|| "token".to_string());
+        gateway.mode = Some("local".to_string());
+    }
+
+    /// Gateway 认证方式（未设置时视为 token，与 Manager 生成 token 时的默认值一致）
+    pub fn gateway_auth_mode(&self) -> GatewayAuthMode {
+        match self.gateway_auth().and_then(|a| a.mode.as_deref()) {
+            Some("password") => GatewayAuthMode::Password,
+            Some("none") => GatewayAuthMode::None,
+            _ => GatewayAuthMode::Token,
+        }
+    }
+
+    /// Gateway 认证密码（未设置或为空时返回 None）
+    pub fn gateway_password(&self) -> Option<&str> {
+        self.gateway_auth()
+            .and_then(|a| a.password.as_deref())
+            .filter(|p| !p.is_empty())
+    }
+
+    /// Gateway 是否启用了 HTTPS
+    pub fn gateway_tls_enabled(&self) -> bool {
+        self.gateway
+            .as_ref()
+            .and_then(|g| g.tls.as_ref())
+            .is_some_and(|t| t.enabled == Some(true))
+    }
+
+    /// 允许连接 Gateway 的网页来源
+    pub fn gateway_allowed_origins(&self) -> &[String] {
+        self.gateway
+            .as_ref()
+            .and_then(|g| g.control_ui.as_ref())
+            .and_then(|ui| ui.allowed_origins.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// 更新 meta.lastTouchedAt
+    pub fn touch(&mut self) {
+        self.meta
+            .get_or_insert_with(Default::default)
+            .last_touched_at = Some(Utc::now().to_rfc3339());
+    }
+}
+
+impl ModelCostConfig {
+    /// 补齐未填写的费用（按 0 计），Manager 新写入的模型总是带完整的 cost
+    pub fn filled(self) -> Self {
+        Self {
+            input: Some(self.input.unwrap_or_default()),
+            output: Some(self.output.unwrap_or_default()),
+            cache_read: Some(self.cache_read.unwrap_or_default()),
+            cache_write: Some(self.cache_write.unwrap_or_default()),
+            extra: self.extra,
+        }
+    }
+}
+
+impl ProviderConfig {
+    /// 模型列表（未配置时为空）
+    pub fn models(&self) -> &[ModelConfig] {
+        self.models.as_deref().unwrap_or_default()
+    }
 }
 
 // ============ 前端展示用数据结构 ============
@@ -295,7 +722,42 @@ pub struct ChannelConfig {
     /// 是否启用
     pub enabled: bool,
     /// 配置详情
-    pub config: HashMap<String, serde_json::Value>,
+    pub config: HashMap<String, Value>,
+}
+
+// ============ 配置修改预览 ============
+
+/// 一次 openclaw.json 修改（与对应命令的参数相同，用于预览 / 应用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConfigMutation {
+    /// 整体替换配置（save_config）
+    SaveConfig { config: Value },
+    /// 添加或更新 Provider（save_provider）
+    SaveProvider {
+        provider_name: String,
+        base_url: String,
+        api_key: Option<String>,
+        api_type: String,
+        models: Vec<ModelConfig>,
+    },
+    /// 删除 Provider（delete_provider）
+    DeleteProvider { provider_name: String },
+    /// 设置主模型（set_primary_model）
+    SetPrimaryModel { model_id: String },
+    /// 保存渠道配置（save_channel_config）
+    SaveChannelConfig { channel: ChannelConfig },
+    /// 清空渠道配置（clear_channel_config）
+    ClearChannelConfig { channel_id: String },
+}
+
+/// 配置修改预览结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigPreview {
+    /// 预览时 openclaw.json 内容的哈希，应用修改时用于确认文件未被改动
+    pub base_hash: String,
+    /// 将要发生的变化（密钥已脱敏）
+    pub changes: Vec<JsonChange>,
 }
 
 /// 环境变量配置
@@ -304,3 +766,99 @@ pub struct EnvConfig {
     pub key: String,
     pub value: String,
 }
+
+/// env 文件中的一个变量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvEntry {
+    pub key: String,
+    /// 值（密钥已脱敏）
+    pub value: String,
+    /// 是否为密钥
+    pub secret: bool,
+}
+
+/// Gateway 环境变量的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvSource {
+    /// 继承自 Manager 进程
+    Process,
+    /// ~/.openclaw/env
+    EnvFile,
+    /// Manager 启动 Gateway 时设置（如扩展后的 PATH）
+    Manager,
+}
+
+/// Gateway 实际收到的一个环境变量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectiveEnvVar {
+    pub key: String,
+    /// 值（密钥已脱敏）
+    pub value: String,
+    pub secret: bool,
+    pub source: EnvSource,
+    /// 被覆盖的同名变量来源（如 env 文件覆盖了进程环境变量）
+    pub overrides: Option<EnvSource>,
+    /// 运行中的 Gateway 使用的值与当前配置不同（systemd 单元文件中的快照已过期），重新安装服务并重启后生效
+    pub pending_restart: bool,
+}
+
+/// Gateway 启动时实际收到的环境变量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectiveEnv {
+    /// 由 systemd 用户服务启动（不继承 Manager 进程的环境变量）
+    pub via_systemd: bool,
+    pub vars: Vec<EffectiveEnvVar>,
+}
+
+/// 更换 Gateway Token 的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayTokenRotation {
+    /// 新 token
+    pub token: String,
+    /// 同步更新的其他位置（env 文件、systemd 单元文件）
+    pub updated: Vec<String>,
+    /// Gateway 是否已重启以使用新 token
+    pub restarted: bool,
+    /// 重启失败的原因（token 已更换，需要手动重启 Gateway）
+    pub restart_error: Option<String>,
+}
+
+/// Gateway 认证方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GatewayAuthMode {
+    Token,
+    Password,
+    /// 不认证（仅适合只监听本机回环地址）
+    None,
+}
+
+impl GatewayAuthMode {
+    /// 写入 gateway.auth.mode 的值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GatewayAuthMode::Token => "token",
+            GatewayAuthMode::Password => "password",
+            GatewayAuthMode::None => "none",
+        }
+    }
+}
+
+/// Gateway 认证与网络暴露设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayAccessSettings {
+    pub auth_mode: GatewayAuthMode,
+    /// 是否已配置 token
+    pub token_set: bool,
+    /// 是否已配置密码
+    pub password_set: bool,
+    /// 实际生效的绑定模式
+    pub bind: String,
+    /// 允许连接 Gateway 的网页来源
+    pub allowed_origins: Vec<String>,
+    /// 同一局域网内其他设备（如手机）访问控制台的地址（bind 为 loopback 时为空）
+    pub lan_urls: Vec<String>,
+    /// 当前设置存在的安全风险
+    pub risks: Vec<String>,
+}
@@ -0,0 +1,90 @@
+use crate::models::ServiceState;
+use serde::{Deserialize, Serialize};
+
+/// 服务事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceAction {
+    /// 启动
+    Start,
+    /// 停止
+    Stop,
+    /// 重启
+    Restart,
+    /// 意外退出
+    Crash,
+    /// 守护线程自动重启
+    AutoRestart,
+    /// 检测到的服务状态变化（用于计算可用率）
+    StateChanged,
+}
+
+/// 事件发起方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventTrigger {
+    /// 用户在界面上操作
+    User,
+    /// 定时计划
+    Scheduler,
+    /// 守护 / 状态检测
+    Watchdog,
+    /// 配置变化后自动重启
+    ConfigWatch,
+}
+
+/// 一条服务事件（service-events.jsonl 中的一行）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceEventRecord {
+    /// 时间（RFC 3339）
+    pub timestamp: String,
+    /// 事件类型
+    pub action: ServiceAction,
+    /// 发起方
+    pub trigger: EventTrigger,
+    /// 进程 PID
+    #[serde(default)]
+    pub pid: Option<u32>,
+    /// 是否成功
+    pub success: bool,
+    /// 事件后的服务状态（state_changed 事件必有）
+    #[serde(default)]
+    pub state: Option<ServiceState>,
+    /// 说明
+    #[serde(default)]
+    pub message: String,
+}
+
+/// 时间线上的一段（同一状态持续的时间）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UptimeSegment {
+    /// 状态
+    pub state: ServiceState,
+    /// 开始时间
+    pub start: String,
+    /// 结束时间
+    pub end: String,
+    /// 持续秒数
+    pub duration_secs: u64,
+}
+
+/// 一段时间内的可用率统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UptimeReport {
+    /// 统计开始时间
+    pub since: String,
+    /// 统计结束时间
+    pub until: String,
+    /// 有状态记录覆盖的秒数（首条记录之前的时间不计入）
+    pub observed_secs: u64,
+    /// 运行中（含降级）的秒数
+    pub up_secs: u64,
+    /// 可用率（up_secs / observed_secs，百分比；没有记录时为空）
+    pub uptime_percent: Option<f64>,
+    /// 意外退出次数
+    pub crashes: u32,
+    /// 启动 / 重启 / 自动重启次数
+    pub restarts: u32,
+    /// 时间线
+    pub segments: Vec<UptimeSegment>,
+}
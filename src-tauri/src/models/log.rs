@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// Gateway 日志条目（由日志文件中的一行解析而来）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayLogEntry {
+    /// 时间（RFC 3339）
+    pub timestamp: Option<String>,
+    /// 日志级别：debug / info / warn / error
+    pub level: String,
+    /// 子系统（如 gateway、telegram、plugins）
+    pub subsystem: Option<String>,
+    /// 输出流：stdout / stderr
+    pub stream: Option<String>,
+    /// 消息正文
+    pub message: String,
+    /// 原始行
+    pub raw: String,
+}
+
+/// 实时日志推送批次（gateway-log 事件的负载）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayLogBatch {
+    /// 本批日志
+    pub entries: Vec<GatewayLogEntry>,
+    /// 自上一批以来因积压过多而丢弃的条数
+    pub dropped: u64,
+    /// 仍在队列中等待发送的条数
+    pub pending: usize,
+}
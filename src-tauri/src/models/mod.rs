@@ -1,5 +1,21 @@
+pub mod backup;
+pub mod bundle;
 pub mod config;
+pub mod history;
+pub mod log;
+pub mod schedule;
+pub mod settings;
 pub mod status;
+pub mod tls;
+pub mod validation;
 
+pub use backup::*;
+pub use bundle::*;
 pub use config::*;
+pub use history::*;
+pub use log::*;
+pub use schedule::*;
+pub use settings::*;
 pub use status::*;
+pub use tls::*;
+pub use validation::*;
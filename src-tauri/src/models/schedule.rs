@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// 定时计划触发的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleAction {
+    Start,
+    Stop,
+}
+
+/// 即将发生的定时启停
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleTransition {
+    /// 触发时间
+    pub at: String,
+    /// 动作
+    pub action: ScheduleAction,
+    /// 触发该动作的规则名称
+    pub rule: String,
+}
+
+/// 定时计划最近一次执行的动作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleActionRecord {
+    /// 执行时间
+    pub at: String,
+    /// 动作
+    pub action: ScheduleAction,
+    /// 是否成功
+    pub success: bool,
+    /// 结果说明
+    pub message: String,
+}
+
+/// 定时计划状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleStatus {
+    /// 是否启用
+    pub enabled: bool,
+    /// 当前是否处于运行时间窗口内
+    pub in_window: bool,
+    /// 当前生效的规则名称
+    pub active_rule: Option<String>,
+    /// 最近一次执行的动作
+    pub last_action: Option<ScheduleActionRecord>,
+    /// 即将发生的启停
+    pub upcoming: Vec<ScheduleTransition>,
+}
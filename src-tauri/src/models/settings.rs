@@ -0,0 +1,292 @@
+use serde::{Deserialize, Serialize};
+
+/// OpenClaw Manager 自身配置 - 对应 ~/.openclaw/manager.json
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ManagerSettings {
+    /// Gateway 监听配置
+    #[serde(default)]
+    pub gateway: GatewayListenSettings,
+    /// Gateway 守护（崩溃自动重启）配置
+    #[serde(default)]
+    pub supervisor: SupervisorSettings,
+    /// Gateway 日志文件配置
+    #[serde(default)]
+    pub logs: LogSettings,
+    /// Gateway 停止流程配置
+    #[serde(default)]
+    pub stop: StopSettings,
+    /// Gateway 定时启停配置
+    #[serde(default)]
+    pub schedule: ScheduleSettings,
+    /// openclaw.json / env 变化检测配置
+    #[serde(rename = "configWatch", default)]
+    pub config_watch: ConfigWatchSettings,
+    /// openclaw.json 备份配置
+    #[serde(default)]
+    pub backup: BackupSettings,
+    /// 未识别的字段（保留，避免新旧版本互相覆盖）
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Gateway 监听配置（为空时回退到 openclaw.json 的 gateway.port / gateway.bind）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayListenSettings {
+    /// 监听端口
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// 绑定模式：loopback / lan / tailnet / auto
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bind: Option<String>,
+    /// 端口被其他程序占用时自动选择空闲端口
+    #[serde(default = "default_true")]
+    pub auto_port: bool,
+    /// 当前 Gateway 实际使用的端口（自动选择端口时记录，停止后清除）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_port: Option<u16>,
+}
+
+impl Default for GatewayListenSettings {
+    fn default() -> Self {
+        Self {
+            port: None,
+            bind: None,
+            auto_port: true,
+            active_port: None,
+        }
+    }
+}
+
+/// Gateway 守护配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupervisorSettings {
+    /// Gateway 意外退出后是否自动重启
+    #[serde(default = "default_true")]
+    pub auto_restart: bool,
+    /// 崩溃循环判定：时间窗口内最多允许的退出次数
+    #[serde(default = "default_crash_loop_max_exits")]
+    pub crash_loop_max_exits: u32,
+    /// 崩溃循环判定的时间窗口（分钟）
+    #[serde(default = "default_crash_loop_window_minutes")]
+    pub crash_loop_window_minutes: u32,
+    /// 首次重启前的等待时间（秒），之后每次翻倍
+    #[serde(default = "default_backoff_initial_secs")]
+    pub backoff_initial_secs: u64,
+    /// 重启等待时间上限（秒）
+    #[serde(default = "default_backoff_max_secs")]
+    pub backoff_max_secs: u64,
+}
+
+impl Default for SupervisorSettings {
+    fn default() -> Self {
+        Self {
+            auto_restart: true,
+            crash_loop_max_exits: default_crash_loop_max_exits(),
+            crash_loop_window_minutes: default_crash_loop_window_minutes(),
+            backoff_initial_secs: default_backoff_initial_secs(),
+            backoff_max_secs: default_backoff_max_secs(),
+        }
+    }
+}
+
+/// Gateway 日志文件配置（按大小轮转）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSettings {
+    /// 单个日志文件大小上限（MB），超过后轮转
+    #[serde(default = "default_log_max_file_size_mb")]
+    pub max_file_size_mb: u64,
+    /// 保留的历史日志文件个数（gateway.log.1 ~ gateway.log.N）
+    #[serde(default = "default_log_max_files")]
+    pub max_files: u32,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            max_file_size_mb: default_log_max_file_size_mb(),
+            max_files: default_log_max_files(),
+        }
+    }
+}
+
+/// Gateway 停止流程配置：每个阶段执行后等待进程退出的时间（秒），超时再升级到下一阶段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopSettings {
+    /// openclaw gateway stop 后的等待时间（systemd 管理时为 systemctl stop 后的等待时间）
+    #[serde(default = "default_stop_graceful_secs")]
+    pub graceful_secs: u64,
+    /// openclaw gateway stop --force 后的等待时间
+    #[serde(default = "default_stop_force_secs")]
+    pub force_secs: u64,
+    /// SIGTERM / taskkill 后的等待时间
+    #[serde(default = "default_stop_terminate_secs")]
+    pub terminate_secs: u64,
+    /// SIGKILL / taskkill /F 后的等待时间
+    #[serde(default = "default_stop_kill_secs")]
+    pub kill_secs: u64,
+    /// 检查进程是否退出的间隔（毫秒）
+    #[serde(default = "default_stop_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+}
+
+impl Default for StopSettings {
+    fn default() -> Self {
+        Self {
+            graceful_secs: default_stop_graceful_secs(),
+            force_secs: default_stop_force_secs(),
+            terminate_secs: default_stop_terminate_secs(),
+            kill_secs: default_stop_kill_secs(),
+            poll_interval_ms: default_stop_poll_interval_ms(),
+        }
+    }
+}
+
+/// Gateway 定时启停配置：任一规则的时间窗口内运行，窗口外停止
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleSettings {
+    /// 是否启用定时启停
+    #[serde(default)]
+    pub enabled: bool,
+    /// 运行时间窗口
+    #[serde(default)]
+    pub rules: Vec<ScheduleRule>,
+}
+
+/// 运行时间窗口，例如工作日 08:00-20:00
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleRule {
+    /// 规则名称
+    #[serde(default)]
+    pub name: String,
+    /// 是否启用
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 生效的星期（1 = 周一 ... 7 = 周日），为空表示每天
+    #[serde(default)]
+    pub days: Vec<u8>,
+    /// 开始时间 HH:MM
+    pub start: String,
+    /// 结束时间 HH:MM，早于开始时间表示跨天（在次日结束）
+    pub end: String,
+}
+
+/// 配置变化检测：openclaw.json / env 修改后是否自动重启 Gateway
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigWatchSettings {
+    /// 检测到需要重启的变化后自动重启 Gateway
+    #[serde(default)]
+    pub auto_restart: bool,
+    /// 最后一次修改后等待多久（秒）再自动重启，避免连续保存时反复重启
+    #[serde(default = "default_config_watch_debounce_secs")]
+    pub debounce_secs: u64,
+}
+
+impl Default for ConfigWatchSettings {
+    fn default() -> Self {
+        Self {
+            auto_restart: false,
+            debounce_secs: default_config_watch_debounce_secs(),
+        }
+    }
+}
+
+/// openclaw.json 备份：每次修改前自动备份，超出数量时删除最旧的
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSettings {
+    /// 最多保留的备份数量
+    #[serde(default = "default_backup_max_backups")]
+    pub max_backups: usize,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            max_backups: default_backup_max_backups(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_crash_loop_max_exits() -> u32 {
+    5
+}
+
+fn default_crash_loop_window_minutes() -> u32 {
+    10
+}
+
+fn default_backoff_initial_secs() -> u64 {
+    2
+}
+
+fn default_backoff_max_secs() -> u64 {
+    120
+}
+
+fn default_log_max_file_size_mb() -> u64 {
+    10
+}
+
+fn default_log_max_files() -> u32 {
+    5
+}
+
+fn default_stop_graceful_secs() -> u64 {
+    10
+}
+
+fn default_stop_force_secs() -> u64 {
+    5
+}
+
+fn default_stop_terminate_secs() -> u64 {
+    10
+}
+
+fn default_stop_kill_secs() -> u64 {
+    5
+}
+
+fn default_stop_poll_interval_ms() -> u64 {
+    250
+}
+
+fn default_config_watch_debounce_secs() -> u64 {
+    5
+}
+
+fn default_backup_max_backups() -> usize {
+    20
+}
+
+/// Gateway 默认端口（与 openclaw CLI 默认值一致）
+pub const DEFAULT_GATEWAY_PORT: u16 = 18789;
+
+/// Gateway 默认绑定模式
+pub const DEFAULT_GATEWAY_BIND: &str = "loopback";
+
+/// Gateway 监听地址（合并 Manager 配置、openclaw.json 与默认值后的结果）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayListenInfo {
+    /// 配置的端口
+    pub port: u16,
+    /// 绑定模式
+    pub bind: String,
+    /// 配置来源：manager / openclaw / default
+    pub source: String,
+    /// 是否允许自动选择空闲端口
+    pub auto_port: bool,
+    /// 当前实际使用的端口（与 port 不同说明发生了自动切换）
+    pub active_port: Option<u16>,
+}
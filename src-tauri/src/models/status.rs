@@ -1,35 +1,319 @@
+use crate::models::{ChangeKind, DEFAULT_GATEWAY_BIND, DEFAULT_GATEWAY_PORT};
 use serde::{Deserialize, Serialize};
 
+/// Gateway 服务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServiceState {
+    /// 正在启动，端口尚未监听
+    Starting,
+    /// 正常运行
+    Running,
+    /// 进程存在但未正常提供服务
+    Degraded,
+    /// 正在停止
+    Stopping,
+    /// 已停止
+    Stopped,
+    /// 意外退出（可能正在等待自动重启）
+    Crashed,
+    /// 端口被其他程序占用
+    Occupied,
+}
+
 /// 服务运行状态
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceStatus {
+    /// 服务状态
+    pub state: ServiceState,
     /// 是否正在运行
     pub running: bool,
     /// 进程 ID
     pub pid: Option<u32>,
     /// 监听端口
     pub port: u16,
+    /// 绑定模式
+    pub bind: String,
     /// 运行时长（秒）
     pub uptime_seconds: Option<u64>,
     /// 内存使用（MB）
     pub memory_mb: Option<f64>,
     /// CPU 使用率
     pub cpu_percent: Option<f64>,
+    /// 线程数
+    pub thread_count: Option<u32>,
+    /// 进程命令行
+    pub command_line: Option<String>,
+    /// 进程管理方：systemd / manager / external（未运行时为空）
+    pub managed_by: Option<String>,
+    /// 端口占用者（端口空闲时为空，占用者不是 Gateway 时 running 为 false）
+    pub port_owner: Option<PortOwner>,
+    /// HTTP 健康检查结果（仅运行中时探测）
+    pub health: Option<GatewayHealth>,
+}
+
+/// Gateway 健康状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthState {
+    /// 端口无法连接
+    Unreachable,
+    /// 端口已打开，但 HTTP 无响应或报告尚未就绪（如正在加载插件）
+    NotReady,
+    /// 已就绪
+    Ready,
+    /// 已就绪，但部分渠道异常
+    Degraded,
+    /// HTTP 服务可访问，但探测请求被拒绝（401 / 403），无法读取就绪和渠道状态
+    AuthRequired,
+    /// 服务可访问，但无法判断是否就绪（已启用 HTTPS，或 HTTP 响应中没有健康检查信息）
+    Unknown,
+}
+
+/// Gateway HTTP 健康检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayHealth {
+    /// 健康状态
+    pub state: HealthState,
+    /// 响应延迟（毫秒）
+    pub latency_ms: Option<u64>,
+    /// HTTP 状态码
+    pub http_status: Option<u16>,
+    /// 实际响应的探测路径
+    pub endpoint: Option<String>,
+    /// 异常的渠道
+    pub degraded_channels: Vec<String>,
+    /// 说明
+    pub message: String,
+    /// 检查时间（RFC 3339）
+    pub checked_at: String,
+}
+
+/// 端口占用进程
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortOwner {
+    /// 进程 ID（权限不足时可能无法解析）
+    pub pid: Option<u32>,
+    /// 进程名
+    pub name: Option<String>,
+    /// 可执行文件路径
+    pub exe: Option<String>,
+    /// 完整命令行
+    pub command_line: Option<String>,
+    /// 是否为 openclaw gateway（无法确认身份时为 false）
+    pub is_gateway: bool,
+    /// 是否已通过进程信息确认（无法获取 PID 时为 false）
+    pub verified: bool,
+}
+
+impl PortOwner {
+    /// 用于提示的进程描述，如 "node (PID 1234)"
+    pub fn describe(&self) -> String {
+        let name = self.name.as_deref().unwrap_or("未知进程");
+        match self.pid {
+            Some(pid) => format!("{} (PID {})", name, pid),
+            None => name.to_string(),
+        }
+    }
 }
 
 impl Default for ServiceStatus {
     fn default() -> Self {
         Self {
+            state: ServiceState::Stopped,
             running: false,
             pid: None,
-            port: 18789,
+            port: DEFAULT_GATEWAY_PORT,
+            bind: DEFAULT_GATEWAY_BIND.to_string(),
             uptime_seconds: None,
             memory_mb: None,
             cpu_percent: None,
+            thread_count: None,
+            command_line: None,
+            managed_by: None,
+            port_owner: None,
+            health: None,
         }
     }
 }
 
+/// 服务状态变化事件（service-state-changed 事件的负载）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceStateEvent {
+    /// 新状态
+    pub state: ServiceState,
+    /// 之前的状态（应用启动后的首次检测为空）
+    pub previous: Option<ServiceState>,
+    /// 状态变化原因
+    pub reason: String,
+    /// 变化时间（RFC 3339）
+    pub changed_at: String,
+    /// 完整的服务状态
+    pub status: ServiceStatus,
+}
+
+/// Gateway 停止流程的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopPhase {
+    /// 本来就没有运行
+    AlreadyStopped,
+    /// systemctl --user stop
+    Systemd,
+    /// openclaw gateway stop
+    Graceful,
+    /// openclaw gateway stop --force
+    Force,
+    /// SIGTERM / taskkill
+    Terminate,
+    /// SIGKILL / taskkill /F
+    Kill,
+}
+
+/// 停止流程中单个阶段的执行结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StopStep {
+    /// 阶段
+    pub phase: StopPhase,
+    /// 停止命令本身是否执行成功
+    pub action_ok: bool,
+    /// 命令输出或错误
+    pub output: String,
+    /// 等待期内进程是否已退出
+    pub stopped: bool,
+    /// 本阶段耗时（毫秒）
+    pub elapsed_ms: u64,
+}
+
+/// 停止流程结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StopResult {
+    /// 是否已停止
+    pub stopped: bool,
+    /// 使进程退出的阶段（未停止时为最后执行的阶段）
+    pub phase: Option<StopPhase>,
+    /// 停止前的进程 PID
+    pub pid: Option<u32>,
+    /// 各阶段执行记录
+    pub steps: Vec<StopStep>,
+    /// 总耗时（毫秒）
+    pub elapsed_ms: u64,
+    /// 说明
+    pub message: String,
+}
+
+/// 停止进度事件（service-stop-progress 事件的负载）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StopProgressEvent {
+    /// 当前阶段
+    pub phase: StopPhase,
+    /// 第几个阶段（从 1 开始）
+    pub step: u32,
+    /// 说明
+    pub message: String,
+    /// 进程 PID
+    pub pid: Option<u32>,
+    /// 自停止开始的耗时（毫秒）
+    pub elapsed_ms: u64,
+}
+
+/// 配置文件中的一处变化
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    /// 文件：openclaw.json / env
+    pub file: String,
+    /// 变化的配置路径（env 为变量名）
+    pub path: String,
+    /// added / removed / modified
+    pub kind: ChangeKind,
+    /// 是否需要重启 Gateway 才能生效
+    pub restart_required: bool,
+}
+
+/// 配置变化检测状态（config-restart-required 事件的负载）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigWatchStatus {
+    /// 运行中的 Gateway 是否需要重启才能应用磁盘上的配置
+    pub restart_required: bool,
+    /// 相对 Gateway 启动时配置的变化（不含具体值，避免泄露密钥）
+    pub changes: Vec<ConfigChange>,
+    /// 首次检测到需要重启的时间
+    pub detected_at: Option<String>,
+    /// 是否会自动重启
+    pub auto_restart: bool,
+    /// 自动重启前等待配置稳定的时间（秒）
+    pub debounce_secs: u64,
+}
+
+/// Gateway 进程退出记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayExitRecord {
+    /// 退出时间（RFC 3339）
+    pub exited_at: String,
+    /// 进程 ID
+    pub pid: u32,
+    /// 退出码
+    pub exit_code: Option<i32>,
+    /// 终止信号（仅 Unix）
+    pub signal: Option<i32>,
+    /// 退出原因描述
+    pub reason: String,
+    /// 本次运行时长（秒）
+    pub uptime_seconds: u64,
+    /// 是否由用户主动停止
+    pub expected: bool,
+    /// 是否已安排自动重启
+    pub restart_scheduled: bool,
+}
+
+/// Gateway 守护状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupervisorStatus {
+    /// 当前是否有受守护的 Gateway 进程
+    pub supervising: bool,
+    /// 受守护进程的 PID
+    pub pid: Option<u32>,
+    /// 是否开启自动重启
+    pub auto_restart: bool,
+    /// 自本次手动启动以来的自动重启次数
+    pub restart_count: u32,
+    /// 是否检测到崩溃循环（已停止自动重启）
+    pub crash_loop: bool,
+    /// 下一次自动重启时间（RFC 3339）
+    pub next_restart_at: Option<String>,
+    /// 最近一次退出原因
+    pub last_exit: Option<GatewayExitRecord>,
+    /// 退出历史（新的在前）
+    pub history: Vec<GatewayExitRecord>,
+}
+
+/// systemd 用户服务状态（仅 Linux）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SystemdServiceStatus {
+    /// 当前系统是否支持 systemd --user
+    pub supported: bool,
+    /// 单元名
+    pub unit_name: String,
+    /// 单元文件路径
+    pub unit_path: String,
+    /// 单元文件是否已安装
+    pub installed: bool,
+    /// 是否已设置自启
+    pub enabled: bool,
+    /// 是否正在运行
+    pub active: bool,
+    /// systemd ActiveState（active / inactive / failed 等）
+    pub active_state: Option<String>,
+    /// systemd SubState（running / dead / auto-restart 等）
+    pub sub_state: Option<String>,
+    /// 服务主进程 PID
+    pub main_pid: Option<u32>,
+    /// 是否开启 linger（未开启时退出登录后服务会停止）
+    pub linger: Option<bool>,
+    /// 单元文件与当前配置不一致（端口、绑定模式或环境变量已修改），需要重新安装服务
+    pub stale: bool,
+}
+
 /// 系统信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemInfo {
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// Gateway HTTPS 状态（本地 CA 与服务器证书）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayTlsStatus {
+    /// openclaw.json 中是否已启用 gateway.tls
+    pub enabled: bool,
+    /// 本地 CA 证书路径（需要安装到手机等客户端设备上）
+    pub ca_path: Option<String>,
+    /// CA 证书的 SHA-256 指纹，在客户端安装时用于核对
+    pub ca_fingerprint: Option<String>,
+    /// CA 证书过期时间（RFC 3339）
+    pub ca_expires_at: Option<String>,
+    /// 服务器证书路径
+    pub cert_path: Option<String>,
+    /// 服务器证书过期时间（RFC 3339）
+    pub cert_expires_at: Option<String>,
+    /// 服务器证书包含的主机名和 IP
+    pub cert_names: Vec<String>,
+    /// 本机当前的主机名和 IP
+    pub current_names: Vec<String>,
+    /// 服务器证书即将过期，或不包含本机当前的主机名 / IP，需要重新签发
+    pub needs_renewal: bool,
+    /// 通过 HTTPS 访问控制台的地址
+    pub urls: Vec<String>,
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 问题严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    /// 阻止启动
+    Error,
+    /// 仅提示
+    Warning,
+}
+
+/// 配置校验发现的问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigIssue {
+    /// 严重程度
+    pub severity: IssueSeverity,
+    /// 问题代码，如 primary_model_missing / channel_credentials_missing
+    pub code: String,
+    /// 出问题的配置路径，如 agents.defaults.model.primary
+    pub path: String,
+    /// 问题说明
+    pub message: String,
+    /// 修复建议
+    pub hint: Option<String>,
+}
+
+/// 启动前配置校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightReport {
+    /// 是否没有阻止启动的错误
+    pub passed: bool,
+    /// 所有问题（错误在前）
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl PreflightReport {
+    /// 阻止启动的错误
+    pub fn errors(&self) -> impl Iterator<Item = &ConfigIssue> {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == IssueSeverity::Error)
+    }
+}
+
+/// openclaw.json 结构校验发现的问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaIssue {
+    /// 严重程度
+    pub severity: IssueSeverity,
+    /// 出问题的位置（JSON Pointer），如 /models/providers/openai/models/0/api
+    pub pointer: String,
+    /// 所在行（从 1 开始）
+    pub line: Option<u32>,
+    /// 所在列（从 1 开始）
+    pub column: Option<u32>,
+    /// 问题说明
+    pub message: String,
+    /// 修复建议
+    pub fix: Option<String>,
+}
+
+/// openclaw.json 结构校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaReport {
+    /// 是否没有错误（允许有警告）
+    pub valid: bool,
+    /// 所有问题（按出现位置排序）
+    pub issues: Vec<SchemaIssue>,
+}
+
+/// 启动服务失败（配置校验未通过时带上阻止启动的问题列表）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartError {
+    /// 错误说明
+    pub message: String,
+    /// 阻止启动的配置问题
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl From<String> for StartError {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            issues: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for StartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        for issue in &self.issues {
+            write!(f, "\n- {}: {}", issue.path, issue.message)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use crate::models::ConfigBackup;
+use crate::utils::{file, platform, settings};
+use chrono::{Local, NaiveDateTime, TimeZone};
+use log::{info, warn};
+use std::fs;
+use std::path::PathBuf;
+
+/// 备份文件名：openclaw-YYYYMMDD-HHMMSS-mmm.json（按文件名排序即按时间排序）
+const BACKUP_PREFIX: &str = "openclaw-";
+const BACKUP_SUFFIX: &str = ".json";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S-%3f";
+
+/// 导入配置包前的完整快照：setup-YYYYMMDD-HHMMSS.zip（包含密钥，与备份共用保留数量）
+const SNAPSHOT_PREFIX: &str = "setup-";
+const SNAPSHOT_SUFFIX: &str = ".zip";
+
+fn backup_path(id: &str) -> Result<PathBuf, String> {
+    let valid = id.starts_with(BACKUP_PREFIX)
+        && id.ends_with(BACKUP_SUFFIX)
+        && !id.contains(['/', '\\'])
+        && !id.contains("..");
+    if !valid {
+        return Err(format!("无效的备份 ID: {}", id));
+    }
+    Ok(platform::get_config_backup_dir().join(id))
+}
+
+fn created_at(id: &str) -> Option<String> {
+    let stamp = id
+        .strip_prefix(BACKUP_PREFIX)?
+        .strip_suffix(BACKUP_SUFFIX)?;
+    let naive = NaiveDateTime::parse_from_str(stamp, TIMESTAMP_FORMAT).ok()?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|t| t.to_rfc3339())
+}
+
+/// 列出全部备份（最新的在前）
+pub fn list() -> Vec<ConfigBackup> {
+    let Ok(entries) = fs::read_dir(platform::get_config_backup_dir()) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<ConfigBackup> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry.file_name().to_string_lossy().to_string();
+            let created_at = created_at(&id)?;
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            Some(ConfigBackup {
+                id,
+                created_at,
+                size,
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.id.cmp(&a.id));
+    backups
+}
+
+/// 读取备份内容
+pub fn read(id: &str) -> Result<String, String> {
+    let path = backup_path(id)?;
+    fs::read_to_string(&path).map_err(|e| format!("读取备份 {} 失败: {}", id, e))
+}
+
+/// 备份当前的 openclaw.json（文件不存在、或与最新备份相同时不重复备份）
+pub fn create() -> Result<Option<ConfigBackup>, String> {
+    let config_path = platform::get_config_file_path();
+    if !file::file_exists(&config_path) {
+        return Ok(None);
+    }
+    let content = file::read_file(&config_path).map_err(|e| format!("读取配置文件失败: {}", e))?;
+
+    if let Some(latest) = list().into_iter().next() {
+        if read(&latest.id).is_ok_and(|c| c == content) {
+            return Ok(Some(latest));
+        }
+    }
+
+    let id = format!(
+        "{}{}{}",
+        BACKUP_PREFIX,
+        Local::now().format(TIMESTAMP_FORMAT),
+        BACKUP_SUFFIX
+    );
+    let path = backup_path(&id)?;
+    file::write_file(&path.to_string_lossy(), &content)
+        .map_err(|e| format!("写入备份失败: {}", e))?;
+    info!("[配置备份] 已备份 openclaw.json: {}", id);
+
+    prune(settings::load().backup.max_backups);
+    Ok(Some(ConfigBackup {
+        created_at: created_at(&id).unwrap_or_default(),
+        size: content.len() as u64,
+        id,
+    }))
+}
+
+/// 新的导入前快照路径
+pub fn snapshot_path() -> PathBuf {
+    platform::get_config_backup_dir().join(format!(
+        "{}{}{}",
+        SNAPSHOT_PREFIX,
+        Local::now().format("%Y%m%d-%H%M%S"),
+        SNAPSHOT_SUFFIX
+    ))
+}
+
+/// 删除超出保留数量的旧快照（按文件名排序即按时间排序）
+fn prune_snapshots(max_snapshots: usize) {
+    let dir = platform::get_config_backup_dir();
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return;
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with(SNAPSHOT_PREFIX) && name.ends_with(SNAPSHOT_SUFFIX))
+        .collect();
+    names.sort_by(|a, b| b.cmp(a));
+    for name in names.into_iter().skip(max_snapshots.max(1)) {
+        match fs::remove_file(dir.join(&name)) {
+            Ok(_) => info!("[配置备份] 删除旧快照: {}", name),
+            Err(e) => warn!("[配置备份] 删除旧快照 {} 失败: {}", name, e),
+        }
+    }
+}
+
+/// 删除超出保留数量的旧备份和导入前快照
+pub fn prune(max_backups: usize) {
+    for backup in list().into_iter().skip(max_backups.max(1)) {
+        let Ok(path) = backup_path(&backup.id) else {
+            continue;
+        };
+        match fs::remove_file(&path) {
+            Ok(_) => info!("[配置备份] 删除旧备份: {}", backup.id),
+            Err(e) => warn!("[配置备份] 删除旧备份 {} 失败: {}", backup.id, e),
+        }
+    }
+    prune_snapshots(max_backups);
+}
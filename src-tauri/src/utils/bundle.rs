@@ -0,0 +1,678 @@
+use crate::models::{
+    BundleEncryption, BundleFileChange, BundleImportPreview, BundleImportResult, BundleManifest,
+    BundlePlugin, ChangeKind, OpenClawConfig, SecretMode,
+};
+use crate::utils::dotenv::EnvFile;
+use crate::utils::{
+    backup, config_repo, file, json_diff, platform, preflight, schema, secrets, settings,
+};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::Local;
+use log::info;
+use serde_json::{Map, Value};
+use sha2::Sha256;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use zip::result::ZipError;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// 配置包格式版本，导入时拒绝更高的版本
+const FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const CONFIG_FILE: &str = "openclaw.json";
+const ENV_FILE: &str = "env";
+const SECRETS_FILE: &str = "secrets.enc";
+const WORKSPACE_PREFIX: &str = "workspace/";
+
+/// 密钥位置前缀：openclaw.json 中的 JSON Pointer / env 中的变量名
+const CONFIG_SECRET_PREFIX: &str = "openclaw.json#";
+const ENV_SECRET_PREFIX: &str = "env#";
+
+const PBKDF2_ITERATIONS: u32 = 600_000;
+
+/// 包内单个文件的大小上限，防止解压炸弹
+const MAX_ENTRY_SIZE: u64 = 16 * 1024 * 1024;
+
+/// 没有记录安装来源时使用的插件 npm 包
+const KNOWN_PLUGIN_SPECS: &[(&str, &str)] = &[
+    ("feishu", "@m1heng-clawd/feishu"),
+    ("wecom", "@wecom/wecom-openclaw-plugin"),
+];
+
+/// 从配置包中读出的内容
+pub struct Bundle {
+    pub manifest: BundleManifest,
+    pub config: Value,
+    pub env: Option<String>,
+    /// 工作区 Markdown 文件：相对工作区目录的路径 → 内容
+    pub workspace: BTreeMap<String, String>,
+    /// 解密出的密钥（未加密或未提供密码时为空）
+    pub secrets: BTreeMap<String, String>,
+}
+
+/// 导入后的 openclaw.json 与 env 内容
+struct ImportPlan {
+    config: String,
+    env: Option<String>,
+    missing_secrets: Vec<String>,
+}
+
+// ============ 工作区 / 插件 ============
+
+/// 工作区目录：agents.defaults.workspace，未配置时为 ~/.openclaw/workspace
+fn workspace_dir(config: &OpenClawConfig) -> PathBuf {
+    let configured = config
+        .agent_defaults()
+        .and_then(|d| d.extra.get("workspace"))
+        .and_then(Value::as_str)
+        .filter(|s| !s.trim().is_empty());
+    match configured {
+        Some(dir) => match (dir.strip_prefix("~/"), dirs::home_dir()) {
+            (Some(rest), Some(home)) => home.join(rest),
+            _ => PathBuf::from(dir),
+        },
+        None => PathBuf::from(platform::get_config_dir()).join("workspace"),
+    }
+}
+
+/// 递归收集工作区中的 .md 文件（跳过隐藏目录、node_modules 和符号链接）
+fn collect_markdown(dir: &Path, base: &Path, files: &mut BTreeMap<String, String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if name.starts_with('.') || name == "node_modules" {
+            continue;
+        }
+        if file_type.is_dir() {
+            collect_markdown(&path, base, files);
+        } else if file_type.is_file()
+            && path
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("md"))
+        {
+            let (Ok(relative), Ok(content)) = (path.strip_prefix(base), fs::read_to_string(&path))
+            else {
+                continue;
+            };
+            let relative: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect();
+            files.insert(relative.join("/"), content);
+        }
+    }
+}
+
+/// 已安装的插件（plugins.installs 中的记录，以及 plugins.allow 中的非内置插件）
+fn installed_plugins(config: &OpenClawConfig) -> Vec<BundlePlugin> {
+    let ids: BTreeSet<&String> = config
+        .plugin_installs()
+        .keys()
+        .chain(
+            config
+                .plugins_allow()
+                .iter()
+                .filter(|id| !preflight::BUILTIN_PLUGINS.contains(&id.as_str())),
+        )
+        .collect();
+    ids.into_iter()
+        .map(|id| {
+            let install = config.plugin_installs().get(id);
+            let field = |key: &str| {
+                install
+                    .and_then(|i| i.get(key))
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            };
+            // 本地路径 / 压缩包安装的插件换到其他机器上无法按原路径安装
+            let npm_spec = match field("source").as_deref() {
+                None | Some("npm") => field("spec"),
+                Some(_) => None,
+            };
+            let known_spec = KNOWN_PLUGIN_SPECS
+                .iter()
+                .find(|(known, _)| known == id)
+                .map(|(_, spec)| spec.to_string());
+            BundlePlugin {
+                id: id.clone(),
+                spec: npm_spec.or(known_spec),
+                version: field("version"),
+            }
+        })
+        .collect()
+}
+
+/// 配置包中本机尚未安装的插件
+pub fn missing_plugins(manifest: &BundleManifest) -> Vec<BundlePlugin> {
+    let extensions_dir = PathBuf::from(platform::get_config_dir()).join("extensions");
+    let installs = config_repo::load()
+        .map(|c| c.plugin_installs().clone())
+        .unwrap_or_default();
+    manifest
+        .plugins
+        .iter()
+        .filter(|plugin| {
+            let install_path = installs
+                .get(&plugin.id)
+                .and_then(|i| i.get("installPath"))
+                .and_then(Value::as_str);
+            !extensions_dir.join(&plugin.id).is_dir()
+                && !install_path.is_some_and(|p| Path::new(p).is_dir())
+        })
+        .cloned()
+        .collect()
+}
+
+/// 安装插件用的 npm 规格（记录了版本时固定到该版本）
+/// 规格来自配置包，以 - 开头的会被 openclaw 当作命令行选项，直接拒绝
+pub fn install_spec(plugin: &BundlePlugin) -> Result<String, String> {
+    let spec = plugin
+        .spec
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "配置包中没有记录安装来源，请手动安装".to_string())?;
+    if spec.starts_with('-') {
+        return Err(format!("安装来源 \"{}\" 无效，已拒绝安装", spec));
+    }
+    // 作用域包的 @ 在开头，其余位置出现 @ 说明已带版本
+    let has_version = spec.rfind('@').is_some_and(|i| i > 0);
+    Ok(match &plugin.version {
+        Some(version) if !has_version => format!("{}@{}", spec, version),
+        _ => spec.to_string(),
+    })
+}
+
+// ============ 密钥 ============
+
+/// 移出 JSON 中非空的密钥字段，记录 JSON Pointer → 值
+fn take_json_secrets(value: &mut Value, pointer: &str, taken: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            let keys: Vec<String> = map.keys().cloned().collect();
+            for key in keys {
+                let child = format!("{}/{}", pointer, schema::pointer_segment(&key));
+                let is_secret = secrets::is_secret_key(&key)
+                    && map
+                        .get(&key)
+                        .and_then(Value::as_str)
+                        .is_some_and(|s| !s.is_empty());
+                if is_secret {
+                    if let Some(Value::String(secret)) = map.remove(&key) {
+                        taken.insert(format!("{}{}", CONFIG_SECRET_PREFIX, child), secret);
+                    }
+                } else if let Some(v) = map.get_mut(&key) {
+                    take_json_secrets(v, &child, taken);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                take_json_secrets(item, &format!("{}/{}", pointer, i), taken);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 把密钥放回 JSON Pointer 指向的位置（父对象不存在时返回 false）
+fn put_json_secret(value: &mut Value, pointer: &str, secret: String) -> bool {
+    let Some((parent, last)) = pointer.rsplit_once('/') else {
+        return false;
+    };
+    let key = last.replace("~1", "/").replace("~0", "~");
+    match value.pointer_mut(parent) {
+        Some(Value::Object(map)) => {
+            map.insert(key, Value::String(secret));
+            true
+        }
+        _ => false,
+    }
+}
+
+/// 移出 env 文件中的密钥变量，返回剩余内容
+fn take_env_secrets(content: &str, taken: &mut BTreeMap<String, String>) -> String {
+    let mut env = EnvFile::parse(content);
+    for (key, value) in env.vars() {
+        if secrets::is_secret_env_key(&key) && !value.is_empty() {
+            env.remove(&key);
+            taken.insert(format!("{}{}", ENV_SECRET_PREFIX, key), value);
+        }
+    }
+    env.to_content()
+}
+
+fn env_map(env: &EnvFile) -> Value {
+    let map: Map<String, Value> = env
+        .vars()
+        .into_iter()
+        .map(|(k, v)| (k, Value::String(v)))
+        .collect();
+    Value::Object(map)
+}
+
+fn load_current_env() -> EnvFile {
+    EnvFile::load(&platform::get_env_file_path()).unwrap_or_default()
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    key
+}
+
+fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<(BundleEncryption, Vec<u8>), String> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt, PBKDF2_ITERATIONS);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| "加密密钥失败".to_string())?;
+    let encryption = BundleEncryption {
+        iterations: PBKDF2_ITERATIONS,
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+    };
+    Ok((encryption, ciphertext))
+}
+
+fn decrypt(
+    encryption: &BundleEncryption,
+    ciphertext: &[u8],
+    passphrase: &str,
+) -> Result<Vec<u8>, String> {
+    let invalid = |_| "配置包加密参数无效".to_string();
+    let salt = BASE64.decode(&encryption.salt).map_err(invalid)?;
+    let nonce = BASE64.decode(&encryption.nonce).map_err(invalid)?;
+    if nonce.len() != 12 {
+        return Err("配置包加密参数无效".to_string());
+    }
+    let key = derive_key(passphrase, &salt, encryption.iterations);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext)
+        .map_err(|_| "密码错误或配置包已损坏，无法解密密钥".to_string())
+}
+
+// ============ 导出 ============
+
+/// 把当前的 OpenClaw 配置导出为 zip 配置包
+pub fn export(
+    path: &Path,
+    secret_mode: SecretMode,
+    passphrase: Option<&str>,
+) -> Result<BundleManifest, String> {
+    let passphrase = passphrase.filter(|p| !p.is_empty());
+    if secret_mode == SecretMode::Encrypt && passphrase.is_none() {
+        return Err("加密导出需要设置密码".to_string());
+    }
+
+    let mut config_value = config_repo::load_raw()?;
+    let config = config_repo::from_value(config_value.clone())?;
+    let env_path = platform::get_env_file_path();
+    let mut env = if file::file_exists(&env_path) {
+        Some(file::read_file(&env_path).map_err(|e| format!("读取 env 文件失败: {}", e))?)
+    } else {
+        None
+    };
+    let workspace_root = workspace_dir(&config);
+    let mut workspace = BTreeMap::new();
+    collect_markdown(&workspace_root, &workspace_root, &mut workspace);
+
+    let mut taken = BTreeMap::new();
+    if secret_mode != SecretMode::Include {
+        take_json_secrets(&mut config_value, "", &mut taken);
+        env = env.map(|content| take_env_secrets(&content, &mut taken));
+    }
+
+    let config_text = serde_json::to_string_pretty(&config_value)
+        .map_err(|e| format!("序列化配置失败: {}", e))?;
+    let mut entries: Vec<(String, Vec<u8>)> = vec![(CONFIG_FILE.to_string(), config_text.into())];
+    if let Some(env) = env {
+        entries.push((ENV_FILE.to_string(), env.into_bytes()));
+    }
+    for (relative, content) in workspace {
+        entries.push((
+            format!("{}{}", WORKSPACE_PREFIX, relative),
+            content.into_bytes(),
+        ));
+    }
+
+    let mut encryption = None;
+    if let (SecretMode::Encrypt, Some(passphrase)) = (secret_mode, passphrase) {
+        if !taken.is_empty() {
+            let plaintext = serde_json::to_vec(&taken).map_err(|e| e.to_string())?;
+            let (params, ciphertext) = encrypt(&plaintext, passphrase)?;
+            encryption = Some(params);
+            entries.push((SECRETS_FILE.to_string(), ciphertext));
+        }
+    }
+
+    let manifest = BundleManifest {
+        format_version: FORMAT_VERSION,
+        created_at: Local::now().to_rfc3339(),
+        manager_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: platform::get_os(),
+        secret_mode,
+        encryption,
+        secret_locations: taken.into_keys().collect(),
+        plugins: installed_plugins(&config),
+        files: entries.iter().map(|(name, _)| name.clone()).collect(),
+    };
+    let manifest_text =
+        serde_json::to_string_pretty(&manifest).map_err(|e| format!("序列化清单失败: {}", e))?;
+    entries.insert(0, (MANIFEST_FILE.to_string(), manifest_text.into()));
+
+    if let Err(e) = write_zip(path, &entries) {
+        let _ = fs::remove_file(path);
+        return Err(format!("写入配置包失败: {}", e));
+    }
+    info!(
+        "[配置包] 已导出 {}（{} 个文件，{} 个插件）",
+        path.display(),
+        manifest.files.len(),
+        manifest.plugins.len()
+    );
+    Ok(manifest)
+}
+
+fn write_zip(path: &Path, entries: &[(String, Vec<u8>)]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    // 配置包可能包含密钥（导入前的快照总是包含），创建时即仅允许当前用户读写
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let file = options.open(path).map_err(|e| e.to_string())?;
+    let mut zip = ZipWriter::new(file);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, data) in entries {
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| e.to_string())?;
+        zip.write_all(data).map_err(|e| e.to_string())?;
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ============ 导入 ============
+
+fn read_entry(archive: &mut ZipArchive<fs::File>, name: &str) -> Result<Option<Vec<u8>>, String> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(format!("读取配置包中的 {} 失败: {}", name, e)),
+    };
+    if entry.size() > MAX_ENTRY_SIZE {
+        return Err(format!("配置包中的 {} 过大", name));
+    }
+    let mut data = Vec::new();
+    entry
+        .read_to_end(&mut data)
+        .map_err(|e| format!("读取配置包中的 {} 失败: {}", name, e))?;
+    Ok(Some(data))
+}
+
+fn utf8(name: &str, data: Vec<u8>) -> Result<String, String> {
+    String::from_utf8(data).map_err(|_| format!("配置包中的 {} 不是 UTF-8 文本", name))
+}
+
+/// 读取配置包；提供密码时解密其中的密钥
+pub fn read(path: &Path, passphrase: Option<&str>) -> Result<Bundle, String> {
+    let file = fs::File::open(path).map_err(|e| format!("打开配置包失败: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("不是有效的配置包: {}", e))?;
+
+    let manifest_data = read_entry(&mut archive, MANIFEST_FILE)?
+        .ok_or_else(|| format!("不是有效的配置包：缺少 {}", MANIFEST_FILE))?;
+    let manifest: BundleManifest =
+        serde_json::from_slice(&manifest_data).map_err(|e| format!("配置包清单无效: {}", e))?;
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(format!(
+            "配置包由更新版本的 Manager（{}）导出，请先升级 Manager",
+            manifest.manager_version
+        ));
+    }
+
+    let config_data = read_entry(&mut archive, CONFIG_FILE)?
+        .ok_or_else(|| format!("配置包缺少 {}", CONFIG_FILE))?;
+    let config: Value = serde_json::from_slice(&config_data)
+        .map_err(|e| format!("配置包中的 openclaw.json 无效: {}", e))?;
+    config_repo::from_value(config.clone())?;
+
+    let env = match read_entry(&mut archive, ENV_FILE)? {
+        Some(data) => Some(utf8(ENV_FILE, data)?),
+        None => None,
+    };
+
+    let mut workspace = BTreeMap::new();
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("读取配置包失败: {}", e))?;
+        let name = entry.name().to_string();
+        let Some(relative) = name.strip_prefix(WORKSPACE_PREFIX) else {
+            continue;
+        };
+        // 只接受工作区内的 .md 文件，拒绝 ../、绝对路径等越界路径
+        if entry.enclosed_name().is_none()
+            || !is_safe_relative(relative)
+            || !relative.to_lowercase().ends_with(".md")
+        {
+            continue;
+        }
+        if entry.size() > MAX_ENTRY_SIZE {
+            return Err(format!("配置包中的 {} 过大", name));
+        }
+        let mut content = String::new();
+        entry
+            .read_to_string(&mut content)
+            .map_err(|e| format!("读取配置包中的 {} 失败: {}", name, e))?;
+        workspace.insert(relative.to_string(), content);
+    }
+
+    let mut secrets = BTreeMap::new();
+    if let (Some(encryption), Some(passphrase)) =
+        (&manifest.encryption, passphrase.filter(|p| !p.is_empty()))
+    {
+        let ciphertext = read_entry(&mut archive, SECRETS_FILE)?
+            .ok_or_else(|| format!("配置包缺少 {}", SECRETS_FILE))?;
+        let plaintext = decrypt(encryption, &ciphertext, passphrase)?;
+        secrets = serde_json::from_slice(&plaintext).map_err(|e| format!("密钥数据无效: {}", e))?;
+    }
+
+    Ok(Bundle {
+        manifest,
+        config,
+        env,
+        workspace,
+        secrets,
+    })
+}
+
+/// 计算导入后的 openclaw.json 与 env：移出的密钥优先用解密结果，否则保留本机已有的值
+fn plan(bundle: &Bundle) -> Result<ImportPlan, String> {
+    let current_config = config_repo::load_raw()?;
+    let current_env = load_current_env();
+
+    let mut config = bundle.config.clone();
+    let mut env = EnvFile::parse(bundle.env.as_deref().unwrap_or_default());
+    let mut env_restored = false;
+    let mut missing_secrets = Vec::new();
+    for location in &bundle.manifest.secret_locations {
+        let decrypted = bundle.secrets.get(location).cloned();
+        let restored = if let Some(pointer) = location.strip_prefix(CONFIG_SECRET_PREFIX) {
+            decrypted
+                .or_else(|| {
+                    current_config
+                        .pointer(pointer)
+                        .and_then(Value::as_str)
+                        .map(str::to_string)
+                })
+                .is_some_and(|secret| put_json_secret(&mut config, pointer, secret))
+        } else if let Some(key) = location.strip_prefix(ENV_SECRET_PREFIX) {
+            let secret = decrypted.as_deref().or(current_env.get(key));
+            let restored = secret.is_some_and(|secret| env.set(key, secret).is_ok());
+            env_restored |= restored;
+            restored
+        } else {
+            false
+        };
+        if !restored {
+            missing_secrets.push(location.clone());
+        }
+    }
+
+    Ok(ImportPlan {
+        config: serde_json::to_string_pretty(&config)
+            .map_err(|e| format!("序列化配置失败: {}", e))?,
+        env: (bundle.env.is_some() || env_restored).then(|| env.to_content()),
+        missing_secrets,
+    })
+}
+
+/// 导入时会写入的文件：(显示路径, 目标路径, 内容)
+fn planned_files(
+    bundle: &Bundle,
+    plan: &ImportPlan,
+) -> Result<Vec<(String, PathBuf, String)>, String> {
+    let mut files = vec![(
+        CONFIG_FILE.to_string(),
+        PathBuf::from(platform::get_config_file_path()),
+        plan.config.clone(),
+    )];
+    if let Some(env) = &plan.env {
+        files.push((
+            ENV_FILE.to_string(),
+            PathBuf::from(platform::get_env_file_path()),
+            env.clone(),
+        ));
+    }
+    // 工作区文件写入本机配置的工作区目录
+    let workspace_root = workspace_dir(&config_repo::load()?);
+    for (relative, content) in &bundle.workspace {
+        let target = workspace_root.join(relative);
+        if !is_safe_relative(relative) || !target.starts_with(&workspace_root) {
+            return Err(format!("配置包中的 {} 路径越界，已拒绝导入", relative));
+        }
+        files.push((
+            format!("{}{}", WORKSPACE_PREFIX, relative),
+            target,
+            content.clone(),
+        ));
+    }
+    Ok(files)
+}
+
+/// 相对路径的每一段都是普通名称（不含 ..、根目录或盘符），拼接后不会离开所在目录
+fn is_safe_relative(relative: &str) -> bool {
+    !relative.is_empty()
+        && Path::new(relative)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+}
+
+fn file_change(path: &Path, content: &str) -> Option<ChangeKind> {
+    match fs::read_to_string(path) {
+        Ok(existing) if existing == content => None,
+        Ok(_) => Some(ChangeKind::Modified),
+        Err(_) if path.exists() => Some(ChangeKind::Modified),
+        Err(_) => Some(ChangeKind::Added),
+    }
+}
+
+/// 预览导入：列出会覆盖的配置、env 和文件（密钥已脱敏），不写入磁盘
+pub fn preview(bundle: &Bundle) -> Result<BundleImportPreview, String> {
+    let plan = plan(bundle)?;
+
+    let imported: Value = serde_json::from_str(&plan.config).map_err(|e| e.to_string())?;
+    let mut config_changes = json_diff::diff(&config_repo::load_raw()?, &imported, None);
+    config_changes.iter_mut().for_each(secrets::mask_change);
+
+    let mut env_changes = match &plan.env {
+        Some(env) => json_diff::diff(
+            &env_map(&load_current_env()),
+            &env_map(&EnvFile::parse(env)),
+            None,
+        ),
+        None => Vec::new(),
+    };
+    env_changes.iter_mut().for_each(secrets::mask_env_change);
+
+    let files = planned_files(bundle, &plan)?
+        .into_iter()
+        .filter_map(|(path, target, content)| {
+            file_change(&target, &content).map(|kind| BundleFileChange { path, kind })
+        })
+        .collect();
+
+    Ok(BundleImportPreview {
+        manifest: bundle.manifest.clone(),
+        config_changes,
+        env_changes,
+        files,
+        missing_plugins: missing_plugins(&bundle.manifest)
+            .into_iter()
+            .map(|plugin| BundlePlugin {
+                spec: install_spec(&plugin).ok(),
+                ..plugin
+            })
+            .collect(),
+        missing_secrets: plan.missing_secrets,
+    })
+}
+
+/// 导入配置包：先把当前配置完整快照到备份目录，再写入 openclaw.json、env 和工作区文件（不安装插件）
+pub fn apply(bundle: &Bundle) -> Result<BundleImportResult, String> {
+    let plan = plan(bundle)?;
+    let files = planned_files(bundle, &plan)?;
+
+    let snapshot = backup::snapshot_path();
+    export(&snapshot, SecretMode::Include, None)
+        .map_err(|e| format!("备份当前配置失败，已取消导入: {}", e))?;
+    info!("[配置包] 导入前已备份当前配置: {}", snapshot.display());
+    backup::prune(settings::load().backup.max_backups);
+
+    let mut files_written = Vec::new();
+    for (path, target, content) in files {
+        if file_change(&target, &content).is_none() {
+            continue;
+        }
+        if path == CONFIG_FILE {
+            config_repo::replace(&content)?;
+        } else {
+            file::write_file(&target.to_string_lossy(), &content)
+                .map_err(|e| format!("写入 {} 失败: {}", path, e))?;
+        }
+        files_written.push(path);
+    }
+    info!("[配置包] 已导入 {} 个文件", files_written.len());
+
+    Ok(BundleImportResult {
+        snapshot_path: snapshot.to_string_lossy().to_string(),
+        files_written,
+        plugins_installed: Vec::new(),
+        plugin_errors: Vec::new(),
+        missing_secrets: plan.missing_secrets,
+    })
+}
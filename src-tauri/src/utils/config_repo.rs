@@ -0,0 +1,187 @@
+use crate::models::{ConfigPreview, OpenClawConfig};
+use crate::utils::{backup, file, json_diff, platform, secrets};
+use log::warn;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::io;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+/// 串行化 openclaw.json 的读取-修改-写回，避免并发修改互相覆盖
+static CONFIG_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+/// 检测到外部修改时最多尝试应用修改的次数
+const MAX_ATTEMPTS: usize = 3;
+
+/// 读取 openclaw.json 文本（文件不存在时为空）
+fn read_content() -> Result<String, String> {
+    let config_path = platform::get_config_file_path();
+    if !file::file_exists(&config_path) {
+        return Ok(String::new());
+    }
+    file::read_file(&config_path).map_err(|e| format!("读取配置文件失败: {}", e))
+}
+
+fn parse_raw(content: &str) -> Result<Value, String> {
+    if content.trim().is_empty() {
+        return Ok(Value::Object(Default::default()));
+    }
+    serde_json::from_str(content).map_err(|e| format!("解析配置文件失败: {}", e))
+}
+
+/// 配置文件内容的哈希（SHA-256 十六进制）
+fn content_hash(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 读取 openclaw.json 原始内容（文件不存在时返回空对象）
+pub fn load_raw() -> Result<Value, String> {
+    parse_raw(&read_content()?)
+}
+
+/// 把 JSON 转换为类型化配置（未建模的字段保留在 extra 中）
+pub fn from_value(value: Value) -> Result<OpenClawConfig, String> {
+    serde_json::from_value(value).map_err(|e| format!("openclaw.json 结构不正确: {}", e))
+}
+
+/// 读取 openclaw.json（文件不存在时返回空配置）
+pub fn load() -> Result<OpenClawConfig, String> {
+    from_value(load_raw()?)
+}
+
+/// 读取 gateway.auth.token（未配置或读取失败时为 None）
+pub fn gateway_token() -> Option<String> {
+    load().ok()?.gateway_token().map(str::to_string)
+}
+
+/// 备份后写入 openclaw.json；给定 expected 时在替换文件前确认其内容哈希仍为 expected，
+/// 不一致说明期间被其他程序改动，放弃写入并返回 Ok(false)
+fn write(content: &str, expected: Option<&str>) -> Result<bool, String> {
+    // 备份失败不阻止保存，只记录警告
+    if let Err(e) = backup::create() {
+        warn!("[配置备份] 备份 openclaw.json 失败: {}", e);
+    }
+
+    let mut changed = false;
+    let result = file::write_file_checked(&platform::get_config_file_path(), content, || {
+        let Some(expected) = expected else {
+            return Ok(());
+        };
+        let current = read_content().map_err(io::Error::other)?;
+        if content_hash(&current) != expected {
+            changed = true;
+            return Err(io::Error::other(conflict_error()));
+        }
+        Ok(())
+    });
+    if changed {
+        return Ok(false);
+    }
+    result.map_err(|e| format!("写入配置文件失败: {}", e))?;
+    Ok(true)
+}
+
+fn lock() -> MutexGuard<'static, ()> {
+    CONFIG_LOCK
+        .get_or_init(|| Mutex::new(()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// 读取配置、执行修改并写回，返回修改函数的结果；修改函数出错或配置没有变化时不写入
+/// 读取后文件被其他程序（如 openclaw CLI）改动时，重新读取并再次执行修改，修改函数可能被调用多次
+pub fn update<F, T>(mutate: F) -> Result<T, String>
+where
+    F: FnMut(&mut OpenClawConfig) -> Result<T, String>,
+{
+    update_inner(None, mutate)
+}
+
+/// 与 update 相同，但要求 openclaw.json 的内容哈希仍为 base_hash（即预览之后未被改动），
+/// 期间被改动时直接返回冲突错误，不重新应用
+pub fn update_checked<F, T>(base_hash: &str, mutate: F) -> Result<T, String>
+where
+    F: FnMut(&mut OpenClawConfig) -> Result<T, String>,
+{
+    update_inner(Some(base_hash), mutate)
+}
+
+fn conflict_error() -> String {
+    "openclaw.json 已被其他程序修改，为避免覆盖对方的修改已取消保存，请刷新后重试".to_string()
+}
+
+fn update_inner<F, T>(base_hash: Option<&str>, mut mutate: F) -> Result<T, String>
+where
+    F: FnMut(&mut OpenClawConfig) -> Result<T, String>,
+{
+    // 进程内串行化，Manager 自身的修改不会互相覆盖
+    let _guard = lock();
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let content = read_content()?;
+        let hash = content_hash(&content);
+        if base_hash.is_some_and(|h| h != hash) {
+            return Err(conflict_error());
+        }
+
+        let mut config = from_value(parse_raw(&content)?)?;
+        let before = config.clone();
+        let result = mutate(&mut config)?;
+        if config == before {
+            return Ok(result);
+        }
+
+        // 写入时确认文件在读取之后没有被其他程序改动（在备份之后、替换文件之前检查）
+        let content =
+            serde_json::to_string_pretty(&config).map_err(|e| format!("序列化配置失败: {}", e))?;
+        if !write(&content, Some(&hash))? {
+            if base_hash.is_some() {
+                return Err(conflict_error());
+            }
+            warn!(
+                "[配置] openclaw.json 在修改期间被其他程序改动，重新读取后再次应用 ({}/{})",
+                attempt, MAX_ATTEMPTS
+            );
+            continue;
+        }
+        return Ok(result);
+    }
+    Err(conflict_error())
+}
+
+/// 预览修改（dry-run）：返回将要发生的变化（密钥已脱敏）和当前文件的哈希，不写入磁盘
+pub fn preview<F>(mutate: F) -> Result<ConfigPreview, String>
+where
+    F: FnOnce(&mut OpenClawConfig) -> Result<(), String>,
+{
+    let content = read_content()?;
+    let current = parse_raw(&content)?;
+    let mut config = from_value(current.clone())?;
+    mutate(&mut config)?;
+    let updated = serde_json::to_value(&config).map_err(|e| format!("序列化配置失败: {}", e))?;
+
+    let mut changes = json_diff::diff(&current, &updated, None);
+    changes.iter_mut().for_each(secrets::mask_change);
+    Ok(ConfigPreview {
+        base_hash: content_hash(&content),
+        changes,
+    })
+}
+
+/// 用给定内容整体覆盖 openclaw.json（校验结构后写入，覆盖前同样会备份当前文件）
+pub fn replace(content: &str) -> Result<(), String> {
+    let value: Value =
+        serde_json::from_str(content).map_err(|e| format!("不是有效的 JSON: {}", e))?;
+    from_value(value)?;
+
+    let _guard = lock();
+    write(content, None).map(|_| ())
+}
+
+/// 用备份覆盖 openclaw.json（恢复操作本身也可以撤销）
+pub fn restore(backup_id: &str) -> Result<(), String> {
+    let content = backup::read(backup_id)?;
+    replace(&content).map_err(|e| format!("备份 {} {}", backup_id, e))
+}
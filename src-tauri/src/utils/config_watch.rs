@@ -0,0 +1,322 @@
+use crate::models::{ChangeKind, ConfigChange, ConfigWatchStatus, EventTrigger};
+use crate::utils::{dotenv, file, gateway, json_diff, platform, settings, supervisor};
+use chrono::Local;
+use log::{debug, info, warn};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc;
+
+/// 推送给前端的事件名（需要重启 / 已不需要重启时都会推送）
+pub const CONFIG_RESTART_EVENT: &str = "config-restart-required";
+
+/// 文件监听不可用（如配置目录尚不存在）时轮询配置文件的间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 文件监听正常时的兜底检查间隔（同步 Gateway 启停状态、补上遗漏的文件事件）
+const FALLBACK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 不影响 Gateway 运行的 openclaw.json 顶层字段
+const IGNORED_CONFIG_KEYS: &[&str] = &["meta", "wizard"];
+
+/// 只供 Manager 测试渠道使用的 env 变量后缀（OPENCLAW_<渠道>_<字段>）
+const TEST_ONLY_ENV_SUFFIXES: &[&str] = &["_USERID", "_TESTCHATID", "_TESTCHANNELID"];
+
+/// 变化路径最多展开的层级，更深的变化归并到该层
+const MAX_PATH_DEPTH: usize = 4;
+
+static WATCH: OnceLock<Mutex<WatchState>> = OnceLock::new();
+
+/// 配置文件内容
+#[derive(Clone, PartialEq, Default)]
+struct Snapshot {
+    /// openclaw.json（不存在或解析失败时为 None）
+    config: Option<Value>,
+    /// ~/.openclaw/env
+    env: BTreeMap<String, String>,
+}
+
+#[derive(Default)]
+struct WatchState {
+    started: bool,
+    /// Gateway 启动时读取到的配置
+    applied: Option<Snapshot>,
+    /// 最近一次检测到的配置
+    last_seen: Option<Snapshot>,
+    /// 最近一次检测到文件变化的时间（用于防抖）
+    last_change: Option<Instant>,
+    detected_at: Option<String>,
+    /// 上一次推送的变化列表，没有变化时不重复推送
+    reported: Vec<(String, String, ChangeKind)>,
+}
+
+fn watch() -> &'static Mutex<WatchState> {
+    WATCH.get_or_init(|| Mutex::new(WatchState::default()))
+}
+
+fn read_snapshot() -> Snapshot {
+    let config = file::read_file(&platform::get_config_file_path())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok());
+    let env = dotenv::load_openclaw_env_vars().into_iter().collect();
+    Snapshot { config, env }
+}
+
+/// 记录 Gateway 启动时使用的配置（在启动 / 重启 Gateway 前调用）
+pub fn mark_applied() {
+    let mut st = watch().lock().unwrap_or_else(|e| e.into_inner());
+    st.applied = Some(read_snapshot());
+    st.detected_at = None;
+}
+
+/// 该 env 变量是否只供 Manager 使用
+fn env_test_only(key: &str) -> bool {
+    key.starts_with("OPENCLAW_") && TEST_ONLY_ENV_SUFFIXES.iter().any(|s| key.ends_with(s))
+}
+
+/// 列出 applied 到 current 的变化并判断是否需要重启
+fn classify_changes(applied: &Snapshot, current: &Snapshot) -> Vec<ConfigChange> {
+    let mut changes = Vec::new();
+
+    // openclaw.json 被删除或暂时写坏时不判定，等下一次检测
+    if let (Some(a), Some(b)) = (&applied.config, &current.config) {
+        for change in json_diff::diff(a, b, Some(MAX_PATH_DEPTH)) {
+            let top = change.path.split('.').next().unwrap_or_default();
+            changes.push(ConfigChange {
+                file: "openclaw.json".to_string(),
+                restart_required: !IGNORED_CONFIG_KEYS.contains(&top),
+                path: change.path,
+                kind: change.kind,
+            });
+        }
+    }
+
+    // env 只在进程启动时读取，除测试用变量外都需要重启
+    let keys: Vec<&String> = {
+        let mut k: Vec<&String> = applied.env.keys().chain(current.env.keys()).collect();
+        k.sort();
+        k.dedup();
+        k
+    };
+    for key in keys {
+        let kind = match (applied.env.get(key), current.env.get(key)) {
+            (Some(a), Some(b)) if a == b => continue,
+            (None, Some(_)) => ChangeKind::Added,
+            (Some(_), None) => ChangeKind::Removed,
+            _ => ChangeKind::Modified,
+        };
+        changes.push(ConfigChange {
+            file: "env".to_string(),
+            path: key.clone(),
+            kind,
+            restart_required: !env_test_only(key),
+        });
+    }
+    changes
+}
+
+fn build_status(st: &WatchState, changes: Vec<ConfigChange>) -> ConfigWatchStatus {
+    let config = settings::load().config_watch;
+    ConfigWatchStatus {
+        restart_required: changes.iter().any(|c| c.restart_required),
+        changes,
+        detected_at: st.detected_at.clone(),
+        auto_restart: config.auto_restart,
+        debounce_secs: config.debounce_secs,
+    }
+}
+
+/// 当前的配置变化状态
+pub fn status() -> ConfigWatchStatus {
+    let st = watch().lock().unwrap_or_else(|e| e.into_inner());
+    let changes = match (&st.applied, &st.last_seen) {
+        (Some(applied), Some(current)) => classify_changes(applied, current),
+        _ => Vec::new(),
+    };
+    build_status(&st, changes)
+}
+
+/// 启动后台检测（只启动一次）
+pub fn start(app: AppHandle) {
+    {
+        let mut st = watch().lock().unwrap_or_else(|e| e.into_inner());
+        if st.started {
+            return;
+        }
+        st.started = true;
+        // 假设已在运行的 Gateway 使用的是当前磁盘上的配置
+        if st.applied.is_none() {
+            st.applied = Some(read_snapshot());
+        }
+    }
+    info!("[配置检测] 启动 openclaw.json / env 变化检测");
+    tauri::async_runtime::spawn(async move {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut watcher = None;
+        loop {
+            if watcher.is_none() {
+                watcher = watch_config_dir(tx.clone());
+            }
+            tick(&app).await;
+
+            let timeout = match (&watcher, pending_debounce()) {
+                (_, Some(remaining)) => remaining,
+                (Some(_), None) => FALLBACK_INTERVAL,
+                (None, None) => POLL_INTERVAL,
+            };
+            let _ = tokio::time::timeout(timeout, rx.recv()).await;
+            // 一次保存通常触发多个事件，合并为一次检测
+            while rx.try_recv().is_ok() {}
+        }
+    });
+}
+
+/// 监听配置目录中 openclaw.json 和 env 的变化（编辑器、写入函数都是先写临时文件再重命名，因此监听目录）
+fn watch_config_dir(tx: mpsc::UnboundedSender<()>) -> Option<RecommendedWatcher> {
+    let dir = platform::get_config_dir();
+    let names = [
+        platform::get_config_file_path(),
+        platform::get_env_file_path(),
+    ]
+    .map(|p| Path::new(&p).file_name().map(|n| n.to_os_string()));
+
+    let handler = move |result: notify::Result<notify::Event>| match result {
+        Ok(event) => {
+            let relevant = event.paths.iter().any(|p| {
+                names
+                    .iter()
+                    .flatten()
+                    .any(|n| p.file_name() == Some(n.as_os_str()))
+            });
+            if relevant {
+                let _ = tx.send(());
+            }
+        }
+        Err(e) => debug!("[配置检测] 文件监听出错: {}", e),
+    };
+    let watched = notify::recommended_watcher(handler).and_then(|mut watcher| {
+        watcher.watch(Path::new(&dir), RecursiveMode::NonRecursive)?;
+        Ok(watcher)
+    });
+    match watched {
+        Ok(watcher) => {
+            info!("[配置检测] ✓ 已监听 {}", dir);
+            Some(watcher)
+        }
+        Err(e) => {
+            debug!("[配置检测] 无法监听 {}，改为定时检查: {}", dir, e);
+            None
+        }
+    }
+}
+
+/// 有等待防抖的配置变化且开启了自动重启时，距离防抖结束的剩余时间
+fn pending_debounce() -> Option<Duration> {
+    let config = settings::load().config_watch;
+    if !config.auto_restart {
+        return None;
+    }
+    let st = watch().lock().unwrap_or_else(|e| e.into_inner());
+    // 只有已检测到需要重启的变化时才需要按时检查
+    st.detected_at.as_ref()?;
+    let elapsed = st.last_change?.elapsed();
+    Some(
+        Duration::from_secs(config.debounce_secs)
+            .saturating_sub(elapsed)
+            .max(Duration::from_millis(100)),
+    )
+}
+
+/// 检测一次：文件变化时重新分类并推送事件，满足防抖条件时自动重启
+async fn tick(app: &AppHandle) {
+    let current = read_snapshot();
+    let running = tauri::async_runtime::spawn_blocking(gateway::is_running)
+        .await
+        .unwrap_or(false);
+
+    let (status, auto_restart) = {
+        let mut st = watch().lock().unwrap_or_else(|e| e.into_inner());
+        if st.last_seen.as_ref() != Some(&current) {
+            st.last_change = Some(Instant::now());
+            st.last_seen = Some(current.clone());
+        }
+        // Gateway 未运行时，下次启动自然会读取最新配置
+        if !running {
+            st.applied = Some(current.clone());
+        }
+
+        let changes = match &st.applied {
+            Some(applied) => classify_changes(applied, &current),
+            None => Vec::new(),
+        };
+        let status = build_status(&st, changes);
+        if status.restart_required && st.detected_at.is_none() {
+            st.detected_at = Some(Local::now().to_rfc3339());
+        } else if !status.restart_required {
+            st.detected_at = None;
+        }
+        let status = ConfigWatchStatus {
+            detected_at: st.detected_at.clone(),
+            ..status
+        };
+
+        let reported: Vec<(String, String, ChangeKind)> = status
+            .changes
+            .iter()
+            .map(|c| (c.file.clone(), c.path.clone(), c.kind))
+            .collect();
+        if reported != st.reported {
+            st.reported = reported;
+            if status.restart_required {
+                info!(
+                    "[配置检测] 配置已变化，需要重启 Gateway: {}",
+                    status
+                        .changes
+                        .iter()
+                        .filter(|c| c.restart_required)
+                        .map(|c| format!("{}:{}", c.file, c.path))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+            if let Err(e) = app.emit(CONFIG_RESTART_EVENT, status.clone()) {
+                warn!("[配置检测] 推送事件失败: {}", e);
+            }
+        }
+
+        let settled = st
+            .last_change
+            .is_some_and(|t| t.elapsed() >= Duration::from_secs(status.debounce_secs));
+        let auto_restart = status.restart_required && status.auto_restart && running && settled;
+        (status, auto_restart)
+    };
+
+    if auto_restart {
+        info!(
+            "[配置检测] 配置已稳定 {} 秒，自动重启 Gateway",
+            status.debounce_secs
+        );
+        match supervisor::restart_with_reason(app, EventTrigger::ConfigWatch).await {
+            Ok(message) => info!("[配置检测] ✓ {}", message),
+            Err(e) => warn!("[配置检测] 自动重启失败: {}", e),
+        }
+        // 重启失败时保留提示但不反复重试，等待下一次配置变化
+        watch()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .last_change = None;
+    }
+}
+
+/// 用户选择暂不重启：把当前配置视为已应用，清除提示
+pub fn dismiss() -> ConfigWatchStatus {
+    mark_applied();
+    let mut st = watch().lock().unwrap_or_else(|e| e.into_inner());
+    st.last_seen = st.applied.clone();
+    st.reported.clear();
+    build_status(&st, Vec::new())
+}
@@ -0,0 +1,338 @@
+use crate::utils::{file, platform};
+use std::collections::HashMap;
+use std::io;
+
+/// env 文件中的一条记录
+#[derive(Debug, Clone)]
+enum Line {
+    /// 变量定义（raw 为原文，可能跨多行；未修改的行写回时保持原样）
+    Entry {
+        key: String,
+        value: String,
+        export: bool,
+        raw: String,
+    },
+    /// 注释、空行和无法解析的行，原样保留
+    Other(String),
+}
+
+/// 解析后的 env 文件，修改后写回时保留注释、顺序和未修改的行
+///
+/// 支持 `KEY=value` / `export KEY=value`、单引号（原样）和双引号（支持 `\" \\ \$ \n` 等转义）、
+/// 行尾注释，以及引号内跨行的值
+#[derive(Debug, Clone, Default)]
+pub struct EnvFile {
+    lines: Vec<Line>,
+}
+
+/// 环境变量名：字母或下划线开头，只含字母、数字和下划线
+pub fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 写入双引号值时的转义，同时兼容 shell 的 source 和 dotenv 解析（换行原样保留在引号内）
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | '$' | '`') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn render(key: &str, value: &str, export: bool) -> String {
+    let prefix = if export { "export " } else { "" };
+    format!("{}{}={}", prefix, key, quote(value))
+}
+
+/// 解析双引号值（text 以 " 开头），返回值和右引号之后的位置
+fn parse_double_quoted(text: &str) -> Option<(String, usize)> {
+    let mut value = String::new();
+    let mut chars = text.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, i + 1)),
+            '\\' => match chars.next()?.1 {
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                't' => value.push('\t'),
+                // 反斜杠 + 换行为续行
+                '\n' => {}
+                escaped @ ('"' | '\\' | '$' | '`') => value.push(escaped),
+                other => {
+                    value.push('\\');
+                    value.push(other);
+                }
+            },
+            _ => value.push(c),
+        }
+    }
+    None
+}
+
+/// 去掉未加引号的值的行尾注释（# 前必须有空白，KEY=a#b 的值为 a#b）
+fn strip_comment(text: &str) -> &str {
+    let mut prev_blank = false;
+    for (i, c) in text.char_indices() {
+        if c == '#' && prev_blank {
+            return &text[..i];
+        }
+        prev_blank = c == ' ' || c == '\t';
+    }
+    text
+}
+
+/// 尝试从 text 开头解析一个变量定义，返回记录和消耗的字节数（含行尾换行符）
+fn parse_entry(text: &str) -> Option<(Line, usize)> {
+    let line_end = text.find('\n').unwrap_or(text.len());
+    let line = &text[..line_end];
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return None;
+    }
+
+    let (export, body) = match trimmed.strip_prefix("export") {
+        Some(rest) if rest.starts_with([' ', '\t']) => (true, rest.trim_start()),
+        _ => (false, trimmed),
+    };
+    let (key, _) = body.split_once('=')?;
+    let key = key.trim_end();
+    if !is_valid_key(key) {
+        return None;
+    }
+
+    // = 之后的位置（相对 text）
+    let after_eq = line_end - body.len() + body.find('=')? + 1;
+    let value_start =
+        after_eq + (text[after_eq..line_end].len() - text[after_eq..line_end].trim_start().len());
+    let rest = &text[value_start..];
+    let (value, value_end) = match rest.chars().next() {
+        Some('"') => parse_double_quoted(rest)?,
+        Some('\'') => {
+            let close = rest[1..].find('\'')? + 1;
+            (rest[1..close].to_string(), close + 1)
+        }
+        _ => {
+            let unquoted = strip_comment(&text[after_eq..line_end]).trim();
+            let end = line_end - value_start;
+            (unquoted.to_string(), end)
+        }
+    };
+
+    // 引号之后只允许空白和注释
+    let tail = &rest[value_end..];
+    let tail_end = tail.find('\n').unwrap_or(tail.len());
+    let tail_text = tail[..tail_end].trim();
+    if !tail_text.is_empty() && !tail_text.starts_with('#') {
+        return None;
+    }
+
+    let raw_end = value_start + value_end + tail_end;
+    let consumed = (raw_end + 1).min(text.len());
+    let entry = Line::Entry {
+        key: key.to_string(),
+        value,
+        export,
+        raw: text[..raw_end].trim_end_matches('\r').to_string(),
+    };
+    Some((entry, consumed))
+}
+
+impl EnvFile {
+    /// 解析 env 文件内容
+    pub fn parse(content: &str) -> EnvFile {
+        let mut lines = Vec::new();
+        let mut rest = content;
+        while !rest.is_empty() {
+            let consumed = match parse_entry(rest) {
+                Some((entry, consumed)) => {
+                    lines.push(entry);
+                    consumed
+                }
+                None => {
+                    let end = rest.find('\n').map_or(rest.len(), |i| i + 1);
+                    let line = rest[..end].trim_end_matches('\n').trim_end_matches('\r');
+                    lines.push(Line::Other(line.to_string()));
+                    end
+                }
+            };
+            rest = &rest[consumed..];
+        }
+        EnvFile { lines }
+    }
+
+    /// 读取 env 文件（文件不存在时为空）
+    pub fn load(path: &str) -> io::Result<EnvFile> {
+        if !file::file_exists(path) {
+            return Ok(EnvFile::default());
+        }
+        Ok(EnvFile::parse(&file::read_file(path)?))
+    }
+
+    /// 写回 env 文件
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        file::write_file(path, &self.to_content())
+    }
+
+    /// 生成文件内容
+    pub fn to_content(&self) -> String {
+        let mut content = String::new();
+        for line in &self.lines {
+            match line {
+                Line::Entry { raw, .. } | Line::Other(raw) => content.push_str(raw),
+            }
+            content.push('\n');
+        }
+        content
+    }
+
+    /// 读取变量（重复定义时以最后一次为准，与 shell 一致）
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.lines.iter().rev().find_map(|line| match line {
+            Line::Entry { key: k, value, .. } if k == key => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// 全部变量（按首次出现的顺序，重复定义时取最后一次的值）
+    pub fn vars(&self) -> Vec<(String, String)> {
+        let mut vars: Vec<(String, String)> = Vec::new();
+        for line in &self.lines {
+            let Line::Entry { key, value, .. } = line else {
+                continue;
+            };
+            match vars.iter_mut().find(|(k, _)| k == key) {
+                Some(existing) => existing.1 = value.clone(),
+                None => vars.push((key.clone(), value.clone())),
+            }
+        }
+        vars
+    }
+
+    /// 设置变量：已有时在原位置更新（并删除后面的重复定义），否则追加到末尾
+    pub fn set(&mut self, key: &str, value: &str) -> io::Result<()> {
+        if !is_valid_key(key) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("无效的环境变量名: {}", key),
+            ));
+        }
+        let mut found = false;
+        self.lines.retain_mut(|line| {
+            let Line::Entry {
+                key: k,
+                value: v,
+                export,
+                raw,
+            } = line
+            else {
+                return true;
+            };
+            if k != key {
+                return true;
+            }
+            if found {
+                return false;
+            }
+            found = true;
+            if v != value {
+                *v = value.to_string();
+                *raw = render(key, value, *export);
+            }
+            true
+        });
+        if !found {
+            self.lines.push(Line::Entry {
+                key: key.to_string(),
+                value: value.to_string(),
+                export: true,
+                raw: render(key, value, true),
+            });
+        }
+        Ok(())
+    }
+
+    /// 删除变量（包括重复定义），返回是否存在
+    pub fn remove(&mut self, key: &str) -> bool {
+        let before = self.lines.len();
+        self.lines
+            .retain(|line| !matches!(line, Line::Entry { key: k, .. } if k == key));
+        self.lines.len() != before
+    }
+
+    /// 重命名变量，保留位置、值和 export 前缀
+    pub fn rename(&mut self, from: &str, to: &str) -> io::Result<()> {
+        if !is_valid_key(to) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("无效的环境变量名: {}", to),
+            ));
+        }
+        if self.get(from).is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("环境变量 {} 不存在", from),
+            ));
+        }
+        if from == to {
+            return Ok(());
+        }
+        if self.get(to).is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("环境变量 {} 已存在", to),
+            ));
+        }
+        for line in &mut self.lines {
+            if let Line::Entry {
+                key,
+                value,
+                export,
+                raw,
+            } = line
+            {
+                if key == from {
+                    *key = to.to_string();
+                    *raw = render(to, value, *export);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 读取 env 文件中的变量
+pub fn read_value(path: &str, key: &str) -> Option<String> {
+    EnvFile::load(path).ok()?.get(key).map(str::to_string)
+}
+
+/// 设置 env 文件中的变量
+pub fn set_value(path: &str, key: &str, value: &str) -> io::Result<()> {
+    let mut env = EnvFile::load(path)?;
+    env.set(key, value)?;
+    env.save(path)
+}
+
+/// 删除 env 文件中的变量（不存在时不改动文件）
+pub fn remove_value(path: &str, key: &str) -> io::Result<()> {
+    let mut env = EnvFile::load(path)?;
+    if env.remove(key) {
+        env.save(path)?;
+    }
+    Ok(())
+}
+
+/// 读取 ~/.openclaw/env 中的全部变量
+pub fn load_openclaw_env_vars() -> HashMap<String, String> {
+    EnvFile::load(&platform::get_env_file_path())
+        .map(|env| env.vars().into_iter().collect())
+        .unwrap_or_default()
+}
@@ -1,25 +1,79 @@
 use std::fs;
-use std::io::{self, BufRead, BufReader};
-use std::path::Path;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 临时文件序号，同一进程内多个线程同时写入同一文件时临时文件名也不会冲突
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
 
 /// 读取文件内容
 pub fn read_file(path: &str) -> io::Result<String> {
     fs::read_to_string(path)
 }
 
-/// 写入文件内容
+/// 写入文件内容（先写临时文件再重命名，写入中途崩溃不会留下半个文件）
 pub fn write_file(path: &str, content: &str) -> io::Result<()> {
+    write_file_checked(path, content, || Ok(()))
+}
+
+/// 与 write_file 相同，但在临时文件写好、替换目标文件之前调用 check，check 出错时放弃写入
+pub fn write_file_checked<F>(path: &str, content: &str, check: F) -> io::Result<()>
+where
+    F: FnOnce() -> io::Result<()>,
+{
+    // 符号链接写入其指向的文件，保持链接不变
+    let target = fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+
     // 确保父目录存在
-    if let Some(parent) = Path::new(path).parent() {
+    if let Some(parent) = target.parent() {
         fs::create_dir_all(parent)?;
     }
-    fs::write(path, content)
+
+    let mut tmp_name = target.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let tmp = target.with_file_name(tmp_name);
+
+    let result = write_and_replace(&tmp, &target, content, check);
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+fn write_and_replace<F>(tmp: &Path, target: &Path, content: &str, check: F) -> io::Result<()>
+where
+    F: FnOnce() -> io::Result<()>,
+{
+    // 写入内容前就确定权限：沿用原文件的权限（如 env 文件的 600），
+    // 新文件（配置备份、新建的 env 等可能包含密钥）只允许当前用户读写
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        let mode = fs::metadata(target)
+            .map(|m| m.permissions().mode() & 0o7777)
+            .unwrap_or(0o600);
+        options.mode(mode);
+    }
+    let mut file = options.open(tmp)?;
+    // mode 会受 umask 影响，原文件的权限需要再设置一次
+    if let Ok(meta) = fs::metadata(target) {
+        file.set_permissions(meta.permissions())?;
+    }
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    check()?;
+    fs::rename(tmp, target)
 }
 
 /// 追加文件内容
 pub fn append_file(path: &str, content: &str) -> io::Result<()> {
     use std::fs::OpenOptions;
-    use std::io::Write;
 
     let mut file = OpenOptions::new().create(true).append(true).open(path)?;
 
@@ -32,64 +86,38 @@ pub fn file_exists(path: &str) -> bool {
 }
 
 /// 读取文件最后 N 行
+/// 从文件末尾向前分块读取，读够 N 行即停止，不会把大文件整个读入内存
 pub fn read_last_lines(path: &str, n: usize) -> io::Result<Vec<String>> {
-    let file = fs::File::open(path)?;
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader.lines().filter_map(|l| l.ok()).collect();
-
-    let start = if lines.len() > n { lines.len() - n } else { 0 };
-    Ok(lines[start..].to_vec())
-}
-
-/// 从环境变量文件读取值
-pub fn read_env_value(env_file: &str, key: &str) -> Option<String> {
-    let content = read_file(env_file).ok()?;
-
-    for line in content.lines() {
-        let line = line.trim();
-        if line.starts_with(&format!("export {}=", key)) {
-            let value = line
-                .trim_start_matches(&format!("export {}=", key))
-                .trim_matches('"')
-                .trim_matches('\'');
-            return Some(value.to_string());
-        }
+    const CHUNK_SIZE: u64 = 64 * 1024;
+
+    let mut file = fs::File::open(path)?;
+    let mut pos = file.metadata()?.len();
+    let mut chunks = Vec::new();
+    let mut newlines = 0usize;
+    // 末尾换行之前的每个换行都是一行的起点，多读到一个换行即可保证最前面的行是完整的
+    while pos > 0 && newlines <= n {
+        let size = CHUNK_SIZE.min(pos);
+        pos -= size;
+        let mut chunk = vec![0u8; size as usize];
+        file.seek(SeekFrom::Start(pos))?;
+        file.read_exact(&mut chunk)?;
+        newlines += chunk.iter().filter(|&&b| b == b'\n').count();
+        chunks.push(chunk);
     }
+    chunks.reverse();
+    let content = chunks.concat();
 
-    None
-}
-
-/// 设置环境变量文件中的值
-pub fn set_env_value(env_file: &str, key: &str, value: &str) -> io::Result<()> {
-    let content = read_file(env_file).unwrap_or_default();
-    let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
-
-    let new_line = format!("export {}=\"{}\"", key, value);
-    let mut found = false;
-
-    for line in &mut lines {
-        if line.starts_with(&format!("export {}=", key)) {
-            *line = new_line.clone();
-            found = true;
-            break;
-        }
+    let mut lines: Vec<&[u8]> = content.split(|&b| b == b'\n').collect();
+    if content.is_empty() || content.ends_with(b"\n") {
+        lines.pop();
     }
-
-    if !found {
-        lines.push(new_line);
+    if pos > 0 && !lines.is_empty() {
+        // 第一段是被截断的行
+        lines.remove(0);
     }
-
-    write_file(env_file, &lines.join("\n"))
-}
-
-/// 从环境变量文件中删除指定的值
-pub fn remove_env_value(env_file: &str, key: &str) -> io::Result<()> {
-    let content = read_file(env_file).unwrap_or_default();
-    let lines: Vec<String> = content
-        .lines()
-        .filter(|line| !line.starts_with(&format!("export {}=", key)))
-        .map(|s| s.to_string())
-        .collect();
-
-    write_file(env_file, &lines.join("\n"))
+    let start = lines.len().saturating_sub(n);
+    Ok(lines[start..]
+        .iter()
+        .map(|l| String::from_utf8_lossy(l.strip_suffix(b"\r").unwrap_or(l)).into_owned())
+        .collect())
 }
@@ -0,0 +1,376 @@
+use crate::models::{
+    GatewayAccessSettings, GatewayAuthMode, GatewayListenInfo, PortOwner, ServiceStatus,
+    DEFAULT_GATEWAY_BIND, DEFAULT_GATEWAY_PORT,
+};
+use crate::utils::{config_repo, file, health, platform, procinfo, settings, supervisor, systemd};
+use log::{debug, info};
+use serde_json::Value;
+use std::net::{IpAddr, SocketAddr, TcpListener};
+
+/// openclaw gateway --bind 支持的模式
+pub const GATEWAY_BIND_MODES: &[&str] = &["loopback", "lan", "tailnet", "auto"];
+
+/// Gateway 密码最短长度
+pub const MIN_GATEWAY_PASSWORD_LEN: usize = 8;
+
+/// 自动选择端口时向后尝试的范围
+const AUTO_PORT_SEARCH_RANGE: u16 = 100;
+
+/// 从 openclaw.json 读取 gateway.port / gateway.bind
+fn read_openclaw_listen() -> (Option<u16>, Option<String>) {
+    let content = match file::read_file(&platform::get_config_file_path()) {
+        Ok(c) => c,
+        Err(_) => return (None, None),
+    };
+    let config: Value = match serde_json::from_str(&content) {
+        Ok(v) => v,
+        Err(_) => return (None, None),
+    };
+    let port = config
+        .pointer("/gateway/port")
+        .and_then(|v| v.as_u64())
+        .and_then(|p| u16::try_from(p).ok())
+        .filter(|p| *p > 0);
+    let bind = config
+        .pointer("/gateway/bind")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+    (port, bind)
+}
+
+/// 获取 Gateway 监听配置
+/// 优先级：Manager 配置 > openclaw.json > 默认值
+pub fn get_listen_info() -> GatewayListenInfo {
+    let manager = settings::load().gateway;
+    let (config_port, config_bind) = read_openclaw_listen();
+
+    let (port, source) = match (manager.port, config_port) {
+        (Some(p), _) => (p, "manager"),
+        (None, Some(p)) => (p, "openclaw"),
+        (None, None) => (DEFAULT_GATEWAY_PORT, "default"),
+    };
+    let bind = manager
+        .bind
+        .or(config_bind)
+        .unwrap_or_else(|| DEFAULT_GATEWAY_BIND.to_string());
+
+    GatewayListenInfo {
+        port,
+        bind,
+        source: source.to_string(),
+        auto_port: manager.auto_port,
+        active_port: manager.active_port,
+    }
+}
+
+/// 当前 Gateway 应该在的端口（自动切换过端口时为实际端口）
+pub fn get_gateway_port() -> u16 {
+    let info = get_listen_info();
+    info.active_port.unwrap_or(info.port)
+}
+
+/// 记录 Gateway 实际使用的端口，与配置端口相同时清除记录
+pub fn set_active_port(port: Option<u16>) -> Result<(), String> {
+    let configured = get_listen_info().port;
+    let active = port.filter(|p| *p != configured);
+    if settings::load().gateway.active_port == active {
+        return Ok(());
+    }
+    settings::update(|s| s.gateway.active_port = active).map(|_| ())
+}
+
+/// 本机的局域网 IPv4 地址（排除回环和链路本地地址）
+pub fn lan_addresses() -> Vec<IpAddr> {
+    let interfaces = match if_addrs::get_if_addrs() {
+        Ok(interfaces) => interfaces,
+        Err(e) => {
+            debug!("[Gateway] 读取网卡地址失败: {}", e);
+            return Vec::new();
+        }
+    };
+    let mut addrs: Vec<IpAddr> = interfaces
+        .iter()
+        .filter(|i| !i.is_loopback() && !i.is_link_local())
+        .map(|i| i.ip())
+        .filter(|ip| ip.is_ipv4())
+        .collect();
+    addrs.sort();
+    addrs.dedup();
+    addrs
+}
+
+/// 本机连接 Gateway（健康检查等）使用的地址
+/// tailnet 只监听 Tailscale 地址（100.64.0.0/10），回环地址不可达；其余模式均可通过回环地址访问
+pub fn probe_address(bind: &str) -> IpAddr {
+    let loopback = IpAddr::from([127, 0, 0, 1]);
+    if bind != "tailnet" {
+        return loopback;
+    }
+    lan_addresses()
+        .into_iter()
+        .find(|ip| match ip {
+            IpAddr::V4(v4) => {
+                let [a, b, ..] = v4.octets();
+                a == 100 && (64..128).contains(&b)
+            }
+            IpAddr::V6(_) => false,
+        })
+        .unwrap_or(loopback)
+}
+
+/// 绑定模式是否只允许本机访问
+pub fn is_loopback_bind(bind: &str) -> bool {
+    bind == "loopback"
+}
+
+/// 规范化允许的网页来源：scheme://host[:port]，或 * 表示任意来源
+pub fn normalize_origin(origin: &str) -> Result<String, String> {
+    let origin = origin.trim().trim_end_matches('/').to_lowercase();
+    if origin == "*" {
+        return Ok(origin);
+    }
+    let host = origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+        .ok_or_else(|| format!("来源必须以 http:// 或 https:// 开头: {}", origin))?;
+    if host.is_empty() || host.contains(['/', '?', '#', ' ']) {
+        return Err(format!("来源只能包含协议、主机和端口: {}", origin));
+    }
+    Ok(origin)
+}
+
+/// 同一局域网内其他设备访问控制台的地址（bind 为 loopback 时为空）
+pub fn lan_urls() -> Vec<String> {
+    let config = config_repo::load().unwrap_or_default();
+    if is_loopback_bind(&get_listen_info().bind) {
+        return Vec::new();
+    }
+    let scheme = if config.gateway_tls_enabled() {
+        "https"
+    } else {
+        "http"
+    };
+    let port = get_gateway_port();
+    lan_addresses()
+        .iter()
+        .map(|ip| format!("{}://{}:{}", scheme, ip, port))
+        .collect()
+}
+
+/// 认证方式、绑定模式、HTTPS 和允许来源组合后的安全风险
+pub fn access_risks(
+    auth_mode: GatewayAuthMode,
+    bind: &str,
+    tls: bool,
+    origins: &[String],
+) -> Vec<String> {
+    let mut risks = Vec::new();
+    if !is_loopback_bind(bind) {
+        if auth_mode == GatewayAuthMode::None {
+            risks.push(format!(
+                "Gateway 监听 {} 且未开启认证，同一网络中的任何设备都能打开控制台并调用模型",
+                bind
+            ));
+        } else if !tls {
+            risks.push(
+                "局域网访问使用明文 HTTP，token / 密码可能被同一网络中的设备截获，建议启用 HTTPS"
+                    .to_string(),
+            );
+        }
+    }
+    if origins.iter().any(|o| o == "*") {
+        risks.push("允许来源包含 *，任意网页都能在浏览器中连接 Gateway".to_string());
+    }
+    risks
+}
+
+/// 读取当前的认证与网络暴露设置
+pub fn get_access_settings() -> Result<GatewayAccessSettings, String> {
+    let config = config_repo::load()?;
+    let auth_mode = config.gateway_auth_mode();
+    let bind = get_listen_info().bind;
+    let allowed_origins = config.gateway_allowed_origins().to_vec();
+    Ok(GatewayAccessSettings {
+        auth_mode,
+        token_set: config.gateway_token().is_some(),
+        password_set: config.gateway_password().is_some(),
+        risks: access_risks(
+            auth_mode,
+            &bind,
+            config.gateway_tls_enabled(),
+            &allowed_origins,
+        ),
+        bind,
+        allowed_origins,
+        lan_urls: lan_urls(),
+    })
+}
+
+/// 识别端口占用进程，确认是否为 openclaw gateway
+/// 本应用守护的进程和 systemd 服务中的进程直接视为 Gateway，其余根据命令行和可执行文件判断
+pub fn identify_port_owner(pid: Option<u32>) -> PortOwner {
+    let Some(pid) = pid else {
+        // 无法解析 PID（权限不足），无法确认身份，按其他程序处理，避免误判为运行中或误终止
+        return PortOwner {
+            pid: None,
+            name: None,
+            exe: None,
+            command_line: None,
+            is_gateway: false,
+            verified: false,
+        };
+    };
+
+    let info = procinfo::get_process_info(pid);
+    let verified = info.is_some();
+    let info = info.unwrap_or_default();
+    let known = supervisor::status().pid == Some(pid) || systemd::owns_pid(pid);
+    PortOwner {
+        pid: Some(pid),
+        is_gateway: known || looks_like_gateway(&info),
+        name: info.name,
+        exe: info.exe,
+        command_line: info.command_line,
+        verified,
+    }
+}
+
+fn looks_like_gateway(info: &procinfo::ProcessInfo) -> bool {
+    let cmd = info
+        .command_line
+        .as_deref()
+        .unwrap_or_default()
+        .to_lowercase();
+    let exe = info.exe.as_deref().unwrap_or_default().to_lowercase();
+    let name = info.name.as_deref().unwrap_or_default().to_lowercase();
+
+    // npm 安装时为 node .../openclaw/... gateway，也可能以 openclaw-gateway 为进程名
+    let mentions_openclaw =
+        cmd.contains("openclaw") || exe.contains("openclaw") || name.contains("openclaw");
+    let mentions_gateway = cmd.contains("gateway") || name.contains("gateway");
+    mentions_openclaw && mentions_gateway
+}
+
+/// 判断进程是否为 openclaw gateway
+pub fn is_gateway_process(pid: u32) -> bool {
+    identify_port_owner(Some(pid)).is_gateway
+}
+
+/// 端口是否可以绑定
+fn port_is_free(port: u16) -> bool {
+    if procinfo::find_port_listener(port).is_some() {
+        return false;
+    }
+    TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], port))).is_ok()
+}
+
+/// 从 start 之后查找一个空闲端口
+pub fn find_free_port(start: u16) -> Option<u16> {
+    let end = start.saturating_add(AUTO_PORT_SEARCH_RANGE);
+    let port = (start.saturating_add(1)..=end).find(|p| port_is_free(*p));
+    debug!("[Gateway] 从 {} 之后查找空闲端口: {:?}", start, port);
+    port
+}
+
+/// 为即将启动的 Gateway 选择端口
+/// 配置端口被其他程序占用时，若允许则自动选择空闲端口
+pub fn resolve_start_port() -> Result<u16, String> {
+    let info = get_listen_info();
+    let port = info.port;
+
+    let listener = match procinfo::find_port_listener(port) {
+        Some(l) => l,
+        None => return Ok(port),
+    };
+    let occupant = identify_port_owner(listener.pid).describe();
+
+    if !info.auto_port {
+        return Err(format!(
+            "端口 {} 已被其他程序占用 ({})，请在设置中更换 Gateway 端口",
+            port, occupant
+        ));
+    }
+
+    let free = find_free_port(port).ok_or_else(|| {
+        format!(
+            "端口 {} 已被其他程序占用 ({})，且附近没有可用端口",
+            port, occupant
+        )
+    })?;
+    info!(
+        "[Gateway] 端口 {} 被 {} 占用，自动改用端口 {}",
+        port, occupant, free
+    );
+    Ok(free)
+}
+
+/// 当前端口是否由 Gateway 监听（不做健康检查，适合频繁轮询）
+pub fn is_running() -> bool {
+    procinfo::find_port_listener(get_gateway_port())
+        .map(|l| identify_port_owner(l.pid).is_gateway)
+        .unwrap_or(false)
+}
+
+/// 在阻塞线程池中采集状态，供 async 命令调用，避免健康检查阻塞 tokio 工作线程
+pub async fn collect_status_async() -> Result<ServiceStatus, String> {
+    tauri::async_runtime::spawn_blocking(collect_status)
+        .await
+        .map_err(|e| format!("获取服务状态失败: {}", e))
+}
+
+/// 采集 Gateway 当前状态（检查端口占用，并读取监听进程的资源占用）
+/// 不含 state 字段，状态判定见 service_watch::classify
+pub fn collect_status() -> ServiceStatus {
+    let listen = get_listen_info();
+    let port = listen.active_port.unwrap_or(listen.port);
+    let owner = procinfo::find_port_listener(port).map(|l| identify_port_owner(l.pid));
+    // 只有确认是 Gateway 才算运行中，其他程序占用端口单独报告
+    let running = owner.as_ref().is_some_and(|o| o.is_gateway);
+    let pid = owner.as_ref().filter(|o| o.is_gateway).and_then(|o| o.pid);
+
+    let mut status = ServiceStatus {
+        running,
+        pid,
+        port,
+        bind: listen.bind,
+        port_owner: owner,
+        ..ServiceStatus::default()
+    };
+
+    if running {
+        status.managed_by = Some(managed_by(pid).to_string());
+        status.health = Some(health::probe_cached(port, &status.bind));
+    }
+
+    if let Some(info) = pid.and_then(procinfo::get_process_info) {
+        status.uptime_seconds = info.uptime_seconds;
+        status.memory_mb = info.memory_mb;
+        status.cpu_percent = info.cpu_percent;
+        status.thread_count = info.thread_count;
+        status.command_line = info.command_line;
+    }
+
+    status
+}
+
+/// 判断监听进程由谁管理：systemd 用户服务 / 本应用守护 / 外部启动
+fn managed_by(pid: Option<u32>) -> &'static str {
+    let Some(pid) = pid else {
+        return if supervisor::status().supervising {
+            "manager"
+        } else {
+            "external"
+        };
+    };
+    if systemd::owns_pid(pid) {
+        return "systemd";
+    }
+    let supervised = supervisor::status().pid;
+    // Windows 上受守护的是 cmd /c 包装进程，监听端口的是其子进程
+    if supervised == Some(pid) || (supervised.is_some() && platform::is_windows()) {
+        "manager"
+    } else {
+        "external"
+    }
+}
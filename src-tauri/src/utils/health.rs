@@ -0,0 +1,352 @@
+use crate::models::{GatewayHealth, HealthState};
+use crate::utils::{config_repo, gateway};
+use chrono::Local;
+use log::debug;
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// 单次探测的连接 / 读写超时
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 依次尝试的健康检查路径，404 时尝试下一个
+const HEALTH_PATHS: &[&str] = &["/health", "/healthz", "/"];
+
+/// 探测结果的缓存时间：略短于状态检测线程的轮询间隔，
+/// 每次轮询探测一次，期间前端刷新状态直接复用结果
+const CACHE_TTL: Duration = Duration::from_secs(4);
+
+/// 响应最多读取的字节数
+const MAX_RESPONSE_BYTES: usize = 64 * 1024;
+
+/// 视为异常的渠道 / 服务状态
+const BAD_STATUSES: &[&str] = &["error", "failed", "degraded", "unhealthy", "disconnected"];
+
+/// 视为尚未就绪的服务状态
+const PENDING_STATUSES: &[&str] = &["starting", "loading", "initializing", "booting"];
+
+static CACHE: OnceLock<Mutex<Option<CachedProbe>>> = OnceLock::new();
+
+/// 最近一次探测结果
+struct CachedProbe {
+    addr: SocketAddr,
+    at: Instant,
+    health: GatewayHealth,
+}
+
+/// 一次 HTTP 响应
+struct HttpResponse {
+    status: u16,
+    body: String,
+}
+
+/// 发送一次 GET 请求（HTTP/1.1，Connection: close）
+fn http_get(addr: &SocketAddr, path: &str, token: Option<&str>) -> Result<HttpResponse, String> {
+    let mut stream =
+        TcpStream::connect_timeout(addr, PROBE_TIMEOUT).map_err(|e| format!("连接失败: {}", e))?;
+    let _ = stream.set_read_timeout(Some(PROBE_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(PROBE_TIMEOUT));
+
+    let mut request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nAccept: application/json\r\nUser-Agent: openclaw-manager\r\nConnection: close\r\n",
+        path, addr
+    );
+    if let Some(token) = token {
+        request.push_str(&format!("Authorization: Bearer {}\r\n", token));
+    }
+    request.push_str("\r\n");
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| format!("发送请求失败: {}", e))?;
+
+    let mut raw = Vec::new();
+    let mut buf = [0u8; 8192];
+    loop {
+        match stream.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => {
+                raw.extend_from_slice(&buf[..n]);
+                if raw.len() >= MAX_RESPONSE_BYTES {
+                    break;
+                }
+            }
+            // 已收到响应头时，超时视为读取结束
+            Err(_) if raw.windows(4).any(|w| w == b"\r\n\r\n") => break,
+            Err(e) => return Err(format!("等待响应超时: {}", e)),
+        }
+    }
+    parse_response(&raw).ok_or_else(|| "响应不是有效的 HTTP".to_string())
+}
+
+fn parse_response(raw: &[u8]) -> Option<HttpResponse> {
+    let text = String::from_utf8_lossy(raw);
+    let (head, body) = text.split_once("\r\n\r\n")?;
+    let status = head
+        .lines()
+        .next()?
+        .split_whitespace()
+        .nth(1)?
+        .parse::<u16>()
+        .ok()?;
+    let chunked = head.lines().any(|l| {
+        let l = l.to_ascii_lowercase();
+        l.starts_with("transfer-encoding:") && l.contains("chunked")
+    });
+    let body = if chunked {
+        decode_chunked(body)
+    } else {
+        body.to_string()
+    };
+    Some(HttpResponse { status, body })
+}
+
+/// 解码 chunked 响应体（只用于读取 JSON，格式异常时尽量返回已解析的部分）
+fn decode_chunked(body: &str) -> String {
+    let mut out = String::new();
+    let mut rest = body;
+    while let Some((size_line, after)) = rest.split_once("\r\n") {
+        let size = size_line.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size, 16).unwrap_or(0);
+        if size == 0 {
+            break;
+        }
+        let Some(chunk) = after.get(..size) else {
+            out.push_str(after);
+            break;
+        };
+        out.push_str(chunk);
+        rest = after
+            .get(size..)
+            .unwrap_or_default()
+            .trim_start_matches("\r\n");
+    }
+    out
+}
+
+fn status_of(value: &Value) -> Option<String> {
+    value
+        .get("status")
+        .or_else(|| value.get("state"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_lowercase())
+}
+
+/// 渠道是否异常
+fn channel_failing(value: &Value) -> bool {
+    if value.get("ok").and_then(|v| v.as_bool()) == Some(false)
+        || value.get("connected").and_then(|v| v.as_bool()) == Some(false)
+    {
+        return true;
+    }
+    if value
+        .get("error")
+        .is_some_and(|e| !e.is_null() && e != &Value::Bool(false))
+    {
+        return true;
+    }
+    status_of(value).is_some_and(|s| BAD_STATUSES.contains(&s.as_str()))
+}
+
+/// 从健康检查 JSON 中找出异常渠道
+fn failing_channels(body: &Value) -> Vec<String> {
+    let channels = match body.get("channels") {
+        Some(c) => c,
+        None => return Vec::new(),
+    };
+    let mut failing: Vec<String> = match channels {
+        Value::Object(map) => map
+            .iter()
+            .filter(|(_, v)| channel_failing(v))
+            .map(|(k, _)| k.clone())
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .filter(|v| channel_failing(v))
+            .map(|v| {
+                v.get("id")
+                    .or_else(|| v.get("name"))
+                    .and_then(|n| n.as_str())
+                    .unwrap_or("unknown")
+                    .to_string()
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    failing.sort();
+    failing
+}
+
+/// 根据 HTTP 响应判定健康状态，返回 (状态, 异常渠道, 说明)
+fn interpret(response: &HttpResponse) -> (HealthState, Vec<String>, String) {
+    if response.status == 401 || response.status == 403 {
+        return (
+            HealthState::AuthRequired,
+            Vec::new(),
+            format!("HTTP {}（需要认证，无法读取健康状态）", response.status),
+        );
+    }
+
+    let json: Option<Value> = serde_json::from_str(response.body.trim()).ok();
+    if response.status >= 500 {
+        let channels = json.as_ref().map(failing_channels).unwrap_or_default();
+        if !channels.is_empty() {
+            return (
+                HealthState::Degraded,
+                channels,
+                format!("HTTP {}，部分渠道异常", response.status),
+            );
+        }
+        return (
+            HealthState::NotReady,
+            Vec::new(),
+            format!("HTTP {}，Gateway 尚未就绪", response.status),
+        );
+    }
+
+    // 非 JSON 响应只说明 HTTP 服务在运行，无法判断是否就绪
+    let Some(body) = json else {
+        return (
+            HealthState::Unknown,
+            Vec::new(),
+            "HTTP 服务已响应（无健康检查端点）".to_string(),
+        );
+    };
+
+    let status = status_of(&body);
+    if body.get("ready").and_then(|v| v.as_bool()) == Some(false)
+        || status
+            .as_deref()
+            .is_some_and(|s| PENDING_STATUSES.contains(&s))
+    {
+        return (
+            HealthState::NotReady,
+            Vec::new(),
+            "Gateway 正在加载（插件 / 渠道尚未就绪）".to_string(),
+        );
+    }
+
+    let channels = failing_channels(&body);
+    if !channels.is_empty() {
+        let message = format!("渠道异常: {}", channels.join(", "));
+        return (HealthState::Degraded, channels, message);
+    }
+    if body.get("ok").and_then(|v| v.as_bool()) == Some(false)
+        || status.as_deref().is_some_and(|s| BAD_STATUSES.contains(&s))
+    {
+        return (
+            HealthState::Degraded,
+            Vec::new(),
+            format!("Gateway 报告异常状态: {}", status.unwrap_or_default()),
+        );
+    }
+
+    (
+        HealthState::Ready,
+        Vec::new(),
+        format!("HTTP {}", response.status),
+    )
+}
+
+/// 探测 Gateway 的 HTTP 服务，区分 端口未打开 / 未就绪 / 就绪 / 需要认证 / 渠道异常
+/// 按绑定模式选择连接地址（如 tailnet 只监听 Tailscale 地址）；会阻塞数秒，async 中需放入 spawn_blocking
+pub fn probe(port: u16, bind: &str) -> GatewayHealth {
+    let addr = SocketAddr::new(gateway::probe_address(bind), port);
+    let health = probe_addr(addr);
+    let mut cache = CACHE
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    *cache = Some(CachedProbe {
+        addr,
+        at: Instant::now(),
+        health: health.clone(),
+    });
+    health
+}
+
+/// 与 probe 相同，但在缓存时间内复用上一次对同一地址的探测结果
+pub fn probe_cached(port: u16, bind: &str) -> GatewayHealth {
+    let addr = SocketAddr::new(gateway::probe_address(bind), port);
+    {
+        let cache = CACHE
+            .get_or_init(|| Mutex::new(None))
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(cached) = cache
+            .as_ref()
+            .filter(|c| c.addr == addr && c.at.elapsed() < CACHE_TTL)
+        {
+            return cached.health.clone();
+        }
+    }
+    probe(port, bind)
+}
+
+fn probe_addr(addr: SocketAddr) -> GatewayHealth {
+    let port = addr.port();
+    // 开启 token 认证时探测需要携带
+    let token = config_repo::gateway_token();
+    let mut health = GatewayHealth {
+        state: HealthState::Unreachable,
+        latency_ms: None,
+        http_status: None,
+        endpoint: None,
+        degraded_channels: Vec::new(),
+        message: String::new(),
+        checked_at: Local::now().to_rfc3339(),
+    };
+
+    let started = Instant::now();
+    if let Err(e) = TcpStream::connect_timeout(&addr, PROBE_TIMEOUT) {
+        health.message = format!("端口 {} 无法连接: {}", port, e);
+        return health;
+    }
+
+    // 启用 HTTPS 后无法用明文 HTTP 探测，端口可连接不代表已就绪，状态记为未知
+    if config_repo::load().is_ok_and(|c| c.gateway_tls_enabled()) {
+        health.state = HealthState::Unknown;
+        health.latency_ms = Some(started.elapsed().as_millis() as u64);
+        health.message = "已启用 HTTPS，端口可连接，无法探测就绪状态".to_string();
+        return health;
+    }
+
+    for path in HEALTH_PATHS {
+        let started = Instant::now();
+        match http_get(&addr, path, token.as_deref()) {
+            Ok(response) if response.status == 404 => {
+                health.latency_ms = Some(started.elapsed().as_millis() as u64);
+            }
+            Ok(response) => {
+                let (state, channels, message) = interpret(&response);
+                health.state = state;
+                health.latency_ms = Some(started.elapsed().as_millis() as u64);
+                health.http_status = Some(response.status);
+                health.endpoint = Some(path.to_string());
+                health.degraded_channels = channels;
+                health.message = message;
+                debug!(
+                    "[健康检查] {}{} -> {:?} ({}ms)",
+                    port,
+                    path,
+                    health.state,
+                    health.latency_ms.unwrap_or(0)
+                );
+                return health;
+            }
+            Err(e) => {
+                // 端口已打开但 HTTP 无响应，通常是 Gateway 仍在加载插件
+                health.state = HealthState::NotReady;
+                health.message = format!("端口已打开但 HTTP 无响应: {}", e);
+                return health;
+            }
+        }
+    }
+
+    // 所有路径都是 404：HTTP 服务在运行，但没有健康检查端点，无法判断是否就绪
+    health.state = HealthState::Unknown;
+    health.http_status = Some(404);
+    health.message = "HTTP 服务已响应（无健康检查端点）".to_string();
+    health
+}
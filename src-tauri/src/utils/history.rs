@@ -0,0 +1,235 @@
+use crate::models::{
+    EventTrigger, ServiceAction, ServiceEventRecord, ServiceState, UptimeReport, UptimeSegment,
+};
+use crate::utils::{file, platform};
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use log::{info, warn};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+/// 事件保留天数，文件超过大小上限时清理更早的记录
+const RETENTION_DAYS: i64 = 90;
+
+/// 事件历史文件的大小上限
+const MAX_FILE_BYTES: u64 = 2 * 1024 * 1024;
+
+/// 串行化追加写入，避免多线程写入的行互相交错
+static WRITE_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+/// 清理事件历史：删除保留期之前的记录（保留其中最后一条状态记录，作为之后时间线的起始状态），
+/// 仍超过大小上限一半时只保留最新的记录，返回删除的条数
+fn compact(path: &Path) -> Result<usize, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let cutoff = Local::now() - ChronoDuration::days(RETENTION_DAYS);
+    let lines: Vec<(&str, Option<ServiceEventRecord>)> = content
+        .lines()
+        .map(|line| (line, serde_json::from_str(line).ok()))
+        .collect();
+    let is_recent =
+        |record: &ServiceEventRecord| parse_time(&record.timestamp).is_some_and(|t| t >= cutoff);
+    let baseline = lines.iter().rposition(|(_, record)| {
+        record
+            .as_ref()
+            .is_some_and(|r| r.state.is_some() && !is_recent(r))
+    });
+    let mut kept: Vec<&str> = lines
+        .iter()
+        .enumerate()
+        .filter(|(i, (_, record))| Some(*i) == baseline || record.as_ref().is_some_and(is_recent))
+        .map(|(_, (line, _))| *line)
+        .collect();
+
+    let budget = (MAX_FILE_BYTES / 2) as usize;
+    let mut size = 0;
+    let mut start = kept.len();
+    while start > 0 && size + kept[start - 1].len() < budget {
+        start -= 1;
+        size += kept[start].len() + 1;
+    }
+    kept.drain(..start);
+
+    let mut output = kept.join("\n");
+    if !output.is_empty() {
+        output.push('\n');
+    }
+    file::write_file(&path.display().to_string(), &output).map_err(|e| e.to_string())?;
+    Ok(lines.len() - kept.len())
+}
+
+fn append(record: &ServiceEventRecord) {
+    let lock = WRITE_LOCK.get_or_init(|| Mutex::new(()));
+    let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+
+    let path = platform::get_event_history_path();
+    let result = serde_json::to_string(record)
+        .map_err(|e| e.to_string())
+        .and_then(|line| {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .map_err(|e| e.to_string())?;
+            writeln!(file, "{}", line).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("[事件历史] 写入 {} 失败: {}", path.display(), e);
+        return;
+    }
+
+    if fs::metadata(&path).is_ok_and(|m| m.len() > MAX_FILE_BYTES) {
+        match compact(&path) {
+            Ok(removed) => info!("[事件历史] ✓ 已清理 {} 条旧记录", removed),
+            Err(e) => warn!("[事件历史] ✗ 清理 {} 失败: {}", path.display(), e),
+        }
+    }
+}
+
+/// 记录一次启停操作
+pub fn record(
+    action: ServiceAction,
+    trigger: EventTrigger,
+    pid: Option<u32>,
+    success: bool,
+    message: &str,
+) {
+    append(&ServiceEventRecord {
+        timestamp: Local::now().to_rfc3339(),
+        action,
+        trigger,
+        pid,
+        success,
+        state: None,
+        message: message.to_string(),
+    });
+}
+
+/// 记录检测到的服务状态变化
+pub fn record_state(state: ServiceState, pid: Option<u32>, message: &str) {
+    append(&ServiceEventRecord {
+        timestamp: Local::now().to_rfc3339(),
+        action: ServiceAction::StateChanged,
+        trigger: EventTrigger::Watchdog,
+        pid,
+        success: true,
+        state: Some(state),
+        message: message.to_string(),
+    });
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Local))
+}
+
+/// 读取全部事件（按时间顺序，忽略无法解析的行）
+fn read_all() -> Vec<(DateTime<Local>, ServiceEventRecord)> {
+    let content = fs::read_to_string(platform::get_event_history_path()).unwrap_or_default();
+    let mut events: Vec<(DateTime<Local>, ServiceEventRecord)> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<ServiceEventRecord>(line).ok())
+        .filter_map(|record| parse_time(&record.timestamp).map(|t| (t, record)))
+        .collect();
+    events.sort_by_key(|(t, _)| *t);
+    events
+}
+
+/// 查询时间范围内的事件（最新的在前）
+pub fn events(
+    since: Option<DateTime<Local>>,
+    until: Option<DateTime<Local>>,
+    limit: usize,
+) -> Vec<ServiceEventRecord> {
+    read_all()
+        .into_iter()
+        .rev()
+        .filter(|(t, _)| since.is_none_or(|s| *t >= s) && until.is_none_or(|u| *t <= u))
+        .take(limit)
+        .map(|(_, record)| record)
+        .collect()
+}
+
+fn is_up(state: ServiceState) -> bool {
+    matches!(state, ServiceState::Running | ServiceState::Degraded)
+}
+
+/// 统计时间范围内的可用率与状态时间线
+/// 以状态变化记录为准；Manager 未运行期间沿用最后一次记录的状态
+pub fn uptime(since: DateTime<Local>, until: DateTime<Local>) -> UptimeReport {
+    let events = read_all();
+
+    let mut segments: Vec<UptimeSegment> = Vec::new();
+    let mut push = |state: ServiceState, start: DateTime<Local>, end: DateTime<Local>| {
+        if end <= start {
+            return;
+        }
+        let duration_secs = (end - start).num_seconds().max(0) as u64;
+        match segments.last_mut() {
+            Some(last) if last.state == state => {
+                last.end = end.to_rfc3339();
+                last.duration_secs += duration_secs;
+            }
+            _ => segments.push(UptimeSegment {
+                state,
+                start: start.to_rfc3339(),
+                end: end.to_rfc3339(),
+                duration_secs,
+            }),
+        }
+    };
+
+    // 窗口开始时的状态取窗口之前最后一次记录
+    let mut current: Option<(ServiceState, DateTime<Local>)> = events
+        .iter()
+        .filter(|(t, _)| *t < since)
+        .filter_map(|(_, r)| r.state)
+        .next_back()
+        .map(|state| (state, since));
+    let mut crashes = 0;
+    let mut restarts = 0;
+
+    for (t, record) in events.iter().filter(|(t, _)| *t >= since && *t < until) {
+        match record.action {
+            ServiceAction::Crash => crashes += 1,
+            ServiceAction::Start | ServiceAction::Restart | ServiceAction::AutoRestart
+                if record.success =>
+            {
+                restarts += 1
+            }
+            _ => {}
+        }
+        let Some(state) = record.state else {
+            continue;
+        };
+        if let Some((prev, start)) = current {
+            push(prev, start, *t);
+        }
+        current = Some((state, *t));
+    }
+    if let Some((state, start)) = current {
+        push(state, start, until);
+    }
+
+    let observed_secs: u64 = segments.iter().map(|s| s.duration_secs).sum();
+    let up_secs: u64 = segments
+        .iter()
+        .filter(|s| is_up(s.state))
+        .map(|s| s.duration_secs)
+        .sum();
+    UptimeReport {
+        since: since.to_rfc3339(),
+        until: until.to_rfc3339(),
+        observed_secs,
+        up_secs,
+        uptime_percent: (observed_secs > 0)
+            .then(|| (up_secs as f64 * 10000.0 / observed_secs as f64).round() / 100.0),
+        crashes,
+        restarts,
+        segments,
+    }
+}
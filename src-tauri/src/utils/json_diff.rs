@@ -0,0 +1,49 @@
+use crate::models::{ChangeKind, JsonChange};
+use serde_json::Value;
+
+/// 对比两份 JSON，列出变化的路径（a.b.c，按路径排序）
+/// 对象逐个键展开，数组整体比较；max_depth 限制展开的层级，更深的变化归并到该层
+pub fn diff(old: &Value, new: &Value, max_depth: Option<usize>) -> Vec<JsonChange> {
+    let mut out = Vec::new();
+    walk(Some(old), Some(new), "", 0, max_depth, &mut out);
+    out
+}
+
+fn walk(
+    old: Option<&Value>,
+    new: Option<&Value>,
+    path: &str,
+    depth: usize,
+    max_depth: Option<usize>,
+    out: &mut Vec<JsonChange>,
+) {
+    let kind = match (old, new) {
+        (Some(a), Some(b)) if a == b => return,
+        (Some(Value::Object(a)), Some(Value::Object(b)))
+            if max_depth.is_none_or(|max| depth < max) =>
+        {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                walk(a.get(key), b.get(key), &child, depth + 1, max_depth, out);
+            }
+            return;
+        }
+        (None, None) => return,
+        (None, Some(_)) => ChangeKind::Added,
+        (Some(_), None) => ChangeKind::Removed,
+        _ => ChangeKind::Modified,
+    };
+    out.push(JsonChange {
+        path: path.to_string(),
+        kind,
+        old: old.cloned(),
+        new: new.cloned(),
+    });
+}
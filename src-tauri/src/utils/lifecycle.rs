@@ -0,0 +1,276 @@
+use crate::models::{StopPhase, StopProgressEvent, StopResult, StopSettings, StopStep};
+use crate::utils::{gateway, settings, shell, systemd};
+use log::{info, warn};
+use std::process::{Command, Output};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+/// Windows CREATE_NO_WINDOW 标志，用于隐藏控制台窗口
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// 推送给前端的停止进度事件名
+pub const STOP_PROGRESS_EVENT: &str = "service-stop-progress";
+
+/// 一次停止流程的上下文
+struct StopRun<'a> {
+    app: &'a AppHandle,
+    config: StopSettings,
+    started: Instant,
+    pid: Option<u32>,
+    steps: Vec<StopStep>,
+}
+
+impl StopRun<'_> {
+    fn elapsed_ms(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    fn progress(&self, phase: StopPhase, message: &str) {
+        info!("[停止] [{:?}] {}", phase, message);
+        let event = StopProgressEvent {
+            phase,
+            step: self.steps.len() as u32 + 1,
+            message: message.to_string(),
+            pid: self.pid,
+            elapsed_ms: self.elapsed_ms(),
+        };
+        if let Err(e) = self.app.emit(STOP_PROGRESS_EVENT, event) {
+            warn!("[停止] 推送进度事件失败: {}", e);
+        }
+    }
+
+    /// 在 grace 时间内轮询进程是否退出
+    async fn wait_for_exit(&self, grace: Duration) -> bool {
+        let poll = Duration::from_millis(self.config.poll_interval_ms.max(50));
+        let deadline = Instant::now() + grace;
+        loop {
+            if !is_running().await {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(poll).await;
+        }
+    }
+
+    /// 执行一个阶段：在阻塞线程池中运行停止动作，然后等待进程退出
+    async fn run_phase<F>(
+        &mut self,
+        phase: StopPhase,
+        grace_secs: u64,
+        message: &str,
+        action: F,
+    ) -> bool
+    where
+        F: FnOnce() -> Result<String, String> + Send + 'static,
+    {
+        self.progress(phase, message);
+        let phase_started = Instant::now();
+        let outcome = tauri::async_runtime::spawn_blocking(action)
+            .await
+            .unwrap_or_else(|e| Err(format!("执行失败: {}", e)));
+        let stopped = self.wait_for_exit(Duration::from_secs(grace_secs)).await;
+
+        let (action_ok, output) = match outcome {
+            Ok(out) => (true, out),
+            Err(err) => (false, err),
+        };
+        info!(
+            "[停止] [{:?}] 命令{}，{}",
+            phase,
+            if action_ok { "成功" } else { "失败" },
+            if stopped {
+                "进程已退出"
+            } else {
+                "等待超时，进程仍在运行"
+            }
+        );
+        self.steps.push(StopStep {
+            phase,
+            action_ok,
+            output,
+            stopped,
+            elapsed_ms: phase_started.elapsed().as_millis() as u64,
+        });
+        stopped
+    }
+
+    fn finish(self, stopped: bool, message: String) -> StopResult {
+        StopResult {
+            stopped,
+            phase: self.steps.last().map(|s| s.phase),
+            pid: self.pid,
+            elapsed_ms: self.elapsed_ms(),
+            steps: self.steps,
+            message,
+        }
+    }
+}
+
+async fn is_running() -> bool {
+    tauri::async_runtime::spawn_blocking(gateway::is_running)
+        .await
+        .unwrap_or(true)
+}
+
+/// 停止 Gateway：按 gateway stop → stop --force → SIGTERM → SIGKILL 逐级升级，
+/// 每级执行后在配置的等待时间内轮询退出；systemd 管理的进程只通过 systemctl 停止。
+/// 端口被其他程序占用时返回错误，不会终止非 Gateway 进程。
+pub async fn stop_gateway(app: &AppHandle) -> Result<StopResult, String> {
+    let status = gateway::collect_status_async().await?;
+
+    if let Some(owner) = status.port_owner.as_ref().filter(|o| !o.is_gateway) {
+        return Err(format!(
+            "端口 {} 被其他程序 {} 占用，它不是 OpenClaw Gateway，已拒绝终止",
+            status.port,
+            owner.describe()
+        ));
+    }
+
+    let mut run = StopRun {
+        app,
+        config: settings::load().stop,
+        started: Instant::now(),
+        pid: status.pid,
+        steps: Vec::new(),
+    };
+
+    if !status.running {
+        run.progress(StopPhase::AlreadyStopped, "服务未运行");
+        return Ok(StopResult {
+            phase: Some(StopPhase::AlreadyStopped),
+            ..run.finish(true, "服务已停止".to_string())
+        });
+    }
+
+    // systemd 管理的进程必须通过 systemctl 停止，否则会被 systemd 重新拉起
+    if status.managed_by.as_deref() == Some("systemd") {
+        let grace = run.config.graceful_secs;
+        let stopped = run
+            .run_phase(StopPhase::Systemd, grace, "systemctl --user stop", || {
+                systemd::stop().map(|_| String::new())
+            })
+            .await;
+        let message = if stopped {
+            "服务已停止 (systemd)".to_string()
+        } else {
+            format!("systemd 停止超时，PID: {:?}", run.pid)
+        };
+        return Ok(run.finish(stopped, message));
+    }
+
+    let grace = run.config.graceful_secs;
+    if run
+        .run_phase(StopPhase::Graceful, grace, "openclaw gateway stop", || {
+            shell::run_openclaw(&["gateway", "stop"])
+        })
+        .await
+    {
+        return Ok(run.finish(true, "服务已停止".to_string()));
+    }
+
+    let grace = run.config.force_secs;
+    if run
+        .run_phase(
+            StopPhase::Force,
+            grace,
+            "openclaw gateway stop --force",
+            || shell::run_openclaw(&["gateway", "stop", "--force"]),
+        )
+        .await
+    {
+        return Ok(run.finish(true, "服务已停止".to_string()));
+    }
+
+    let Some(pid) = run.pid else {
+        let message = "无法获取 Gateway PID，无法继续终止进程".to_string();
+        return Ok(run.finish(false, message));
+    };
+
+    // 终止进程前再次确认身份，避免 PID 已被其他程序复用
+    let escalations = [
+        (
+            StopPhase::Terminate,
+            run.config.terminate_secs,
+            kill_process as fn(u32) -> Result<String, String>,
+        ),
+        (StopPhase::Kill, run.config.kill_secs, force_kill_process),
+    ];
+    for (phase, grace, kill) in escalations {
+        if !gateway::is_gateway_process(pid) {
+            let message = format!("PID {} 已不是 OpenClaw Gateway，已拒绝终止", pid);
+            warn!("[停止] {}", message);
+            return Ok(run.finish(!is_running().await, message));
+        }
+        let message = match phase {
+            StopPhase::Kill => format!("强制终止进程 PID {}", pid),
+            _ => format!("终止进程 PID {}", pid),
+        };
+        if run
+            .run_phase(phase, grace, &message, move || kill(pid))
+            .await
+        {
+            return Ok(run.finish(true, format!("服务已停止 ({})", message)));
+        }
+    }
+
+    Ok(run.finish(false, format!("无法停止服务，PID: {}", pid)))
+}
+
+fn command_result(output: Output) -> Result<String, String> {
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() {
+        Ok(stdout)
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// 终止进程 (SIGTERM)
+fn kill_process(pid: u32) -> Result<String, String> {
+    #[cfg(unix)]
+    {
+        Command::new("kill")
+            .arg(pid.to_string())
+            .output()
+            .map_err(|e| format!("kill 命令失败: {}", e))
+            .and_then(command_result)
+    }
+
+    #[cfg(windows)]
+    {
+        let mut cmd = Command::new("taskkill");
+        cmd.args(["/PID", &pid.to_string()]);
+        cmd.creation_flags(CREATE_NO_WINDOW);
+        cmd.output()
+            .map_err(|e| format!("taskkill 命令失败: {}", e))
+            .and_then(command_result)
+    }
+}
+
+/// 强制终止进程 (SIGKILL / taskkill /F)
+pub fn force_kill_process(pid: u32) -> Result<String, String> {
+    #[cfg(unix)]
+    {
+        Command::new("kill")
+            .args(["-9", &pid.to_string()])
+            .output()
+            .map_err(|e| format!("kill -9 命令失败: {}", e))
+            .and_then(command_result)
+    }
+
+    #[cfg(windows)]
+    {
+        let mut cmd = Command::new("taskkill");
+        cmd.args(["/F", "/PID", &pid.to_string()]);
+        cmd.creation_flags(CREATE_NO_WINDOW);
+        cmd.output()
+            .map_err(|e| format!("taskkill /F 命令失败: {}", e))
+            .and_then(command_result)
+    }
+}
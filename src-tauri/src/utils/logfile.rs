@@ -0,0 +1,435 @@
+use crate::models::{GatewayLogEntry, LogSettings};
+use crate::utils::{file, platform, settings};
+use chrono::{DateTime, FixedOffset, Local};
+use log::{debug, warn};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Once, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+/// 日志级别（由低到高）
+pub const LOG_LEVELS: &[&str] = &["debug", "info", "warn", "error"];
+
+/// 单行日志的最大长度，超出部分截断，避免异常输出撑爆日志
+const MAX_LINE_BYTES: usize = 16 * 1024;
+
+/// 检查 Gateway 输出是否需要轮转的间隔
+const ROTATE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+static WRITER: OnceLock<Mutex<Option<RotatingWriter>>> = OnceLock::new();
+
+static ROTATE_WATCH: Once = Once::new();
+
+/// 按大小轮转的日志写入器
+/// Gateway 进程直接追加写入同一个文件，因此采用复制后截断的方式轮转：
+/// gateway.log 写满后复制为 gateway.log.1（旧文件依次后移，超出保留个数的删除），再清空 gateway.log
+struct RotatingWriter {
+    path: PathBuf,
+    file: File,
+    max_bytes: u64,
+    max_files: u32,
+}
+
+impl RotatingWriter {
+    fn open(path: PathBuf, config: &LogSettings) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            path,
+            file,
+            max_bytes: config.max_file_size_mb.max(1).saturating_mul(1024 * 1024),
+            max_files: config.max_files,
+        })
+    }
+
+    /// 当前文件大小（包含 Gateway 进程直接写入的内容）
+    fn size(&self) -> u64 {
+        self.file.metadata().map(|m| m.len()).unwrap_or(0)
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let size = self.size();
+        if size > 0 && size.saturating_add(line.len() as u64 + 1) > self.max_bytes {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)
+    }
+
+    fn rotate_if_needed(&mut self) -> io::Result<()> {
+        if self.size() > self.max_bytes {
+            self.rotate()?;
+        }
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.max_files > 0 {
+            let _ = fs::remove_file(rotated_path(&self.path, self.max_files));
+            for i in (1..self.max_files).rev() {
+                let from = rotated_path(&self.path, i);
+                if from.exists() {
+                    fs::rename(&from, rotated_path(&self.path, i + 1))?;
+                }
+            }
+            fs::copy(&self.path, rotated_path(&self.path, 1))?;
+        }
+        // 以追加模式打开的文件在截断后会从头继续写入，Gateway 无需重新打开
+        self.file.set_len(0)?;
+        debug!("[日志] 已轮转 {}", self.path.display());
+        Ok(())
+    }
+}
+
+fn rotated_path(path: &Path, index: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+fn writer() -> &'static Mutex<Option<RotatingWriter>> {
+    WRITER.get_or_init(|| Mutex::new(None))
+}
+
+/// 重新打开日志文件（每次启动 Gateway 时调用，使日志配置的修改生效）
+pub fn reopen() {
+    ROTATE_WATCH.call_once(|| {
+        thread::spawn(rotate_watch_loop);
+    });
+    let path = PathBuf::from(platform::get_log_file_path());
+    let config = settings::load().logs;
+    let mut guard = writer().lock().unwrap_or_else(|e| e.into_inner());
+    *guard = match RotatingWriter::open(path.clone(), &config) {
+        Ok(w) => Some(w),
+        Err(e) => {
+            warn!("[日志] 无法打开日志文件 {}: {}", path.display(), e);
+            None
+        }
+    };
+}
+
+/// 定时检查日志大小：Gateway 直接写入文件，Manager 不写日志时也需要按大小轮转
+fn rotate_watch_loop() {
+    loop {
+        thread::sleep(ROTATE_CHECK_INTERVAL);
+        let mut guard = writer().lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(w) = guard.as_mut() {
+            if let Err(e) = w.rotate_if_needed() {
+                warn!("[日志] 轮转日志文件失败: {}", e);
+            }
+        }
+    }
+}
+
+/// 打开日志文件供 Gateway 进程的 stdout / stderr 直接追加写入
+/// 输出不经过 Manager 中转，Manager 退出后 Gateway 仍可正常写日志
+pub fn open_child_output() -> io::Result<File> {
+    let path = PathBuf::from(platform::get_log_file_path());
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new().create(true).append(true).open(&path)
+}
+
+/// 追加一行 Manager 日志，格式：`<RFC 3339 时间> [manager] <内容>`
+/// stream 为 manager（Manager 自身记录的启停事件）；Gateway 自身的输出直接写入文件，不带此前缀
+pub fn write_line(stream: &str, line: &str) {
+    let mut guard = writer().lock().unwrap_or_else(|e| e.into_inner());
+    if guard.is_none() {
+        drop(guard);
+        reopen();
+        guard = writer().lock().unwrap_or_else(|e| e.into_inner());
+    }
+    let Some(w) = guard.as_mut() else {
+        return;
+    };
+
+    let content = strip_ansi_codes(line.trim_end_matches(['\r', '\n']));
+    let formatted = format!("{} [{}] {}", Local::now().to_rfc3339(), stream, content);
+    if let Err(e) = w.write_line(&formatted) {
+        warn!("[日志] 写入日志文件失败: {}", e);
+        *guard = None;
+    }
+}
+
+/// 日志文件的增量读取位置，实时日志流据此读取新写入的行
+pub struct LogCursor {
+    path: PathBuf,
+    offset: u64,
+    /// 上次读到的不完整行
+    partial: Vec<u8>,
+}
+
+impl LogCursor {
+    /// 从日志文件当前末尾开始跟踪
+    pub fn at_end() -> Self {
+        let path = PathBuf::from(platform::get_log_file_path());
+        let offset = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Self {
+            path,
+            offset,
+            partial: Vec::new(),
+        }
+    }
+
+    /// 读取上次以来新写入的完整行；文件被轮转截断后从头开始读取
+    pub fn read_new_lines(&mut self) -> Vec<String> {
+        let Ok(mut file) = File::open(&self.path) else {
+            return Vec::new();
+        };
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        if len < self.offset {
+            self.offset = 0;
+            self.partial.clear();
+        }
+        if len == self.offset || file.seek(SeekFrom::Start(self.offset)).is_err() {
+            return Vec::new();
+        }
+
+        let mut buf = Vec::new();
+        match file.take(len - self.offset).read_to_end(&mut buf) {
+            Ok(n) => self.offset += n as u64,
+            Err(e) => {
+                debug!("[日志] 读取 {} 失败: {}", self.path.display(), e);
+                return Vec::new();
+            }
+        }
+        self.partial.extend_from_slice(&buf);
+
+        let Some(end) = self.partial.iter().rposition(|&b| b == b'\n') else {
+            self.partial.truncate(MAX_LINE_BYTES);
+            return Vec::new();
+        };
+        let rest = self.partial.split_off(end + 1);
+        let lines = self
+            .partial
+            .split(|&b| b == b'\n')
+            .filter(|l| !l.is_empty())
+            .map(|l| String::from_utf8_lossy(&l[..l.len().min(MAX_LINE_BYTES)]).into_owned())
+            .collect();
+        self.partial = rest;
+        lines
+    }
+}
+
+/// 去除 ANSI 转义序列（颜色代码等）
+pub fn strip_ansi_codes(input: &str) -> String {
+    // 匹配 ANSI 转义序列: ESC[ ... m 或 ESC[ ... 其他控制字符
+    let mut result = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // 跳过 ESC[...m 序列
+            if chars.peek() == Some(&'[') {
+                chars.next(); // 跳过 '['
+                              // 跳过直到遇到字母
+                while let Some(&next) = chars.peek() {
+                    chars.next();
+                    if next.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// 日志级别排序值，未知级别返回 None
+pub fn level_rank(level: &str) -> Option<usize> {
+    LOG_LEVELS.iter().position(|l| l.eq_ignore_ascii_case(level))
+}
+
+/// 从消息中识别日志级别
+fn detect_level(message: &str) -> Option<&'static str> {
+    let head: String = message.chars().take(48).collect::<String>().to_lowercase();
+    let tokens = head
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .filter(|t| !t.is_empty());
+    for token in tokens {
+        match token {
+            "error" | "err" | "fatal" | "panic" => return Some("error"),
+            "warn" | "warning" => return Some("warn"),
+            "info" => return Some("info"),
+            "debug" | "trace" | "verbose" => return Some("debug"),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 解析一行日志文件内容
+pub fn parse_log_line(raw: &str) -> GatewayLogEntry {
+    // Gateway 输出直接写入文件，可能带有颜色代码
+    let raw = strip_ansi_codes(raw);
+    let mut rest = raw.trim_end();
+
+    // Manager 写入的前缀：<时间> [stream]
+    let mut timestamp = None;
+    if let Some((head, tail)) = rest.split_once(' ') {
+        if DateTime::parse_from_rfc3339(head).is_ok() {
+            timestamp = Some(head.to_string());
+            rest = tail;
+        }
+    }
+    let mut stream = None;
+    for name in ["stdout", "stderr", "manager"] {
+        if let Some(tail) = rest.strip_prefix(&format!("[{}] ", name)) {
+            stream = Some(name.to_string());
+            rest = tail;
+            break;
+        }
+    }
+
+    // Gateway 自身输出可能带有时间戳和 [子系统] 前缀（Gateway 输出直接写入文件，没有 Manager 前缀）
+    if let Some((head, tail)) = rest.split_once(' ') {
+        if DateTime::parse_from_rfc3339(head).is_ok() {
+            timestamp.get_or_insert_with(|| head.to_string());
+            rest = tail;
+        }
+    }
+    let mut subsystem = None;
+    if let Some(tail) = rest.strip_prefix('[') {
+        if let Some((name, after)) = tail.split_once(']') {
+            if !name.is_empty() && !name.contains(' ') && level_rank(name).is_none() {
+                subsystem = Some(name.to_string());
+                rest = after.trim_start();
+            }
+        }
+    }
+
+    let level = detect_level(rest).unwrap_or("info").to_string();
+
+    GatewayLogEntry {
+        timestamp,
+        level,
+        subsystem,
+        stream,
+        message: rest.to_string(),
+        raw: raw.to_string(),
+    }
+}
+
+/// 日志文件列表（从新到旧，仅包含存在的文件）
+pub fn log_files() -> Vec<PathBuf> {
+    let path = PathBuf::from(platform::get_log_file_path());
+    let max_files = settings::load().logs.max_files;
+    let mut files = vec![path.clone()];
+    files.extend((1..=max_files).map(|i| rotated_path(&path, i)));
+    files.into_iter().filter(|p| p.exists()).collect()
+}
+
+/// 日志过滤条件
+#[derive(Debug, Default)]
+pub struct LogFilter {
+    /// 最低级别
+    pub min_level: Option<usize>,
+    pub since: Option<DateTime<FixedOffset>>,
+    pub until: Option<DateTime<FixedOffset>>,
+    /// 关键字（不区分大小写）
+    pub query: Option<String>,
+}
+
+impl LogFilter {
+    fn is_empty(&self) -> bool {
+        self.min_level.is_none()
+            && self.since.is_none()
+            && self.until.is_none()
+            && self.query.is_none()
+    }
+
+    fn matches(&self, entry: &GatewayLogEntry) -> bool {
+        if let Some(min) = self.min_level {
+            if level_rank(&entry.level).unwrap_or(0) < min {
+                return false;
+            }
+        }
+        if self.since.is_some() || self.until.is_some() {
+            let Some(ts) = entry
+                .timestamp
+                .as_deref()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            else {
+                return false;
+            };
+            if self.since.is_some_and(|since| ts < since) {
+                return false;
+            }
+            if self.until.is_some_and(|until| ts > until) {
+                return false;
+            }
+        }
+        if let Some(query) = &self.query {
+            if !entry.raw.to_lowercase().contains(query) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// 读取最近的 limit 条符合条件的日志（按时间正序返回），必要时继续读取已轮转的文件
+pub fn read_entries(limit: usize, filter: &LogFilter) -> Vec<GatewayLogEntry> {
+    let mut entries = Vec::new();
+    for path in log_files() {
+        let remaining = limit - entries.len();
+        // 无过滤条件时每个文件只需读取剩余行数
+        let n = if filter.is_empty() { remaining } else { usize::MAX };
+        let lines = match file::read_last_lines(&path.display().to_string(), n) {
+            Ok(lines) => lines,
+            Err(e) => {
+                warn!("[日志] 读取 {} 失败: {}", path.display(), e);
+                continue;
+            }
+        };
+
+        for line in lines.iter().rev() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = parse_log_line(line);
+            if filter.matches(&entry) {
+                entries.push(entry);
+                if entries.len() >= limit {
+                    break;
+                }
+            }
+        }
+        if entries.len() >= limit {
+            break;
+        }
+    }
+    entries.reverse();
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gateway_line_keeps_its_timestamp_for_time_filters() {
+        let entry = parse_log_line("2026-10-17T08:30:00.000+08:00 [gateway] listening on 18789");
+        assert_eq!(
+            entry.timestamp.as_deref(),
+            Some("2026-10-17T08:30:00.000+08:00")
+        );
+        assert_eq!(entry.subsystem.as_deref(), Some("gateway"));
+        assert_eq!(entry.level, "info");
+
+        let mut filter = LogFilter {
+            since: DateTime::parse_from_rfc3339("2026-10-17T08:00:00+08:00").ok(),
+            ..LogFilter::default()
+        };
+        assert!(filter.matches(&entry));
+        filter.since = DateTime::parse_from_rfc3339("2026-10-17T09:00:00+08:00").ok();
+        assert!(!filter.matches(&entry));
+    }
+}
@@ -0,0 +1,134 @@
+use crate::models::{GatewayLogBatch, GatewayLogEntry};
+use crate::utils::logfile::{self, LogCursor};
+use log::{debug, info, warn};
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// 推送给前端的事件名
+pub const GATEWAY_LOG_EVENT: &str = "gateway-log";
+
+/// 推送间隔：同一间隔内的日志合并为一批发送
+const FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// 每批最多发送的条数（即每秒最多 4 * 200 条）
+const MAX_BATCH: usize = 200;
+
+/// 待发送队列上限，超出后丢弃最旧的日志并计数
+const MAX_PENDING: usize = 2000;
+
+/// 是否有订阅者，无订阅时写日志不做任何额外处理
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+static STREAM: OnceLock<Mutex<StreamState>> = OnceLock::new();
+
+#[derive(Default)]
+struct StreamState {
+    next_id: u64,
+    subscribers: HashSet<u64>,
+    pending: VecDeque<GatewayLogEntry>,
+    dropped: u64,
+    /// 推送线程是否在运行
+    running: bool,
+}
+
+fn stream() -> &'static Mutex<StreamState> {
+    STREAM.get_or_init(|| Mutex::new(StreamState::default()))
+}
+
+/// 新的日志行写入时调用，有订阅者时放入待发送队列
+fn publish(line: &str) {
+    if !ACTIVE.load(Ordering::Relaxed) {
+        return;
+    }
+    let entry = logfile::parse_log_line(line);
+    let mut st = stream().lock().unwrap_or_else(|e| e.into_inner());
+    if st.subscribers.is_empty() {
+        return;
+    }
+    if st.pending.len() >= MAX_PENDING {
+        st.pending.pop_front();
+        st.dropped += 1;
+    }
+    st.pending.push_back(entry);
+}
+
+/// 订阅实时日志，返回订阅 ID
+pub fn subscribe(app: AppHandle) -> u64 {
+    let mut st = stream().lock().unwrap_or_else(|e| e.into_inner());
+    st.next_id += 1;
+    let id = st.next_id;
+    st.subscribers.insert(id);
+    ACTIVE.store(true, Ordering::Relaxed);
+
+    if !st.running {
+        st.running = true;
+        st.pending.clear();
+        st.dropped = 0;
+        thread::spawn(move || flush_loop(app));
+    }
+    info!(
+        "[日志流] 新增订阅 #{}（当前 {} 个）",
+        id,
+        st.subscribers.len()
+    );
+    id
+}
+
+/// 取消订阅，返回订阅是否存在
+pub fn unsubscribe(id: u64) -> bool {
+    let mut st = stream().lock().unwrap_or_else(|e| e.into_inner());
+    let removed = st.subscribers.remove(&id);
+    if st.subscribers.is_empty() {
+        ACTIVE.store(false, Ordering::Relaxed);
+        st.pending.clear();
+    }
+    if removed {
+        info!(
+            "[日志流] 取消订阅 #{}（剩余 {} 个）",
+            id,
+            st.subscribers.len()
+        );
+    }
+    removed
+}
+
+/// 推送线程：定时读取日志文件新增的行并批量发送，没有订阅者后退出
+fn flush_loop(app: AppHandle) {
+    debug!("[日志流] 推送线程启动");
+    let mut cursor = LogCursor::at_end();
+    loop {
+        thread::sleep(FLUSH_INTERVAL);
+        for line in cursor.read_new_lines() {
+            publish(&line);
+        }
+
+        let batch = {
+            let mut st = stream().lock().unwrap_or_else(|e| e.into_inner());
+            if st.subscribers.is_empty() {
+                st.running = false;
+                break;
+            }
+            if st.pending.is_empty() && st.dropped == 0 {
+                continue;
+            }
+            let n = st.pending.len().min(MAX_BATCH);
+            GatewayLogBatch {
+                entries: st.pending.drain(..n).collect(),
+                dropped: std::mem::take(&mut st.dropped),
+                pending: st.pending.len(),
+            }
+        };
+
+        if batch.dropped > 0 {
+            warn!("[日志流] 日志过多，已丢弃 {} 条", batch.dropped);
+        }
+        if let Err(e) = app.emit(GATEWAY_LOG_EVENT, batch) {
+            warn!("[日志流] 推送日志失败: {}", e);
+        }
+    }
+    debug!("[日志流] 推送线程退出");
+}
@@ -1,4 +1,26 @@
+pub mod backup;
+pub mod bundle;
 pub mod bundled;
+pub mod config_repo;
+pub mod config_watch;
+pub mod dotenv;
 pub mod file;
+pub mod gateway;
+pub mod health;
+pub mod json_diff;
+pub mod history;
+pub mod lifecycle;
+pub mod logfile;
+pub mod logstream;
 pub mod platform;
+pub mod preflight;
+pub mod procinfo;
+pub mod scheduler;
+pub mod schema;
+pub mod secrets;
+pub mod service_watch;
+pub mod settings;
 pub mod shell;
+pub mod supervisor;
+pub mod systemd;
+pub mod tls;
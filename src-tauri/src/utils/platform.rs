@@ -1,4 +1,5 @@
 use std::env;
+use std::path::PathBuf;
 
 /// 获取操作系统类型
 pub fn get_os() -> String {
@@ -41,6 +42,16 @@ pub fn get_config_file_path() -> String {
     }
 }
 
+/// 获取 openclaw.json 备份目录
+pub fn get_config_backup_dir() -> PathBuf {
+    PathBuf::from(get_config_dir()).join("backups")
+}
+
+/// 获取 Gateway HTTPS 证书目录（本地 CA 与服务器证书）
+pub fn get_tls_dir() -> PathBuf {
+    PathBuf::from(get_config_dir()).join("tls")
+}
+
 /// 获取 OpenClaw Manager 自身配置路径（如渠道 latest/nightly）
 pub fn get_manager_config_path() -> String {
     if is_windows() {
@@ -50,13 +61,30 @@ pub fn get_manager_config_path() -> String {
     }
 }
 
-/// 获取日志文件路径
-pub fn get_log_file_path() -> String {
-    if is_windows() {
-        format!("{}\\openclaw-gateway.log", get_config_dir())
-    } else {
-        String::from("/tmp/openclaw-gateway.log")
+/// 获取 OpenClaw Manager 数据目录（按用户隔离，如 ~/.local/share/OpenClawManager）
+pub fn get_manager_data_dir() -> PathBuf {
+    if let Some(local) = dirs::data_local_dir() {
+        return local.join("OpenClawManager");
+    }
+    if let Some(home) = dirs::home_dir() {
+        return home.join(".openclaw-manager");
     }
+    PathBuf::from("OpenClawManager")
+}
+
+/// 获取 Manager 日志目录
+pub fn get_log_dir() -> PathBuf {
+    get_manager_data_dir().join("logs")
+}
+
+/// 获取服务事件历史文件路径（追加写入的 JSON Lines）
+pub fn get_event_history_path() -> PathBuf {
+    get_manager_data_dir().join("service-events.jsonl")
+}
+
+/// 获取 Gateway 日志文件路径
+pub fn get_log_file_path() -> String {
+    get_log_dir().join("gateway.log").display().to_string()
 }
 
 /// 检测当前平台是否为 macOS
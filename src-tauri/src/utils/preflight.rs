@@ -0,0 +1,271 @@
+use crate::models::{ConfigIssue, IssueSeverity, OpenClawConfig, PreflightReport};
+use crate::utils::{dotenv, file, platform, shell};
+use log::{info, warn};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// OpenClaw 内置的渠道插件，不需要单独安装
+pub const BUILTIN_PLUGINS: &[&str] = &[
+    "telegram", "discord", "slack", "whatsapp", "imessage", "signal",
+];
+
+/// 必填凭据：(字段, 可替代的环境变量)
+type Credential = (&'static str, Option<&'static str>);
+
+/// 各渠道的必填凭据
+const CHANNEL_CREDENTIALS: &[(&str, &[Credential])] = &[
+    ("telegram", &[("botToken", Some("TELEGRAM_BOT_TOKEN"))]),
+    ("discord", &[("botToken", Some("DISCORD_BOT_TOKEN"))]),
+    ("slack", &[("botToken", Some("SLACK_BOT_TOKEN"))]),
+    ("feishu", &[("appId", None), ("appSecret", None)]),
+    ("wecom", &[("botId", None), ("secret", None)]),
+    ("wechat", &[("botId", None), ("secret", None)]),
+    ("qqbot", &[("appId", None), ("clientSecret", None)]),
+];
+
+/// 非内置插件的安装方式
+const PLUGIN_INSTALL_HINTS: &[(&str, &str)] = &[
+    ("feishu", "openclaw plugins install @m1heng-clawd/feishu"),
+    (
+        "wecom",
+        "openclaw plugins install @wecom/wecom-openclaw-plugin",
+    ),
+    ("qqbot", "在 Manager 的渠道页面安装 QQ 插件"),
+];
+
+fn issue(
+    severity: IssueSeverity,
+    code: &str,
+    path: &str,
+    message: String,
+    hint: Option<String>,
+) -> ConfigIssue {
+    ConfigIssue {
+        severity,
+        code: code.to_string(),
+        path: path.to_string(),
+        message,
+        hint,
+    }
+}
+
+fn error(code: &str, path: &str, message: String, hint: Option<String>) -> ConfigIssue {
+    issue(IssueSeverity::Error, code, path, message, hint)
+}
+
+/// 启动前校验 openclaw.json：主模型、已启用渠道的凭据、plugins.allow 中的插件
+pub fn check() -> PreflightReport {
+    let issues = collect_issues();
+    let passed = !issues.iter().any(|i| i.severity == IssueSeverity::Error);
+    if passed {
+        info!("[启动检查] ✓ 配置校验通过 ({} 条提示)", issues.len());
+    } else {
+        warn!("[启动检查] ✗ 配置校验未通过:");
+        for i in issues.iter().filter(|i| i.severity == IssueSeverity::Error) {
+            warn!("[启动检查]   {}: {}", i.path, i.message);
+        }
+    }
+    PreflightReport { passed, issues }
+}
+
+fn collect_issues() -> Vec<ConfigIssue> {
+    let path = platform::get_config_file_path();
+    if !file::file_exists(&path) {
+        return vec![error(
+            "config_missing",
+            "",
+            format!("找不到配置文件 {}", path),
+            Some("请先在 Manager 中完成初始化配置".to_string()),
+        )];
+    }
+    let content = match file::read_file(&path) {
+        Ok(c) => c,
+        Err(e) => {
+            return vec![error(
+                "config_unreadable",
+                "",
+                format!("读取配置文件失败: {}", e),
+                None,
+            )]
+        }
+    };
+    let raw: Value = match serde_json::from_str(&content) {
+        Ok(v) => v,
+        Err(e) => {
+            return vec![error(
+                "config_syntax",
+                "",
+                format!("openclaw.json 不是有效的 JSON: {}", e),
+                Some(format!(
+                    "第 {} 行第 {} 列附近有语法错误",
+                    e.line(),
+                    e.column()
+                )),
+            )]
+        }
+    };
+    let config: OpenClawConfig = match serde_json::from_value(raw) {
+        Ok(c) => c,
+        Err(e) => {
+            return vec![error(
+                "config_structure",
+                "",
+                format!("openclaw.json 结构不正确: {}", e),
+                None,
+            )]
+        }
+    };
+
+    let mut issues = Vec::new();
+    check_primary_model(&config, &mut issues);
+    check_channels(&config, &mut issues);
+    check_plugins(&config, &mut issues);
+    issues.sort_by_key(|i| i.severity != IssueSeverity::Error);
+    issues
+}
+
+/// agents.defaults.model.primary 必须指向 models.providers 中存在的 provider/model
+fn check_primary_model(config: &OpenClawConfig, issues: &mut Vec<ConfigIssue>) {
+    const PATH: &str = "agents.defaults.model.primary";
+    let primary = config.primary_model().map(str::trim).unwrap_or_default();
+    if primary.is_empty() {
+        issues.push(error(
+            "primary_model_missing",
+            PATH,
+            "未设置主模型".to_string(),
+            Some("请在 AI 配置页面选择主模型".to_string()),
+        ));
+        return;
+    }
+
+    let Some((provider_name, model_id)) = primary.split_once('/') else {
+        issues.push(error(
+            "primary_model_format",
+            PATH,
+            format!("主模型 {} 格式不正确，应为 provider/model", primary),
+            None,
+        ));
+        return;
+    };
+
+    let Some(provider) = config.providers().get(provider_name) else {
+        issues.push(error(
+            "primary_provider_missing",
+            PATH,
+            format!(
+                "主模型 {} 引用的 Provider {} 不存在于 models.providers",
+                primary, provider_name
+            ),
+            Some("请在 AI 配置页面添加该 Provider 或重新选择主模型".to_string()),
+        ));
+        return;
+    };
+
+    if !provider.models().iter().any(|m| m.id == model_id) {
+        issues.push(error(
+            "primary_model_not_found",
+            PATH,
+            format!("Provider {} 中没有模型 {}", provider_name, model_id),
+            Some(format!(
+                "请在 models.providers.{}.models 中添加该模型或重新选择主模型",
+                provider_name
+            )),
+        ));
+    }
+}
+
+/// 读取配置中的凭据字段（非空字符串或其他非 null 值视为已配置）
+fn has_value(channel: &Value, field: &str) -> bool {
+    match channel.get(field) {
+        Some(Value::String(s)) => !s.trim().is_empty(),
+        Some(Value::Null) | None => false,
+        Some(_) => true,
+    }
+}
+
+/// 已启用的渠道必须填写必填凭据
+fn check_channels(config: &OpenClawConfig, issues: &mut Vec<ConfigIssue>) {
+    let env_vars = dotenv::load_openclaw_env_vars();
+    let env_has = |key: &str| {
+        env_vars.get(key).is_some_and(|v| !v.is_empty())
+            || std::env::var(key).is_ok_and(|v| !v.is_empty())
+    };
+
+    let channels = config.channels();
+    let mut channel_ids: Vec<&String> = channels.keys().collect();
+    channel_ids.sort();
+    for id in channel_ids {
+        let channel = &channels[id];
+        if channel.get("enabled").and_then(|v| v.as_bool()) == Some(false) {
+            continue;
+        }
+        let Some((_, fields)) = CHANNEL_CREDENTIALS.iter().find(|(c, _)| c == id) else {
+            continue;
+        };
+        let missing: Vec<&str> = fields
+            .iter()
+            .filter(|(field, env_key)| !has_value(channel, field) && !env_key.is_some_and(&env_has))
+            .map(|(field, _)| *field)
+            .collect();
+        for field in missing {
+            issues.push(error(
+                "channel_credentials_missing",
+                &format!("channels.{}.{}", id, field),
+                format!("渠道 {} 已启用，但缺少 {}", id, field),
+                Some(format!("请在渠道配置中填写 {}，或禁用该渠道", field)),
+            ));
+        }
+    }
+}
+
+/// plugins.allow 中的插件必须已安装
+fn check_plugins(config: &OpenClawConfig, issues: &mut Vec<ConfigIssue>) {
+    let extensions_dir = PathBuf::from(platform::get_config_dir()).join("extensions");
+    let pending: Vec<&String> = config
+        .plugins_allow()
+        .iter()
+        .filter(|id| {
+            !BUILTIN_PLUGINS.contains(&id.as_str())
+                && !config.plugin_installs().contains_key(id.as_str())
+                && !extensions_dir.join(id.as_str()).is_dir()
+        })
+        .collect();
+    if pending.is_empty() {
+        return;
+    }
+
+    // 其余插件以 openclaw plugins list 为准（可能由 npm 全局安装）
+    let listed = match shell::run_openclaw(&["plugins", "list"]) {
+        Ok(output) => output.to_lowercase(),
+        Err(e) => {
+            for id in pending {
+                issues.push(issue(
+                    IssueSeverity::Warning,
+                    "plugin_unverified",
+                    "plugins.allow",
+                    format!("无法确认插件 {} 是否已安装: {}", id, e),
+                    None,
+                ));
+            }
+            return;
+        }
+    };
+
+    let hints: HashMap<&str, &str> = PLUGIN_INSTALL_HINTS.iter().copied().collect();
+    for id in pending {
+        if listed.contains(&id.to_lowercase()) {
+            continue;
+        }
+        let hint = match hints.get(id.as_str()) {
+            Some(install) => format!("安装方式: {}，或从 plugins.allow 中移除", install),
+            None => "请通过 openclaw plugins install 安装，或从 plugins.allow 中移除".to_string(),
+        };
+        issues.push(error(
+            "plugin_not_installed",
+            "plugins.allow",
+            format!("插件 {} 在 plugins.allow 中，但尚未安装", id),
+            Some(hint),
+        ));
+    }
+}
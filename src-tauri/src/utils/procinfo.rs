@@ -0,0 +1,430 @@
+use log::debug;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+#[cfg(unix)]
+use std::process::Command;
+
+#[cfg(windows)]
+use crate::utils::shell;
+
+/// 上一次 CPU 采样：pid -> (累计 CPU 秒数, 采样时间)
+static CPU_SAMPLES: OnceLock<Mutex<HashMap<u32, (f64, Instant)>>> = OnceLock::new();
+
+/// Linux 上 /proc/<pid>/stat 时间字段的单位（USER_HZ，几乎所有发行版均为 100）
+#[cfg(target_os = "linux")]
+const CLOCK_TICKS_PER_SEC: f64 = 100.0;
+
+/// 端口监听者
+#[derive(Debug, Clone)]
+pub struct PortListener {
+    /// 监听进程 PID（权限不足时可能无法解析）
+    pub pid: Option<u32>,
+}
+
+/// 进程详细信息
+#[derive(Debug, Clone, Default)]
+pub struct ProcessInfo {
+    /// 进程名
+    pub name: Option<String>,
+    /// 可执行文件路径
+    pub exe: Option<String>,
+    /// 完整命令行
+    pub command_line: Option<String>,
+    /// 运行时长（秒）
+    pub uptime_seconds: Option<u64>,
+    /// 常驻内存（MB）
+    pub memory_mb: Option<f64>,
+    /// CPU 使用率（相对单核，可能超过 100）
+    pub cpu_percent: Option<f64>,
+    /// 线程数
+    pub thread_count: Option<u32>,
+}
+
+/// 查找正在监听指定 TCP 端口的进程
+pub fn find_port_listener(port: u16) -> Option<PortListener> {
+    #[cfg(target_os = "linux")]
+    {
+        let inodes = linux::listening_socket_inodes(port);
+        if !inodes.is_empty() {
+            let pid = linux::find_pid_by_socket_inodes(&inodes).or_else(|| lsof_listener_pid(port));
+            debug!("[进程检测] 端口 {} 监听中 (/proc/net), PID: {:?}", port, pid);
+            return Some(PortListener { pid });
+        }
+        // /proc/net 可读且没有监听者，即可确定端口空闲
+        if linux::proc_net_available() {
+            return None;
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        if let Some(pid) = lsof_listener_pid(port) {
+            return Some(PortListener { pid: Some(pid) });
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        if let Some(pid) = netstat_listener_pid(port) {
+            return Some(PortListener { pid: Some(pid) });
+        }
+    }
+
+    // 最后兜底：能连上就说明有进程在监听，只是无法确定 PID
+    if tcp_port_accepts(port) {
+        debug!("[进程检测] 端口 {} 可连接，但无法解析监听进程", port);
+        return Some(PortListener { pid: None });
+    }
+
+    None
+}
+
+/// 读取进程信息，进程不存在时返回 None
+pub fn get_process_info(pid: u32) -> Option<ProcessInfo> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::process_info(pid)
+    }
+
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        ps_process_info(pid)
+    }
+
+    #[cfg(windows)]
+    {
+        windows_process_info(pid)
+    }
+}
+
+/// 根据累计 CPU 时间计算使用率
+/// 有上一次采样时取两次采样之间的平均值，否则退化为进程生命周期内的平均值
+fn cpu_percent_from_sample(pid: u32, cpu_seconds: f64, uptime_seconds: f64) -> Option<f64> {
+    let now = Instant::now();
+    let lock = CPU_SAMPLES.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut samples = lock.lock().ok()?;
+
+    let percent = match samples.get(&pid) {
+        Some((prev_cpu, prev_at)) if cpu_seconds >= *prev_cpu => {
+            let elapsed = now.duration_since(*prev_at).as_secs_f64();
+            if elapsed >= 0.5 {
+                Some((cpu_seconds - prev_cpu) / elapsed * 100.0)
+            } else {
+                // 两次采样间隔太短，沿用生命周期平均值，且不覆盖旧采样
+                return lifetime_cpu_percent(cpu_seconds, uptime_seconds);
+            }
+        }
+        _ => lifetime_cpu_percent(cpu_seconds, uptime_seconds),
+    };
+
+    samples.retain(|p, (_, at)| *p == pid || now.duration_since(*at).as_secs() < 600);
+    samples.insert(pid, (cpu_seconds, now));
+    percent.map(|p| (p * 10.0).round() / 10.0)
+}
+
+fn lifetime_cpu_percent(cpu_seconds: f64, uptime_seconds: f64) -> Option<f64> {
+    if uptime_seconds > 0.0 {
+        Some(((cpu_seconds / uptime_seconds * 100.0) * 10.0).round() / 10.0)
+    } else {
+        None
+    }
+}
+
+/// 尝试连接本机端口
+fn tcp_port_accepts(port: u16) -> bool {
+    use std::net::{SocketAddr, TcpStream};
+    use std::time::Duration;
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    TcpStream::connect_timeout(&addr, Duration::from_millis(300)).is_ok()
+}
+
+/// 使用 lsof 查找监听端口的 PID（lsof 不存在时返回 None）
+#[cfg(unix)]
+fn lsof_listener_pid(port: u16) -> Option<u32> {
+    let output = Command::new("lsof")
+        .args(["-nP", "-t", &format!("-iTCP:{}", port), "-sTCP:LISTEN"])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .and_then(|line| line.trim().parse::<u32>().ok())
+}
+
+/// 使用 netstat 查找监听端口的 PID
+#[cfg(windows)]
+fn netstat_listener_pid(port: u16) -> Option<u32> {
+    let output = shell::run_cmd_output("netstat -ano -p TCP").ok()?;
+    let suffix = format!(":{}", port);
+    for line in output.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        // Proto  Local Address  Foreign Address  State  PID
+        if parts.len() >= 5 && parts[1].ends_with(&suffix) && parts[3] == "LISTENING" {
+            if let Ok(pid) = parts[4].parse::<u32>() {
+                return Some(pid);
+            }
+        }
+    }
+    None
+}
+
+/// 通过 ps 读取进程信息（macOS / BSD）
+#[cfg(all(unix, not(target_os = "linux")))]
+fn ps_process_info(pid: u32) -> Option<ProcessInfo> {
+    // 每个字段单独传 -o，BSD ps 会把 "a=,b=" 中第一个 = 之后的内容都当作列标题
+    let ps = |fields: &[&str]| -> Option<String> {
+        let mut cmd = Command::new("ps");
+        cmd.args(["-p", &pid.to_string()]);
+        for field in fields {
+            cmd.args(["-o", field]);
+        }
+        let output = cmd.output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if value.is_empty() {
+            None
+        } else {
+            Some(value)
+        }
+    };
+
+    let stats = ps(&["rss=", "time=", "etime="])?;
+    let fields: Vec<&str> = stats.split_whitespace().collect();
+    let memory_mb = fields
+        .first()
+        .and_then(|v| v.parse::<f64>().ok())
+        .map(|kb| (kb / 1024.0 * 10.0).round() / 10.0);
+    let cpu_seconds = fields.get(1).and_then(|v| parse_ps_duration(v));
+    let uptime_seconds = fields.get(2).and_then(|v| parse_ps_duration(v));
+
+    let exe = ps(&["comm="]);
+    let name = exe.as_ref().map(|p| {
+        std::path::Path::new(p)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| p.clone())
+    });
+
+    let cpu_percent = match (cpu_seconds, uptime_seconds) {
+        (Some(cpu), Some(up)) => cpu_percent_from_sample(pid, cpu, up),
+        _ => None,
+    };
+
+    Some(ProcessInfo {
+        name,
+        exe,
+        command_line: ps(&["args="]),
+        uptime_seconds: uptime_seconds.map(|s| s as u64),
+        memory_mb,
+        cpu_percent,
+        thread_count: None,
+    })
+}
+
+/// 解析 ps 的时间格式：[[dd-]hh:]mm:ss[.cc]
+#[cfg(all(unix, not(target_os = "linux")))]
+fn parse_ps_duration(value: &str) -> Option<f64> {
+    let (days, rest) = match value.split_once('-') {
+        Some((d, r)) => (d.parse::<f64>().ok()?, r),
+        None => (0.0, value),
+    };
+    let mut seconds = 0.0;
+    for part in rest.split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(days * 86400.0 + seconds)
+}
+
+/// 通过 PowerShell (CIM) 读取进程信息（Windows）
+#[cfg(windows)]
+fn windows_process_info(pid: u32) -> Option<ProcessInfo> {
+    let script = format!(
+        "$p = Get-CimInstance Win32_Process -Filter \"ProcessId={}\"; \
+         if ($p) {{ \
+           $up = [int]((Get-Date) - $p.CreationDate).TotalSeconds; \
+           $cpu = ($p.KernelModeTime + $p.UserModeTime) / 10000000; \
+           \"$($p.Name)`t$($p.WorkingSetSize)`t$($p.ThreadCount)`t$up`t$cpu`t$($p.ExecutablePath)`t$($p.CommandLine)\" \
+         }}",
+        pid
+    );
+    let output = shell::run_powershell_output(&script).ok()?;
+    let line = output.lines().next()?;
+    let fields: Vec<&str> = line.splitn(7, '\t').collect();
+    if fields.len() < 7 {
+        return None;
+    }
+
+    let non_empty = |s: &str| {
+        let s = s.trim();
+        if s.is_empty() {
+            None
+        } else {
+            Some(s.to_string())
+        }
+    };
+    let uptime = fields[3].trim().parse::<f64>().ok();
+    let cpu_seconds = fields[4].trim().parse::<f64>().ok();
+    let cpu_percent = match (cpu_seconds, uptime) {
+        (Some(cpu), Some(up)) => cpu_percent_from_sample(pid, cpu, up),
+        _ => None,
+    };
+
+    Some(ProcessInfo {
+        name: non_empty(fields[0]),
+        exe: non_empty(fields[5]),
+        command_line: non_empty(fields[6]),
+        uptime_seconds: uptime.map(|s| s as u64),
+        memory_mb: fields[1]
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .map(|bytes| (bytes / 1024.0 / 1024.0 * 10.0).round() / 10.0),
+        cpu_percent,
+        thread_count: fields[2].trim().parse::<u32>().ok(),
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::{cpu_percent_from_sample, ProcessInfo, CLOCK_TICKS_PER_SEC};
+    use std::collections::HashSet;
+    use std::fs;
+    use std::path::Path;
+
+    /// TCP 状态 LISTEN 在 /proc/net/tcp 中的编码
+    const TCP_LISTEN: &str = "0A";
+
+    pub fn proc_net_available() -> bool {
+        Path::new("/proc/net/tcp").exists()
+    }
+
+    /// 从 /proc/net/tcp 与 /proc/net/tcp6 中找出监听指定端口的 socket inode
+    pub fn listening_socket_inodes(port: u16) -> HashSet<u64> {
+        let mut inodes = HashSet::new();
+        for table in ["/proc/net/tcp", "/proc/net/tcp6"] {
+            let Ok(content) = fs::read_to_string(table) else {
+                continue;
+            };
+            for line in content.lines().skip(1) {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                if fields.len() < 10 || fields[3] != TCP_LISTEN {
+                    continue;
+                }
+                // local_address 形如 0100007F:4965（端口为十六进制）
+                let local_port = fields[1]
+                    .rsplit_once(':')
+                    .and_then(|(_, p)| u16::from_str_radix(p, 16).ok());
+                if local_port != Some(port) {
+                    continue;
+                }
+                if let Ok(inode) = fields[9].parse::<u64>() {
+                    if inode != 0 {
+                        inodes.insert(inode);
+                    }
+                }
+            }
+        }
+        inodes
+    }
+
+    /// 遍历 /proc/<pid>/fd，找到持有这些 socket 的进程
+    pub fn find_pid_by_socket_inodes(inodes: &HashSet<u64>) -> Option<u32> {
+        let targets: HashSet<String> = inodes.iter().map(|i| format!("socket:[{}]", i)).collect();
+        for entry in fs::read_dir("/proc").ok()?.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+                continue;
+            };
+            // 其他用户的进程通常无权读取 fd，直接跳过
+            let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+                continue;
+            };
+            for fd in fds.flatten() {
+                if let Ok(link) = fs::read_link(fd.path()) {
+                    if targets.contains(link.to_string_lossy().as_ref()) {
+                        return Some(pid);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    pub fn process_info(pid: u32) -> Option<ProcessInfo> {
+        let proc_dir = Path::new("/proc").join(pid.to_string());
+        let stat = fs::read_to_string(proc_dir.join("stat")).ok()?;
+
+        // 进程名在括号中且可能包含空格，因此以最后一个 ')' 为界
+        let open = stat.find('(')?;
+        let close = stat.rfind(')')?;
+        let name = stat.get(open + 1..close).map(|s| s.to_string());
+        let fields: Vec<&str> = stat.get(close + 1..)?.split_whitespace().collect();
+        // fields[0] 对应 stat 第 3 列 (state)
+        let field = |n: usize| fields.get(n - 3).and_then(|v| v.parse::<u64>().ok());
+        let utime = field(14);
+        let stime = field(15);
+        let thread_count = field(20).map(|n| n as u32);
+        let start_ticks = field(22);
+
+        let system_uptime = fs::read_to_string("/proc/uptime")
+            .ok()
+            .and_then(|s| s.split_whitespace().next()?.parse::<f64>().ok());
+        let uptime_seconds = match (system_uptime, start_ticks) {
+            (Some(up), Some(start)) => Some((up - start as f64 / CLOCK_TICKS_PER_SEC).max(0.0)),
+            _ => None,
+        };
+
+        let cpu_percent = match (utime, stime, uptime_seconds) {
+            (Some(u), Some(s), Some(up)) => {
+                cpu_percent_from_sample(pid, (u + s) as f64 / CLOCK_TICKS_PER_SEC, up)
+            }
+            _ => None,
+        };
+
+        let memory_mb = fs::read_to_string(proc_dir.join("status"))
+            .ok()
+            .and_then(|status| {
+                status
+                    .lines()
+                    .find(|l| l.starts_with("VmRSS:"))
+                    .and_then(|l| l.split_whitespace().nth(1))
+                    .and_then(|kb| kb.parse::<f64>().ok())
+            })
+            .map(|kb| (kb / 1024.0 * 10.0).round() / 10.0);
+
+        let command_line = fs::read(proc_dir.join("cmdline")).ok().and_then(|raw| {
+            let args: Vec<String> = raw
+                .split(|b| *b == 0)
+                .filter(|a| !a.is_empty())
+                .map(|a| String::from_utf8_lossy(a).to_string())
+                .collect();
+            if args.is_empty() {
+                None
+            } else {
+                Some(args.join(" "))
+            }
+        });
+
+        let exe = fs::read_link(proc_dir.join("exe"))
+            .ok()
+            .map(|p| p.display().to_string());
+
+        Some(ProcessInfo {
+            name,
+            exe,
+            command_line,
+            uptime_seconds: uptime_seconds.map(|s| s as u64),
+            memory_mb,
+            cpu_percent,
+            thread_count,
+        })
+    }
+}
@@ -0,0 +1,276 @@
+use crate::models::{
+    EventTrigger, ScheduleAction, ScheduleActionRecord, ScheduleRule, ScheduleSettings,
+    ScheduleStatus, ScheduleTransition,
+};
+use crate::utils::{gateway, settings, supervisor};
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDateTime, NaiveTime, TimeZone,
+};
+use log::{info, warn};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// 检查计划的间隔，启停最多比计划时间晚这么久
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 计算即将发生的启停时向后查看的天数
+const LOOKAHEAD_DAYS: i64 = 8;
+
+static SCHEDULER: OnceLock<Mutex<SchedulerState>> = OnceLock::new();
+
+#[derive(Default)]
+struct SchedulerState {
+    started: bool,
+    /// 上一次检查时计划要求的状态（true = 运行），只在变化时执行启停，
+    /// 这样窗口内手动停止、窗口外手动启动都会保持到下一次计划切换；
+    /// 为 None 时（应用启动或计划修改后）立即按计划启停
+    last_desired: Option<bool>,
+    last_action: Option<ScheduleActionRecord>,
+}
+
+fn scheduler() -> &'static Mutex<SchedulerState> {
+    SCHEDULER.get_or_init(|| Mutex::new(SchedulerState::default()))
+}
+
+/// 解析 HH:MM
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| format!("时间格式无效: {}（应为 HH:MM）", value))
+}
+
+/// 校验定时计划
+pub fn validate(schedule: &ScheduleSettings) -> Result<(), String> {
+    for (i, rule) in schedule.rules.iter().enumerate() {
+        let label = if rule.name.is_empty() {
+            format!("规则 {}", i + 1)
+        } else {
+            rule.name.clone()
+        };
+        let start = parse_time(&rule.start).map_err(|e| format!("{}: {}", label, e))?;
+        let end = parse_time(&rule.end).map_err(|e| format!("{}: {}", label, e))?;
+        if start == end {
+            return Err(format!("{}: 开始时间与结束时间不能相同", label));
+        }
+        if let Some(day) = rule.days.iter().find(|d| !(1..=7).contains(*d)) {
+            return Err(format!("{}: 星期必须在 1-7 之间，收到 {}", label, day));
+        }
+    }
+    Ok(())
+}
+
+fn rule_name(rule: &ScheduleRule) -> String {
+    if rule.name.is_empty() {
+        format!("{}-{}", rule.start, rule.end)
+    } else {
+        rule.name.clone()
+    }
+}
+
+fn runs_on(rule: &ScheduleRule, weekday: u32) -> bool {
+    rule.days.is_empty() || rule.days.iter().any(|d| u32::from(*d) == weekday)
+}
+
+/// 规则在给定的本地时间是否处于运行窗口内（跨天窗口算作开始那天的规则）
+fn rule_active(rule: &ScheduleRule, at: NaiveDateTime) -> bool {
+    let (Ok(start), Ok(end)) = (parse_time(&rule.start), parse_time(&rule.end)) else {
+        return false;
+    };
+    if !rule.enabled {
+        return false;
+    }
+    let weekday = at.weekday().number_from_monday();
+    let time = at.time();
+    if start < end {
+        runs_on(rule, weekday) && time >= start && time < end
+    } else {
+        let yesterday = (at - ChronoDuration::days(1))
+            .weekday()
+            .number_from_monday();
+        (runs_on(rule, weekday) && time >= start) || (runs_on(rule, yesterday) && time < end)
+    }
+}
+
+/// 给定时间生效的规则
+fn active_rule(schedule: &ScheduleSettings, at: NaiveDateTime) -> Option<&ScheduleRule> {
+    schedule.rules.iter().find(|r| rule_active(r, at))
+}
+
+/// 计算从 from 开始的后续启停（多个规则重叠时只在整体状态变化时记一次）
+pub fn upcoming(
+    schedule: &ScheduleSettings,
+    from: DateTime<Local>,
+    limit: usize,
+) -> Vec<ScheduleTransition> {
+    if !schedule.enabled {
+        return Vec::new();
+    }
+
+    let now = from.naive_local();
+    let mut candidates: Vec<(NaiveDateTime, String)> = Vec::new();
+    for rule in schedule.rules.iter().filter(|r| r.enabled) {
+        let (Ok(start), Ok(end)) = (parse_time(&rule.start), parse_time(&rule.end)) else {
+            continue;
+        };
+        for offset in -1..=LOOKAHEAD_DAYS {
+            let date = now.date() + ChronoDuration::days(offset);
+            let window_start = date.and_time(start);
+            let mut window_end = date.and_time(end);
+            if end < start {
+                window_end += ChronoDuration::days(1);
+            }
+            for at in [window_start, window_end] {
+                if at > now {
+                    candidates.push((at, rule_name(rule)));
+                }
+            }
+        }
+    }
+    candidates.sort_by_key(|a| a.0);
+    candidates.dedup_by(|a, b| a.0 == b.0);
+
+    let mut transitions = Vec::new();
+    for (at, name) in candidates {
+        let before = active_rule(schedule, at - ChronoDuration::seconds(1)).is_some();
+        let after = active_rule(schedule, at);
+        if before == after.is_some() {
+            continue;
+        }
+        // 夏令时切换时不存在的本地时间直接跳过
+        let Some(local) = Local.from_local_datetime(&at).earliest() else {
+            continue;
+        };
+        transitions.push(ScheduleTransition {
+            at: local.to_rfc3339(),
+            action: if after.is_some() {
+                ScheduleAction::Start
+            } else {
+                ScheduleAction::Stop
+            },
+            rule: after.map(rule_name).unwrap_or(name),
+        });
+        if transitions.len() >= limit {
+            break;
+        }
+    }
+    transitions
+}
+
+/// 当前定时计划状态
+pub fn status(limit: usize) -> ScheduleStatus {
+    let schedule = settings::load().schedule;
+    let now = Local::now();
+    let active = active_rule(&schedule, now.naive_local()).filter(|_| schedule.enabled);
+    ScheduleStatus {
+        enabled: schedule.enabled,
+        in_window: active.is_some(),
+        active_rule: active.map(rule_name),
+        last_action: scheduler()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .last_action
+            .clone(),
+        upcoming: upcoming(&schedule, now, limit),
+    }
+}
+
+/// 计划修改后调用：下一次检查时按新计划立即启停，之后在时间窗口边界启停
+pub fn reload() {
+    set_last_desired(None);
+}
+
+fn set_last_desired(desired: Option<bool>) {
+    scheduler()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .last_desired = desired;
+}
+
+/// 启动后台定时检查（只启动一次）
+pub fn start(app: AppHandle) {
+    {
+        let mut st = scheduler().lock().unwrap_or_else(|e| e.into_inner());
+        if st.started {
+            return;
+        }
+        st.started = true;
+    }
+    info!("[定时计划] 启动定时检查");
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tick(&app).await;
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// 检查一次计划，计划要求的状态变化时启动或停止 Gateway
+async fn tick(app: &AppHandle) {
+    let schedule = settings::load().schedule;
+    if !schedule.enabled || !schedule.rules.iter().any(|r| r.enabled) {
+        reload();
+        return;
+    }
+
+    let active = active_rule(&schedule, Local::now().naive_local()).map(rule_name);
+    let desired = active.is_some();
+    let last_desired = scheduler()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .last_desired;
+    // 首次检查（应用启动或计划修改后）直接按计划执行，之后只在计划要求的状态变化时执行
+    if last_desired == Some(desired) {
+        return;
+    }
+
+    let status = match gateway::collect_status_async().await {
+        Ok(status) => status,
+        Err(e) => {
+            warn!("[定时计划] {}", e);
+            return;
+        }
+    };
+
+    let (action, result) = match active {
+        Some(rule) if !status.running => {
+            let reason = format!("定时计划启动: {}", rule);
+            info!("[定时计划] {}", reason);
+            (
+                ScheduleAction::Start,
+                supervisor::start_with_reason(EventTrigger::Scheduler, &reason, true)
+                    .await
+                    .map_err(|e| e.to_string()),
+            )
+        }
+        None if status.running => {
+            info!("[定时计划] 已离开运行时间窗口，停止 Gateway");
+            let result = supervisor::stop_with_reason(app, EventTrigger::Scheduler, "定时计划停止")
+                .await
+                .map(|r| r.message);
+            (ScheduleAction::Stop, result)
+        }
+        _ => {
+            set_last_desired(Some(desired));
+            return;
+        }
+    };
+
+    // 失败时不再每次检查都重试（如配置检查未通过，重试只会不断失败），等到下一次计划切换
+    set_last_desired(Some(desired));
+    if let Err(e) = &result {
+        warn!(
+            "[定时计划] {:?} 失败，下一次计划切换时再执行: {}",
+            action, e
+        );
+    }
+    let record = ScheduleActionRecord {
+        at: Local::now().to_rfc3339(),
+        action,
+        success: result.is_ok(),
+        message: result.unwrap_or_else(|e| e),
+    };
+    scheduler()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .last_action = Some(record);
+}
@@ -0,0 +1,449 @@
+use crate::models::{IssueSeverity, OpenClawConfig, SchemaIssue, SchemaReport};
+use crate::utils::{file, platform};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// 打包的 openclaw.json 校验规则（JSON Schema draft-07 子集，另加 x-format / x-severity / x-hint）
+const SCHEMA_SOURCE: &str = include_str!("../../resources/schemas/openclaw.schema.json");
+
+static SCHEMA: OnceLock<Result<Value, String>> = OnceLock::new();
+
+/// 打包的校验规则；解析失败时返回错误，不能静默地放过所有配置
+fn schema() -> Result<&'static Value, String> {
+    SCHEMA
+        .get_or_init(|| {
+            serde_json::from_str(SCHEMA_SOURCE)
+                .map_err(|e| format!("内置的配置校验规则无法解析: {}", e))
+        })
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+/// JSON Pointer 中的一段（~ 和 / 需要转义）
+pub fn pointer_segment(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn child_pointer(pointer: &str, key: &str) -> String {
+    format!("{}/{}", pointer, pointer_segment(key))
+}
+
+// ============ 位置定位 ============
+
+/// 扫描 JSON 文本，记录每个 JSON Pointer 对应的行列（对象成员取键的位置，数组元素取元素的位置）
+struct Locator<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    line: u32,
+    column: u32,
+    positions: HashMap<String, (u32, u32)>,
+}
+
+impl<'a> Locator<'a> {
+    fn scan(content: &'a str) -> HashMap<String, (u32, u32)> {
+        let mut locator = Locator {
+            bytes: content.as_bytes(),
+            pos: 0,
+            line: 1,
+            column: 1,
+            positions: HashMap::new(),
+        };
+        locator.skip_whitespace();
+        locator
+            .positions
+            .insert(String::new(), (locator.line, locator.column));
+        locator.value("");
+        locator.positions
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) {
+        let Some(b) = self.peek() else {
+            return;
+        };
+        self.pos += 1;
+        if b == b'\n' {
+            self.line += 1;
+            self.column = 1;
+        } else if b & 0xC0 != 0x80 {
+            // 按字符计列，UTF-8 的后续字节不计数
+            self.column += 1;
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.bump();
+        }
+    }
+
+    fn string(&mut self) -> String {
+        let start = self.pos;
+        self.bump();
+        while let Some(b) = self.peek() {
+            self.bump();
+            match b {
+                b'\\' => self.bump(),
+                b'"' => break,
+                _ => {}
+            }
+        }
+        serde_json::from_slice(&self.bytes[start..self.pos]).unwrap_or_default()
+    }
+
+    fn value(&mut self, pointer: &str) {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => {
+                self.bump();
+                loop {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b'"') => {}
+                        Some(b'}') => {
+                            self.bump();
+                            return;
+                        }
+                        _ => return,
+                    }
+                    let at = (self.line, self.column);
+                    let child = child_pointer(pointer, &self.string());
+                    self.positions.insert(child.clone(), at);
+                    self.skip_whitespace();
+                    if self.peek() == Some(b':') {
+                        self.bump();
+                    }
+                    self.value(&child);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.bump(),
+                        Some(b'}') => {}
+                        _ => return,
+                    }
+                }
+            }
+            Some(b'[') => {
+                self.bump();
+                for index in 0.. {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b']') => {
+                            self.bump();
+                            return;
+                        }
+                        None => return,
+                        _ => {}
+                    }
+                    let child = format!("{}/{}", pointer, index);
+                    self.positions
+                        .insert(child.clone(), (self.line, self.column));
+                    self.value(&child);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.bump(),
+                        Some(b']') => {}
+                        _ => return,
+                    }
+                }
+            }
+            Some(b'"') => {
+                self.string();
+            }
+            _ => {
+                while matches!(self.peek(), Some(b) if !b.is_ascii_whitespace() && !b",}]".contains(&b))
+                {
+                    self.bump();
+                }
+            }
+        }
+    }
+}
+
+// ============ Schema 校验 ============
+
+fn type_label(name: &str) -> &str {
+    match name {
+        "object" => "对象",
+        "array" => "数组",
+        "string" => "字符串",
+        "integer" => "整数",
+        "number" => "数字",
+        "boolean" => "布尔值",
+        other => other,
+    }
+}
+
+fn value_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn type_matches(expected: &str, value: &Value) -> bool {
+    let actual = value_type(value);
+    actual == expected || (expected == "number" && actual == "integer")
+}
+
+/// provider/model-id：斜杠两侧都不能为空，且不含空白
+fn is_provider_model(value: &str) -> bool {
+    value
+        .split_once('/')
+        .is_some_and(|(provider, model)| !provider.is_empty() && !model.is_empty())
+        && !value.contains(char::is_whitespace)
+}
+
+fn is_http_url(value: &str) -> bool {
+    ["http://", "https://"]
+        .iter()
+        .any(|scheme| value.len() > scheme.len() && value.starts_with(scheme))
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    issues: Vec<SchemaIssue>,
+}
+
+impl<'a> Validator<'a> {
+    /// 解析 "#/definitions/xxx" 形式的 $ref
+    fn resolve(&self, schema: &'a Value) -> &'a Value {
+        schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix("#/"))
+            .and_then(|path| self.root.pointer(&format!("/{}", path)))
+            .unwrap_or(schema)
+    }
+
+    fn push(&mut self, schema: &Value, pointer: &str, message: String, fix: Option<String>) {
+        let severity = match schema.get("x-severity").and_then(Value::as_str) {
+            Some("warning") => IssueSeverity::Warning,
+            _ => IssueSeverity::Error,
+        };
+        let hint = schema.get("x-hint").and_then(Value::as_str);
+        let fix = match (fix, hint) {
+            (Some(fix), Some(hint)) => Some(format!("{}；{}", fix, hint)),
+            (fix, hint) => fix.or(hint.map(str::to_string)),
+        };
+        self.issues.push(SchemaIssue {
+            severity,
+            pointer: pointer.to_string(),
+            line: None,
+            column: None,
+            message,
+            fix,
+        });
+    }
+
+    fn check(&mut self, schema: &'a Value, value: &Value, pointer: &str) {
+        let schema = self.resolve(schema);
+
+        if let Some(expected) = schema.get("type").and_then(Value::as_str) {
+            if !type_matches(expected, value) {
+                let message = format!(
+                    "类型应为{}，实际为{}",
+                    type_label(expected),
+                    type_label(value_type(value))
+                );
+                let fix = format!("把该字段改为{}", type_label(expected));
+                self.push(schema, pointer, message, Some(fix));
+                return;
+            }
+        }
+
+        if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+            if !options.contains(value) {
+                let choices: Vec<String> = options.iter().map(Value::to_string).collect();
+                let message = format!("不支持的取值 {}", value);
+                let fix = format!("可选值: {}", choices.join(", "));
+                self.push(schema, pointer, message, Some(fix));
+            }
+        }
+
+        match value {
+            Value::String(s) => self.check_string(schema, s, pointer),
+            Value::Number(n) => {
+                let n = n.as_f64().unwrap_or_default();
+                if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                    if n < min {
+                        self.push(schema, pointer, format!("不能小于 {}", min), None);
+                    }
+                }
+                if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                    if n > max {
+                        self.push(schema, pointer, format!("不能大于 {}", max), None);
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for (i, item) in items.iter().enumerate() {
+                        self.check(item_schema, item, &format!("{}/{}", pointer, i));
+                    }
+                }
+            }
+            Value::Object(map) => {
+                let properties = schema.get("properties").and_then(Value::as_object);
+                if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                    for key in required.iter().filter_map(Value::as_str) {
+                        if !map.contains_key(key) {
+                            let message = format!("缺少必填字段 {}", key);
+                            let fix = format!("添加 {} 字段", key);
+                            self.push(schema, &child_pointer(pointer, key), message, Some(fix));
+                        }
+                    }
+                }
+                for (key, child) in map {
+                    let child_ptr = child_pointer(pointer, key);
+                    if let Some(names) = schema.get("propertyNames") {
+                        self.check(names, &Value::String(key.clone()), &child_ptr);
+                    }
+                    match (
+                        properties.and_then(|p| p.get(key)),
+                        schema.get("additionalProperties"),
+                    ) {
+                        (Some(sub), _) => self.check(sub, child, &child_ptr),
+                        (None, Some(Value::Bool(false))) => {
+                            let message = format!("不支持的字段 {}", key);
+                            self.push(schema, &child_ptr, message, Some("删除该字段".to_string()));
+                        }
+                        (None, Some(sub)) if sub.is_object() => self.check(sub, child, &child_ptr),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_string(&mut self, schema: &Value, s: &str, pointer: &str) {
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+            if (s.chars().count() as u64) < min {
+                let message = if min == 1 {
+                    "不能为空".to_string()
+                } else {
+                    format!("长度不能少于 {}", min)
+                };
+                self.push(schema, pointer, message, None);
+            }
+        }
+        match schema.get("x-format").and_then(Value::as_str) {
+            Some("provider-model") if !is_provider_model(s) => {
+                let message = format!("模型 ID \"{}\" 格式不正确，应为 provider/model-id", s);
+                self.push(schema, pointer, message, None);
+            }
+            Some("url") if !is_http_url(s) => {
+                let message = format!("\"{}\" 不是有效的 http(s) 地址", s);
+                self.push(schema, pointer, message, None);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// serde_json 报告的列按字节计，换算成与 Locator 一致的字符列
+fn char_column(content: &str, line: usize, column: usize) -> usize {
+    match content.lines().nth(line.saturating_sub(1)) {
+        Some(text) => text.char_indices().take_while(|(i, _)| *i < column).count(),
+        None => column,
+    }
+}
+
+/// 去掉 serde_json 错误信息末尾按字节计的位置（位置已单独给出）
+fn error_text(e: &serde_json::Error) -> String {
+    let text = e.to_string();
+    match text.rsplit_once(" at line ") {
+        Some((message, _)) => message.to_string(),
+        None => text,
+    }
+}
+
+fn error_at(line: usize, column: usize, message: String, fix: &str) -> SchemaIssue {
+    SchemaIssue {
+        severity: IssueSeverity::Error,
+        pointer: String::new(),
+        line: (line > 0).then_some(line as u32),
+        column: (line > 0).then_some(column as u32),
+        message,
+        fix: Some(fix.to_string()),
+    }
+}
+
+/// 校验 openclaw.json 文本：JSON 语法 → 打包的 Schema → Manager 的类型化配置模型
+pub fn validate_content(content: &str) -> Result<SchemaReport, String> {
+    let schema = schema()?;
+    let mut issues = Vec::new();
+    if !content.trim().is_empty() {
+        match serde_json::from_str::<Value>(content) {
+            Err(e) => issues.push(error_at(
+                e.line(),
+                char_column(content, e.line(), e.column()),
+                format!("JSON 语法错误: {}", error_text(&e)),
+                "检查该位置附近是否缺少逗号、引号或括号",
+            )),
+            Ok(value) => {
+                let mut validator = Validator {
+                    root: schema,
+                    issues: Vec::new(),
+                };
+                validator.check(schema, &value, "");
+                issues = validator.issues;
+
+                // Schema 没有覆盖到的字段再用类型化模型兜底，避免同一问题重复报告
+                if !issues.iter().any(|i| i.severity == IssueSeverity::Error) {
+                    if let Err(e) = serde_json::from_str::<OpenClawConfig>(content) {
+                        issues.push(error_at(
+                            e.line(),
+                            char_column(content, e.line(), e.column()),
+                            format!("字段类型与 Manager 的配置模型不一致: {}", error_text(&e)),
+                            "按错误位置修正字段类型",
+                        ));
+                    }
+                }
+
+                let positions = Locator::scan(content);
+                for issue in issues.iter_mut().filter(|i| i.line.is_none()) {
+                    // 缺少的字段没有自己的位置，向上找到最近的父级
+                    let mut pointer = issue.pointer.as_str();
+                    let (line, column) = loop {
+                        if let Some(pos) = positions.get(pointer) {
+                            break *pos;
+                        }
+                        match pointer.rfind('/') {
+                            Some(i) => pointer = &pointer[..i],
+                            None => break (1, 1),
+                        }
+                    };
+                    issue.line = Some(line);
+                    issue.column = Some(column);
+                }
+            }
+        }
+    }
+
+    issues.sort_by_key(|i| (i.line, i.column));
+    Ok(SchemaReport {
+        valid: !issues.iter().any(|i| i.severity == IssueSeverity::Error),
+        issues,
+    })
+}
+
+/// 校验当前的 openclaw.json（文件不存在时视为通过）
+pub fn validate() -> Result<SchemaReport, String> {
+    let config_path = platform::get_config_file_path();
+    let content = if file::file_exists(&config_path) {
+        file::read_file(&config_path).map_err(|e| format!("读取配置文件失败: {}", e))?
+    } else {
+        String::new()
+    };
+    validate_content(&content)
+}
@@ -0,0 +1,89 @@
+use crate::models::JsonChange;
+use serde_json::Value;
+
+/// 视为密钥的字段名后缀（不区分大小写），如 apiKey / botToken / appSecret
+const SECRET_KEY_SUFFIXES: &[&str] = &["apikey", "token", "secret", "password", "privatekey"];
+
+/// 字段名是否为密钥
+pub fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase().replace(['_', '-'], "");
+    SECRET_KEY_SUFFIXES.iter().any(|s| key.ends_with(s))
+}
+
+/// 环境变量名中表示密钥的分段，如 AWS_SECRET_ACCESS_KEY / GH_TOKEN
+const SECRET_ENV_SEGMENTS: &[&str] = &[
+    "KEY",
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "CREDENTIAL",
+    "CREDENTIALS",
+];
+
+/// 环境变量名是否为密钥
+pub fn is_secret_env_key(name: &str) -> bool {
+    is_secret_key(name)
+        || name
+            .to_uppercase()
+            .split('_')
+            .any(|segment| SECRET_ENV_SEGMENTS.contains(&segment))
+}
+
+/// 生成随机 token（32 字节，来自操作系统的安全随机数源）
+pub fn generate_token() -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("读取系统随机数失败: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// 脱敏显示：保留首尾各 4 个字符，过短时全部隐藏
+pub fn mask(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() > 8 {
+        let head: String = chars[..4].iter().collect();
+        let tail: String = chars[chars.len() - 4..].iter().collect();
+        format!("{}...{}", head, tail)
+    } else {
+        "****".to_string()
+    }
+}
+
+/// 递归脱敏 JSON 中密钥字段的字符串值
+pub fn mask_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                match v {
+                    Value::String(s) if is_secret_key(key) => *s = mask(s),
+                    _ => mask_json(v),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(mask_json),
+        _ => {}
+    }
+}
+
+/// 脱敏一处 JSON 变化的新旧值
+pub fn mask_change(change: &mut JsonChange) {
+    let secret = change.path.rsplit('.').next().is_some_and(is_secret_key);
+    for value in [&mut change.old, &mut change.new].into_iter().flatten() {
+        match value {
+            Value::String(s) if secret => *s = mask(s),
+            _ => mask_json(value),
+        }
+    }
+}
+
+/// 脱敏一处环境变量变化的新旧值（按环境变量名判断是否为密钥）
+pub fn mask_env_change(change: &mut JsonChange) {
+    if !is_secret_env_key(&change.path) {
+        return;
+    }
+    for value in [&mut change.old, &mut change.new].into_iter().flatten() {
+        if let Value::String(s) = value {
+            *s = mask(s);
+        }
+    }
+}
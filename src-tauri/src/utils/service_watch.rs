@@ -0,0 +1,237 @@
+use crate::models::{HealthState, ServiceState, ServiceStateEvent, ServiceStatus};
+use crate::utils::{gateway, history, supervisor};
+use chrono::Local;
+use log::{debug, info, warn};
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// 推送给前端的事件名
+pub const SERVICE_STATE_EVENT: &str = "service-state-changed";
+
+/// 后台检测间隔（状态变化由命令和守护线程主动唤醒，这里只兜底外部启停）
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 启动中状态的最长保持时间
+const STARTING_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 端口已监听但 HTTP 未就绪时，视为仍在启动的时长
+const NOT_READY_GRACE: Duration = Duration::from_secs(90);
+
+/// 停止中状态的最长保持时间
+const STOPPING_TIMEOUT: Duration = Duration::from_secs(60);
+
+static WATCH: OnceLock<(Mutex<WatchState>, Condvar)> = OnceLock::new();
+
+/// 命令发起的过渡状态（启动中 / 停止中），实际状态达成或超时后失效
+#[derive(Clone)]
+struct TransitionHint {
+    state: ServiceState,
+    reason: String,
+    since: Instant,
+}
+
+#[derive(Default)]
+struct WatchState {
+    started: bool,
+    current: Option<ServiceState>,
+    hint: Option<TransitionHint>,
+    /// 由调用方提供的下一次状态变化原因（如守护线程记录的退出原因）
+    pending_reason: Option<String>,
+    wake: bool,
+}
+
+fn watch() -> &'static (Mutex<WatchState>, Condvar) {
+    WATCH.get_or_init(|| (Mutex::new(WatchState::default()), Condvar::new()))
+}
+
+/// 启动后台状态检测线程（只启动一次）
+pub fn start(app: AppHandle) {
+    {
+        let mut st = watch().0.lock().unwrap_or_else(|e| e.into_inner());
+        if st.started {
+            return;
+        }
+        st.started = true;
+    }
+    info!("[状态监测] 启动后台状态检测");
+    thread::spawn(move || watch_loop(app));
+}
+
+/// 标记过渡状态（启动中 / 停止中）并立即触发检测
+pub fn begin_transition(state: ServiceState, reason: &str) {
+    let (lock, cvar) = watch();
+    let mut st = lock.lock().unwrap_or_else(|e| e.into_inner());
+    st.hint = Some(TransitionHint {
+        state,
+        reason: reason.to_string(),
+        since: Instant::now(),
+    });
+    st.wake = true;
+    cvar.notify_all();
+}
+
+/// 清除过渡状态（操作结束时调用）并立即触发检测
+pub fn end_transition() {
+    let (lock, cvar) = watch();
+    let mut st = lock.lock().unwrap_or_else(|e| e.into_inner());
+    st.hint = None;
+    st.wake = true;
+    cvar.notify_all();
+}
+
+/// 立即触发一次检测，reason 作为下一次状态变化的原因
+pub fn notify(reason: Option<String>) {
+    let (lock, cvar) = watch();
+    let mut st = lock.lock().unwrap_or_else(|e| e.into_inner());
+    if reason.is_some() {
+        st.pending_reason = reason;
+    }
+    st.wake = true;
+    cvar.notify_all();
+}
+
+fn active_hint() -> Option<TransitionHint> {
+    let st = watch().0.lock().unwrap_or_else(|e| e.into_inner());
+    st.hint.clone().filter(|h| {
+        let timeout = match h.state {
+            ServiceState::Stopping => STOPPING_TIMEOUT,
+            _ => STARTING_TIMEOUT,
+        };
+        h.since.elapsed() < timeout
+    })
+}
+
+/// 根据采集到的状态、过渡标记和守护状态判定服务状态，返回 (状态, 原因)
+pub fn classify(status: &ServiceStatus) -> (ServiceState, String) {
+    if let Some(hint) = active_hint() {
+        match hint.state {
+            ServiceState::Starting if !status.running => return (hint.state, hint.reason),
+            ServiceState::Stopping if status.running => return (hint.state, hint.reason),
+            _ => {}
+        }
+    }
+
+    if status.running {
+        return classify_running(status);
+    }
+
+    if let Some(owner) = status.port_owner.as_ref().filter(|o| !o.is_gateway) {
+        return (
+            ServiceState::Occupied,
+            format!("端口 {} 被其他程序 {} 占用", status.port, owner.describe()),
+        );
+    }
+
+    let sup = supervisor::status();
+    let last_reason = sup
+        .last_exit
+        .as_ref()
+        .map(|e| e.reason.clone())
+        .unwrap_or_default();
+    if sup.crash_loop {
+        return (
+            ServiceState::Crashed,
+            format!("崩溃循环，已停止自动重启: {}", last_reason),
+        );
+    }
+    if sup.next_restart_at.is_some() {
+        return (
+            ServiceState::Crashed,
+            format!("意外退出，等待自动重启: {}", last_reason),
+        );
+    }
+    if let Some(pid) = sup.pid {
+        return (
+            ServiceState::Degraded,
+            format!(
+                "Gateway 进程 (PID {}) 存在但端口 {} 未监听",
+                pid, status.port
+            ),
+        );
+    }
+    match &sup.last_exit {
+        Some(exit) if !exit.expected => (
+            ServiceState::Crashed,
+            format!("Gateway 意外退出: {}", exit.reason),
+        ),
+        _ => (ServiceState::Stopped, "Gateway 未运行".to_string()),
+    }
+}
+
+/// 端口已由 Gateway 监听时，根据健康检查细分状态
+fn classify_running(status: &ServiceStatus) -> (ServiceState, String) {
+    let Some(health) = &status.health else {
+        return (
+            ServiceState::Running,
+            format!("端口 {} 正在监听", status.port),
+        );
+    };
+    match health.state {
+        HealthState::Ready => (
+            ServiceState::Running,
+            format!("端口 {} 已就绪 ({})", status.port, health.message),
+        ),
+        HealthState::Degraded => (ServiceState::Degraded, health.message.clone()),
+        HealthState::AuthRequired => (
+            ServiceState::Running,
+            format!("端口 {} 已响应 ({})", status.port, health.message),
+        ),
+        HealthState::Unknown => (
+            ServiceState::Running,
+            format!("端口 {} 正在监听 ({})", status.port, health.message),
+        ),
+        // 刚启动时加载插件属于正常过程，长时间未就绪则视为异常
+        HealthState::NotReady | HealthState::Unreachable => {
+            if status.uptime_seconds.unwrap_or(0) < NOT_READY_GRACE.as_secs() {
+                (ServiceState::Starting, health.message.clone())
+            } else {
+                (
+                    ServiceState::Degraded,
+                    format!("Gateway 长时间未就绪: {}", health.message),
+                )
+            }
+        }
+    }
+}
+
+/// 检测线程：被唤醒或超时后采集状态，状态变化时推送事件
+fn watch_loop(app: AppHandle) {
+    loop {
+        let mut status = gateway::collect_status();
+        let (state, reason) = classify(&status);
+        status.state = state;
+
+        let (lock, cvar) = watch();
+        let mut st = lock.lock().unwrap_or_else(|e| e.into_inner());
+        let pending_reason = st.pending_reason.take();
+        if st.current != Some(state) {
+            let previous = st.current.replace(state);
+            let reason = pending_reason.unwrap_or(reason);
+            info!("[状态监测] {:?} -> {:?}: {}", previous, state, reason);
+            history::record_state(state, status.pid, &reason);
+            let event = ServiceStateEvent {
+                state,
+                previous,
+                reason,
+                changed_at: Local::now().to_rfc3339(),
+                status,
+            };
+            if let Err(e) = app.emit(SERVICE_STATE_EVENT, event) {
+                warn!("[状态监测] 推送状态事件失败: {}", e);
+            }
+        }
+
+        if !st.wake {
+            st = cvar
+                .wait_timeout(st, POLL_INTERVAL)
+                .map(|(guard, _)| guard)
+                .unwrap_or_else(|e| e.into_inner().0);
+        }
+        if st.wake {
+            debug!("[状态监测] 收到唤醒");
+        }
+        st.wake = false;
+    }
+}
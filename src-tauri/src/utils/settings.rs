@@ -0,0 +1,43 @@
+use crate::models::ManagerSettings;
+use crate::utils::{file, platform};
+use log::warn;
+use std::sync::{Mutex, OnceLock};
+
+/// 串行化 manager.json 的读-改-写，避免后台任务与命令互相覆盖
+static SETTINGS_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+/// 读取 Manager 配置（文件不存在或损坏时返回默认值）
+pub fn load() -> ManagerSettings {
+    load_strict().unwrap_or_else(|e| {
+        warn!("[Manager 配置] {}，使用默认值", e);
+        ManagerSettings::default()
+    })
+}
+
+/// 读取 Manager 配置（文件不存在时返回默认值，读取或解析失败时返回错误）
+fn load_strict() -> Result<ManagerSettings, String> {
+    let path = platform::get_manager_config_path();
+    if !file::file_exists(&path) {
+        return Ok(ManagerSettings::default());
+    }
+    let content = file::read_file(&path).map_err(|e| format!("读取 {} 失败: {}", path, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("解析 {} 失败: {}", path, e))
+}
+
+/// 修改并保存 Manager 配置（现有文件无法解析时拒绝保存，避免用默认值覆盖其他设置）
+pub fn update<F>(mutate: F) -> Result<ManagerSettings, String>
+where
+    F: FnOnce(&mut ManagerSettings),
+{
+    let lock = SETTINGS_LOCK.get_or_init(|| Mutex::new(()));
+    let _guard = lock.lock().map_err(|_| "Manager 配置锁异常".to_string())?;
+
+    let mut settings = load_strict().map_err(|e| format!("{}，已取消保存以免覆盖其他设置", e))?;
+    mutate(&mut settings);
+
+    let content = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("序列化 Manager 配置失败: {}", e))?;
+    file::write_file(&platform::get_manager_config_path(), &content)
+        .map_err(|e| format!("写入 Manager 配置失败: {}", e))?;
+    Ok(settings)
+}
@@ -1,11 +1,13 @@
-use crate::utils::file;
+use crate::utils::config_repo;
+use crate::utils::dotenv;
+use crate::utils::gateway;
+use crate::utils::logfile;
 use crate::utils::platform;
 use log::{debug, info, warn};
-use std::collections::HashMap;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Output};
+use std::process::{Child, Command, Output, Stdio};
 use std::sync::{Mutex, OnceLock};
 
 #[cfg(windows)]
@@ -1275,6 +1277,7 @@ pub fn run_openclaw_with_cwd(args: &[&str], cwd: &Path) -> Result<String, String
     })?;
 
     let extended_path = get_extended_path();
+    let gateway_port = gateway::get_gateway_port().to_string();
 
     let output = if openclaw_path.ends_with(".cmd") {
         let mut cmd_args = vec!["/c", &openclaw_path];
@@ -1282,9 +1285,10 @@ pub fn run_openclaw_with_cwd(args: &[&str], cwd: &Path) -> Result<String, String
         let mut cmd = Command::new("cmd");
         cmd.args(&cmd_args)
             .current_dir(cwd)
-            .env("OPENCLAW_GATEWAY_TOKEN", DEFAULT_GATEWAY_TOKEN)
+            .env("OPENCLAW_GATEWAY_PORT", &gateway_port)
             .env("PATH", &extended_path)
             .env("NPM_CONFIG_REGISTRY", NPM_REGISTRY_MIRROR);
+        apply_gateway_token(&mut cmd);
         #[cfg(windows)]
         cmd.creation_flags(CREATE_NO_WINDOW);
         cmd.output()
@@ -1292,9 +1296,10 @@ pub fn run_openclaw_with_cwd(args: &[&str], cwd: &Path) -> Result<String, String
         let mut cmd = Command::new(&openclaw_path);
         cmd.args(args)
             .current_dir(cwd)
-            .env("OPENCLAW_GATEWAY_TOKEN", DEFAULT_GATEWAY_TOKEN)
+            .env("OPENCLAW_GATEWAY_PORT", &gateway_port)
             .env("PATH", &extended_path)
             .env("NPM_CONFIG_REGISTRY", NPM_REGISTRY_MIRROR);
+        apply_gateway_token(&mut cmd);
         #[cfg(windows)]
         cmd.creation_flags(CREATE_NO_WINDOW);
         cmd.output()
@@ -1329,15 +1334,19 @@ pub fn run_openclaw(args: &[&str]) -> Result<String, String> {
     let extended_path = get_extended_path();
     debug!("[Shell] 扩展 PATH: {}", extended_path);
 
+    // CLI 子命令（gateway stop / health 等）需要知道 Gateway 实际端口
+    let gateway_port = gateway::get_gateway_port().to_string();
+
     let output = if openclaw_path.ends_with(".cmd") {
         // Windows: .cmd 文件需要通过 cmd /c 执行
         let mut cmd_args = vec!["/c", &openclaw_path];
         cmd_args.extend(args);
         let mut cmd = Command::new("cmd");
         cmd.args(&cmd_args)
-            .env("OPENCLAW_GATEWAY_TOKEN", DEFAULT_GATEWAY_TOKEN)
+            .env("OPENCLAW_GATEWAY_PORT", &gateway_port)
             .env("PATH", &extended_path)
             .env("NPM_CONFIG_REGISTRY", NPM_REGISTRY_MIRROR);
+        apply_gateway_token(&mut cmd);
 
         #[cfg(windows)]
         cmd.creation_flags(CREATE_NO_WINDOW);
@@ -1346,9 +1355,10 @@ pub fn run_openclaw(args: &[&str]) -> Result<String, String> {
     } else {
         let mut cmd = Command::new(&openclaw_path);
         cmd.args(args)
-            .env("OPENCLAW_GATEWAY_TOKEN", DEFAULT_GATEWAY_TOKEN)
+            .env("OPENCLAW_GATEWAY_PORT", &gateway_port)
             .env("PATH", &extended_path)
             .env("NPM_CONFIG_REGISTRY", NPM_REGISTRY_MIRROR);
+        apply_gateway_token(&mut cmd);
 
         #[cfg(windows)]
         cmd.creation_flags(CREATE_NO_WINDOW);
@@ -1376,43 +1386,24 @@ pub fn run_openclaw(args: &[&str]) -> Result<String, String> {
     }
 }
 
-/// 默认的 Gateway Token
-pub const DEFAULT_GATEWAY_TOKEN: &str = "openclaw-manager-local-token";
+/// CLI 子命令（gateway stop / health 等）连接 Gateway 时使用 openclaw.json 中配置的 token，
+/// 未配置 token 时不注入
+fn apply_gateway_token(cmd: &mut Command) {
+    if let Some(token) = config_repo::gateway_token() {
+        cmd.env("OPENCLAW_GATEWAY_TOKEN", token);
+    }
+}
 
 /// 国内 npm 镜像，用于 plugins install 等场景加速、减少超时
 pub const NPM_REGISTRY_MIRROR: &str = "https://registry.npmmirror.com";
 
-/// 从 ~/.openclaw/env 文件读取所有环境变量
-/// 与 shell 脚本 `source ~/.openclaw/env` 行为一致
-fn load_openclaw_env_vars() -> HashMap<String, String> {
-    let mut env_vars = HashMap::new();
-    let env_path = platform::get_env_file_path();
-
-    if let Ok(content) = file::read_file(&env_path) {
-        for line in content.lines() {
-            let line = line.trim();
-            // 跳过注释和空行
-            if line.is_empty() || line.starts_with('#') {
-                continue;
-            }
-            // 解析 export KEY=VALUE 或 KEY=VALUE 格式
-            let line = line.strip_prefix("export ").unwrap_or(line);
-            if let Some((key, value)) = line.split_once('=') {
-                let key = key.trim();
-                // 去除值周围的引号
-                let value = value.trim().trim_matches('"').trim_matches('\'');
-                env_vars.insert(key.to_string(), value.to_string());
-            }
-        }
-    }
-
-    env_vars
-}
-
 /// 后台启动 openclaw gateway
 /// 与 shell 脚本行为一致：先加载 env 文件，再启动 gateway
-pub fn spawn_openclaw_gateway() -> io::Result<()> {
-    info!("[Shell] 后台启动 openclaw gateway...");
+pub fn spawn_openclaw_gateway(port: u16, bind: &str) -> io::Result<Child> {
+    info!(
+        "[Shell] 后台启动 openclaw gateway (port={}, bind={})...",
+        port, bind
+    );
 
     let openclaw_path = get_openclaw_path().ok_or_else(|| {
         warn!("[Shell] 找不到 openclaw 命令");
@@ -1426,7 +1417,7 @@ pub fn spawn_openclaw_gateway() -> io::Result<()> {
 
     // 加载用户的 env 文件环境变量（与 shell 脚本 source ~/.openclaw/env 一致）
     info!("[Shell] 加载用户环境变量...");
-    let user_env_vars = load_openclaw_env_vars();
+    let user_env_vars = dotenv::load_openclaw_env_vars();
     info!("[Shell] 已加载 {} 个环境变量", user_env_vars.len());
     for key in user_env_vars.keys() {
         debug!("[Shell] - 环境变量: {}", key);
@@ -1438,15 +1429,17 @@ pub fn spawn_openclaw_gateway() -> io::Result<()> {
 
     // Windows 上 .cmd 文件需要通过 cmd /c 来执行
     // 设置环境变量 OPENCLAW_GATEWAY_TOKEN，这样所有子命令都能自动使用
+    let port_arg = port.to_string();
+    let gateway_args = ["gateway", "--port", &port_arg, "--bind", bind];
     let mut cmd = if openclaw_path.ends_with(".cmd") {
         info!("[Shell] Windows 模式: 使用 cmd /c 执行");
         let mut c = Command::new("cmd");
-        c.args(["/c", &openclaw_path, "gateway", "--port", "18789"]);
+        c.args(["/c", &openclaw_path]).args(gateway_args);
         c
     } else {
         info!("[Shell] Unix 模式: 直接执行");
         let mut c = Command::new(&openclaw_path);
-        c.args(["gateway", "--port", "18789"]);
+        c.args(gateway_args);
         c
     };
 
@@ -1459,6 +1452,11 @@ pub fn spawn_openclaw_gateway() -> io::Result<()> {
     // 否则插件访问 /json/version 等端点会返回 401）
     cmd.env("PATH", &extended_path);
 
+    // 输出直接写入日志文件，不经过 Manager 中转，Manager 退出后 Gateway 不会因管道断开而退出
+    let stdout = logfile::open_child_output()?;
+    let stderr = stdout.try_clone()?;
+    cmd.stdin(Stdio::null()).stdout(stdout).stderr(stderr);
+
     // Windows: 隐藏控制台窗口
     #[cfg(windows)]
     cmd.creation_flags(CREATE_NO_WINDOW);
@@ -1469,7 +1467,7 @@ pub fn spawn_openclaw_gateway() -> io::Result<()> {
     match child {
         Ok(c) => {
             info!("[Shell] ✓ Gateway 进程已启动, PID: {}", c.id());
-            Ok(())
+            Ok(c)
         }
         Err(e) => {
             warn!("[Shell] ✗ Gateway 启动失败: {}", e);
@@ -0,0 +1,616 @@
+use crate::models::{
+    ConfigIssue, EventTrigger, GatewayExitRecord, ServiceAction, ServiceState, StartError,
+    StopResult, SupervisorSettings, SupervisorStatus,
+};
+use crate::utils::{
+    config_watch, gateway, history, lifecycle, logfile, preflight, procinfo, service_watch,
+    settings, shell, systemd,
+};
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use log::{debug, error, info, warn};
+use std::collections::VecDeque;
+use std::io;
+use std::process::{Child, ExitStatus};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 保留的退出记录条数
+const MAX_HISTORY: usize = 50;
+
+/// 等待重启期间检查停止请求的间隔
+const RESTART_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+static SUPERVISOR: OnceLock<Mutex<SupervisorState>> = OnceLock::new();
+
+/// Gateway 启动参数（自动重启时沿用）
+#[derive(Debug, Clone)]
+struct LaunchSpec {
+    port: u16,
+    bind: String,
+}
+
+#[derive(Default)]
+struct SupervisorState {
+    /// 每次手动启动 +1，旧的监控线程据此判断自己是否已过期
+    generation: u64,
+    /// 本次手动启动的时间，崩溃循环只统计此后的退出
+    started_at: Option<DateTime<Local>>,
+    launch: Option<LaunchSpec>,
+    pid: Option<u32>,
+    stop_requested: bool,
+    restart_count: u32,
+    crash_loop: bool,
+    next_restart_at: Option<DateTime<Local>>,
+    history: VecDeque<GatewayExitRecord>,
+}
+
+fn state() -> &'static Mutex<SupervisorState> {
+    SUPERVISOR.get_or_init(|| Mutex::new(SupervisorState::default()))
+}
+
+/// 启动并守护 Gateway 进程，返回 PID
+/// 会重置崩溃循环状态，视为一次新的手动启动
+pub fn start(port: u16, bind: &str) -> io::Result<u32> {
+    let launch = LaunchSpec {
+        port,
+        bind: bind.to_string(),
+    };
+    logfile::reopen();
+    config_watch::mark_applied();
+    let child = shell::spawn_openclaw_gateway(launch.port, &launch.bind)?;
+    let pid = child.id();
+    logfile::write_line(
+        "manager",
+        &format!(
+            "Gateway 已启动 (PID {}, port={}, bind={})",
+            pid, launch.port, launch.bind
+        ),
+    );
+
+    let generation = {
+        let mut st = state().lock().unwrap_or_else(|e| e.into_inner());
+        st.generation += 1;
+        st.started_at = Some(Local::now());
+        st.launch = Some(launch);
+        st.pid = Some(pid);
+        st.stop_requested = false;
+        st.restart_count = 0;
+        st.crash_loop = false;
+        st.next_restart_at = None;
+        st.generation
+    };
+
+    info!("[守护] 开始守护 Gateway, PID: {}", pid);
+    thread::spawn(move || monitor(child, generation));
+    Ok(pid)
+}
+
+/// 标记即将主动停止 Gateway，受守护进程退出后不再自动重启
+pub fn request_stop() {
+    let mut st = state().lock().unwrap_or_else(|e| e.into_inner());
+    if st.pid.is_some() || st.next_restart_at.is_some() {
+        info!("[守护] 收到停止请求，取消自动重启");
+    }
+    st.stop_requested = true;
+    st.next_restart_at = None;
+}
+
+/// 停止失败时撤销停止请求：Gateway 仍在运行，之后的意外退出照常自动重启
+pub fn cancel_stop() {
+    let mut st = state().lock().unwrap_or_else(|e| e.into_inner());
+    st.stop_requested = false;
+}
+
+/// 放弃本次启动：取消自动重启并结束守护的进程（启动超时时调用，避免留下无人管理的进程）
+pub fn abort() {
+    let pid = {
+        let mut st = state().lock().unwrap_or_else(|e| e.into_inner());
+        st.stop_requested = true;
+        st.next_restart_at = None;
+        st.pid
+    };
+    if let Some(pid) = pid {
+        warn!("[守护] 放弃启动，结束 Gateway 进程 PID {}", pid);
+        if let Err(e) = lifecycle::force_kill_process(pid) {
+            warn!("[守护] 结束进程 PID {} 失败: {}", pid, e);
+        }
+    }
+}
+
+/// 获取守护状态
+pub fn status() -> SupervisorStatus {
+    let config = settings::load().supervisor;
+    let st = state().lock().unwrap_or_else(|e| e.into_inner());
+    SupervisorStatus {
+        supervising: st.pid.is_some(),
+        pid: st.pid,
+        auto_restart: config.auto_restart,
+        restart_count: st.restart_count,
+        crash_loop: st.crash_loop,
+        next_restart_at: st.next_restart_at.map(|t| t.to_rfc3339()),
+        last_exit: st.history.front().cloned(),
+        history: st.history.iter().cloned().collect(),
+    }
+}
+
+/// 启动 Gateway 并记录到事件历史，reason 作为状态变化原因推送给前端（手动启动 / 定时计划等）
+pub async fn start_with_reason(
+    trigger: EventTrigger,
+    reason: &str,
+    check_config: bool,
+) -> Result<String, StartError> {
+    let result = try_start(reason, check_config).await;
+    let pid = match &result {
+        Ok(_) => listener_pid(),
+        Err(_) => None,
+    };
+    record_outcome(ServiceAction::Start, trigger, pid, &result);
+    result
+}
+
+/// 当前监听 Gateway 端口的进程 PID
+fn listener_pid() -> Option<u32> {
+    procinfo::find_port_listener(gateway::get_gateway_port()).and_then(|l| l.pid)
+}
+
+/// 记录一次启停操作的结果
+fn record_outcome<E: std::fmt::Display>(
+    action: ServiceAction,
+    trigger: EventTrigger,
+    pid: Option<u32>,
+    result: &Result<String, E>,
+) {
+    match result {
+        Ok(message) => history::record(action, trigger, pid, true, message),
+        Err(e) => history::record(action, trigger, pid, false, &e.to_string()),
+    }
+}
+
+async fn try_start(reason: &str, check_config: bool) -> Result<String, StartError> {
+    info!("[服务] 启动服务 ({})...", reason);
+
+    // 检查是否已经运行（端口被其他程序占用不算运行中，交给端口选择处理）
+    let status = gateway::collect_status_async().await?;
+    if status.running {
+        info!("[服务] 服务已在运行中");
+        return Err("服务已在运行中".to_string().into());
+    }
+
+    // 检查 openclaw 命令是否存在
+    let openclaw_path = shell::get_openclaw_path();
+    if openclaw_path.is_none() {
+        info!("[服务] 找不到 openclaw 命令");
+        return Err(
+            "找不到 openclaw 命令，请先通过 npm install -g openclaw@latest 安装"
+                .to_string()
+                .into(),
+        );
+    }
+    info!("[服务] openclaw 路径: {:?}", openclaw_path);
+
+    // 配置有问题时 Gateway 会启动失败，只能等到端口超时，提前拦截并给出具体原因
+    if check_config {
+        check_config_before("已取消启动").await?;
+    }
+
+    launch(reason).await.map_err(StartError::from)
+}
+
+/// 校验 openclaw.json，有阻止启动的问题时返回问题列表，outcome 说明被取消的操作
+async fn check_config_before(outcome: &str) -> Result<(), StartError> {
+    let report = tauri::async_runtime::spawn_blocking(preflight::check)
+        .await
+        .map_err(|e| format!("配置校验失败: {}", e))?;
+    if report.passed {
+        return Ok(());
+    }
+    let issues: Vec<ConfigIssue> = report.errors().cloned().collect();
+    Err(StartError {
+        message: format!("配置校验未通过（{} 个问题），{}", issues.len(), outcome),
+        issues,
+    })
+}
+
+/// 启动 Gateway 进程并等待端口开始监听
+async fn launch(reason: &str) -> Result<String, String> {
+    // 已安装 systemd 用户服务时交给 systemd 启动，避免两份 Gateway 抢端口
+    let unit = systemd::status();
+    if unit.supported && unit.installed {
+        return start_via_systemd(reason).await;
+    }
+
+    // 确定监听端口（配置端口被占用时可能自动切换）
+    let listen = gateway::get_listen_info();
+    let port = gateway::resolve_start_port()?;
+    gateway::set_active_port(Some(port))?;
+
+    // 直接后台启动 gateway（不等待 doctor，避免阻塞），由守护线程负责崩溃重启
+    info!("[服务] 后台启动 gateway...");
+    service_watch::begin_transition(ServiceState::Starting, reason);
+    if let Err(e) = start(port, &listen.bind) {
+        service_watch::end_transition();
+        return Err(format!("启动服务失败: {}", e));
+    }
+
+    // 轮询等待端口开始监听（最多 15 秒）
+    info!("[服务] 等待端口 {} 开始监听...", port);
+    for i in 1..=15 {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        if let Some(listener) = procinfo::find_port_listener(port) {
+            info!("[服务] ✓ 启动成功 ({}秒), PID: {:?}", i, listener.pid);
+            service_watch::end_transition();
+            let port_note = if port != listen.port {
+                format!("（端口 {} 被占用，已改用 {}）", listen.port, port)
+            } else {
+                String::new()
+            };
+            return Ok(match listener.pid {
+                Some(pid) => format!("服务已启动，PID: {}{}", pid, port_note),
+                None => format!("服务已启动{}", port_note),
+            });
+        }
+        if i % 3 == 0 {
+            debug!("[服务] 等待中... ({}秒)", i);
+        }
+    }
+
+    info!("[服务] 等待超时，端口仍未监听，结束本次启动的进程");
+    abort();
+    let _ = gateway::set_active_port(None);
+    service_watch::end_transition();
+    Err("服务启动超时（15秒），请检查 openclaw 日志".to_string())
+}
+
+/// 通过 systemctl --user start 启动，并等待端口开始监听
+async fn start_via_systemd(reason: &str) -> Result<String, String> {
+    info!("[服务] 通过 systemd 用户服务启动...");
+    service_watch::begin_transition(ServiceState::Starting, &format!("{} (systemd)", reason));
+    config_watch::mark_applied();
+    if let Err(e) = systemd::start() {
+        service_watch::end_transition();
+        return Err(e);
+    }
+
+    let port = gateway::get_listen_info().port;
+    for i in 1..=15 {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        if let Some(listener) = procinfo::find_port_listener(port) {
+            info!("[服务] ✓ systemd 启动成功 ({}秒), PID: {:?}", i, listener.pid);
+            service_watch::end_transition();
+            return Ok(match listener.pid {
+                Some(pid) => format!("服务已通过 systemd 启动，PID: {}", pid),
+                None => "服务已通过 systemd 启动".to_string(),
+            });
+        }
+    }
+
+    service_watch::end_transition();
+    Err(format!(
+        "服务启动超时（15秒），请通过 journalctl --user -u {} 查看日志",
+        systemd::UNIT_NAME
+    ))
+}
+
+/// 停止 Gateway 并记录到事件历史，reason 作为状态变化原因推送给前端（手动停止 / 定时计划等）
+pub async fn stop_with_reason(
+    app: &tauri::AppHandle,
+    trigger: EventTrigger,
+    reason: &str,
+) -> Result<StopResult, String> {
+    let result = try_stop(app, reason).await;
+    match &result {
+        Ok(r) => history::record(ServiceAction::Stop, trigger, r.pid, true, &r.message),
+        Err(e) => history::record(ServiceAction::Stop, trigger, None, false, e),
+    }
+    result
+}
+
+async fn try_stop(app: &tauri::AppHandle, reason: &str) -> Result<StopResult, String> {
+    info!("[服务] 停止服务 ({})...", reason);
+
+    // 先通知守护线程，避免进程退出后被自动拉起
+    request_stop();
+    service_watch::begin_transition(ServiceState::Stopping, reason);
+    let result = lifecycle::stop_gateway(app).await;
+    service_watch::end_transition();
+
+    let result = match result {
+        Ok(result) if result.stopped => result,
+        Ok(result) => {
+            info!("[服务] ✗ {}", result.message);
+            cancel_stop();
+            return Err(result.message);
+        }
+        Err(e) => {
+            cancel_stop();
+            return Err(e);
+        }
+    };
+    let _ = gateway::set_active_port(None);
+    info!(
+        "[服务] ✓ {} (阶段: {:?}, 耗时 {}ms)",
+        result.message, result.phase, result.elapsed_ms
+    );
+    Ok(result)
+}
+
+/// 重启 Gateway 并记录到事件历史
+pub async fn restart_with_reason(
+    app: &tauri::AppHandle,
+    trigger: EventTrigger,
+) -> Result<String, String> {
+    let result = try_restart(app).await;
+    let pid = match &result {
+        Ok(_) => listener_pid(),
+        Err(_) => None,
+    };
+    record_outcome(ServiceAction::Restart, trigger, pid, &result);
+    result
+}
+
+async fn try_restart(app: &tauri::AppHandle) -> Result<String, String> {
+    info!("[服务] 重启服务...");
+
+    // 先校验配置再停止：配置有问题时新进程必然启动失败，保留正在运行的 Gateway
+    check_config_before("已取消重启，Gateway 保持运行")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // 由本应用守护的 Gateway 不是系统服务，直接走停止 + 启动
+    // 内部的停止和启动不单独记录，事件历史中只有一条重启记录
+    if status().supervising {
+        try_stop(app, "重启").await?;
+        return try_start("重启", false).await.map_err(|e| e.to_string());
+    }
+
+    config_watch::mark_applied();
+    let _ =
+        tauri::async_runtime::spawn_blocking(|| shell::run_openclaw(&["gateway", "restart"])).await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let status = gateway::collect_status_async().await?;
+    if status.running {
+        info!("[服务] ✓ 重启成功, PID: {:?}", status.pid);
+        Ok(format!("服务已重启，PID: {:?}", status.pid))
+    } else {
+        // 手动停止再启动
+        let _ = try_stop(app, "重启").await;
+        try_start("重启", false).await.map_err(|e| e.to_string())
+    }
+}
+
+/// 监控线程：等待子进程退出，按退避策略重启，直到主动停止或进入崩溃循环
+fn monitor(mut child: Child, generation: u64) {
+    loop {
+        let pid = child.id();
+        let started = Instant::now();
+        let exit = child.wait();
+        let uptime_seconds = started.elapsed().as_secs();
+        let config = settings::load().supervisor;
+
+        let mut st = state().lock().unwrap_or_else(|e| e.into_inner());
+        if st.generation != generation {
+            // 已有新的手动启动接管，本线程只负责回收进程
+            return;
+        }
+        st.pid = None;
+
+        let expected = st.stop_requested;
+        let (exit_code, signal, reason) = describe_exit(&exit, expected);
+        let now = Local::now();
+
+        // 窗口内之前的意外退出次数，既用于崩溃循环判定，也决定退避指数
+        let window_start = now - ChronoDuration::minutes(config.crash_loop_window_minutes as i64);
+        let since = st.started_at.map_or(window_start, |t| t.max(window_start));
+        let recent_exits = recent_unexpected_exits(&st.history, since);
+        let crash_loop = !expected && recent_exits + 1 >= config.crash_loop_max_exits.max(1);
+        let restart_delay = if expected || crash_loop || !config.auto_restart {
+            None
+        } else {
+            Some(backoff_delay(&config, recent_exits))
+        };
+
+        let record = GatewayExitRecord {
+            exited_at: now.to_rfc3339(),
+            pid,
+            exit_code,
+            signal,
+            reason: reason.clone(),
+            uptime_seconds,
+            expected,
+            restart_scheduled: restart_delay.is_some(),
+        };
+        st.history.push_front(record);
+        st.history.truncate(MAX_HISTORY);
+        logfile::write_line(
+            "manager",
+            &format!("Gateway (PID {}) 已退出: {}", pid, reason),
+        );
+        if !expected {
+            history::record(
+                ServiceAction::Crash,
+                EventTrigger::Watchdog,
+                Some(pid),
+                false,
+                &reason,
+            );
+            service_watch::notify(Some(format!("Gateway (PID {}) 意外退出: {}", pid, reason)));
+        }
+
+        if expected {
+            info!("[守护] Gateway (PID {}) 已按请求停止: {}", pid, reason);
+            return;
+        }
+        if crash_loop {
+            st.crash_loop = true;
+            error!(
+                "[守护] Gateway 在 {} 分钟内退出 {} 次，判定为崩溃循环，停止自动重启。最后原因: {}",
+                config.crash_loop_window_minutes, config.crash_loop_max_exits, reason
+            );
+            return;
+        }
+        let delay = match restart_delay {
+            Some(d) => d,
+            None => {
+                warn!("[守护] Gateway (PID {}) 意外退出: {}（未开启自动重启）", pid, reason);
+                return;
+            }
+        };
+
+        warn!(
+            "[守护] Gateway (PID {}) 意外退出: {}，{} 秒后自动重启",
+            pid,
+            reason,
+            delay.as_secs()
+        );
+        st.next_restart_at = Some(now + ChronoDuration::from_std(delay).unwrap_or_default());
+        let launch = st.launch.clone();
+        drop(st);
+
+        if !wait_for_restart(delay, generation) {
+            return;
+        }
+        let Some(launch) = launch else {
+            return;
+        };
+
+        {
+            let mut st = state().lock().unwrap_or_else(|e| e.into_inner());
+            if st.generation != generation || st.stop_requested {
+                return;
+            }
+            st.next_restart_at = None;
+        }
+
+        // 启动进程较慢，不持有锁，避免阻塞 status() / request_stop()
+        config_watch::mark_applied();
+        let spawned = shell::spawn_openclaw_gateway(launch.port, &launch.bind);
+        let mut st = state().lock().unwrap_or_else(|e| e.into_inner());
+        match spawned {
+            Ok(mut new_child) => {
+                if st.generation != generation || st.stop_requested {
+                    // 启动期间收到停止请求或被新的启动接管，刚启动的进程无人守护
+                    drop(st);
+                    info!("[守护] 自动重启期间收到停止请求，结束新进程");
+                    let _ = new_child.kill();
+                    let _ = new_child.wait();
+                    return;
+                }
+                st.restart_count += 1;
+                st.pid = Some(new_child.id());
+                info!(
+                    "[守护] ✓ Gateway 已自动重启 (第 {} 次), PID: {}",
+                    st.restart_count,
+                    new_child.id()
+                );
+                logfile::write_line(
+                    "manager",
+                    &format!(
+                        "Gateway 已自动重启 (第 {} 次, PID {})",
+                        st.restart_count,
+                        new_child.id()
+                    ),
+                );
+                let message = format!("自动重启 (第 {} 次)", st.restart_count);
+                history::record(
+                    ServiceAction::AutoRestart,
+                    EventTrigger::Watchdog,
+                    Some(new_child.id()),
+                    true,
+                    &message,
+                );
+                service_watch::begin_transition(ServiceState::Starting, &message);
+                child = new_child;
+            }
+            Err(e) => {
+                error!("[守护] ✗ Gateway 自动重启失败: {}", e);
+                history::record(
+                    ServiceAction::AutoRestart,
+                    EventTrigger::Watchdog,
+                    None,
+                    false,
+                    &e.to_string(),
+                );
+                st.history.push_front(GatewayExitRecord {
+                    exited_at: Local::now().to_rfc3339(),
+                    pid,
+                    exit_code: None,
+                    signal: None,
+                    reason: format!("自动重启失败: {}", e),
+                    uptime_seconds: 0,
+                    expected: false,
+                    restart_scheduled: false,
+                });
+                st.history.truncate(MAX_HISTORY);
+                return;
+            }
+        }
+    }
+}
+
+/// 等待重启延时，期间收到停止请求或被新的启动接管则返回 false
+fn wait_for_restart(delay: Duration, generation: u64) -> bool {
+    let deadline = Instant::now() + delay;
+    while Instant::now() < deadline {
+        thread::sleep(RESTART_POLL_INTERVAL);
+        let st = state().lock().unwrap_or_else(|e| e.into_inner());
+        if st.generation != generation || st.stop_requested {
+            return false;
+        }
+    }
+    true
+}
+
+/// 统计 since 之后已记录的意外退出次数（不含本次）
+fn recent_unexpected_exits(history: &VecDeque<GatewayExitRecord>, since: DateTime<Local>) -> u32 {
+    history
+        .iter()
+        .filter(|r| !r.expected)
+        .filter(|r| {
+            DateTime::parse_from_rfc3339(&r.exited_at)
+                .map(|t| t >= since)
+                .unwrap_or(false)
+        })
+        .count() as u32
+}
+
+/// 指数退避：initial * 2^n，不超过上限
+fn backoff_delay(config: &SupervisorSettings, recent_exits: u32) -> Duration {
+    let factor = 1u64 << recent_exits.min(16);
+    let secs = config
+        .backoff_initial_secs
+        .max(1)
+        .saturating_mul(factor)
+        .min(config.backoff_max_secs.max(1));
+    Duration::from_secs(secs)
+}
+
+/// 将退出状态转换为 (退出码, 信号, 原因描述)
+fn describe_exit(
+    exit: &io::Result<ExitStatus>,
+    expected: bool,
+) -> (Option<i32>, Option<i32>, String) {
+    let status = match exit {
+        Ok(s) => s,
+        Err(e) => return (None, None, format!("无法获取退出状态: {}", e)),
+    };
+
+    #[cfg(unix)]
+    let signal = {
+        use std::os::unix::process::ExitStatusExt;
+        status.signal()
+    };
+    #[cfg(not(unix))]
+    let signal: Option<i32> = None;
+
+    let code = status.code();
+    let reason = match (code, signal) {
+        (_, Some(sig)) if expected => format!("已停止 (信号 {})", sig),
+        (_, Some(sig)) => format!("被信号 {} 终止", sig),
+        (Some(0), _) => "正常退出 (退出码 0)".to_string(),
+        (Some(c), _) => format!("异常退出 (退出码 {})", c),
+        (None, None) => "已退出 (未知状态)".to_string(),
+    };
+    (code, signal, reason)
+}
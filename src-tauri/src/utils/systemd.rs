@@ -0,0 +1,306 @@
+use crate::models::SystemdServiceStatus;
+use crate::utils::{dotenv, gateway, platform, shell};
+use log::{info, warn};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// systemd 用户服务单元名
+pub const UNIT_NAME: &str = "openclaw-gateway.service";
+
+/// 用户单元目录：~/.config/systemd/user
+pub fn unit_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join(".config"))
+        .join("systemd")
+        .join("user")
+}
+
+/// 单元文件路径
+pub fn unit_path() -> PathBuf {
+    unit_dir().join(UNIT_NAME)
+}
+
+/// 当前系统是否支持 systemd 用户服务
+pub fn is_supported() -> bool {
+    platform::is_linux() && systemctl(&["--version"]).is_ok()
+}
+
+/// 执行 systemctl --user
+fn systemctl(args: &[&str]) -> Result<String, String> {
+    let mut full = vec!["--user"];
+    full.extend_from_slice(args);
+    shell::run_command_output("systemctl", &full)
+}
+
+/// 转义 systemd 双引号字符串中的特殊字符（% 为单元说明符，需要写成 %%）
+fn escape_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('%', "%%")
+}
+
+/// escape_value 的逆操作
+fn unescape_value(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                chars.next();
+                result.push('\n');
+            }
+            ('\\', Some(next)) | ('%', Some(next @ '%')) => {
+                chars.next();
+                result.push(next);
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// ExecStart 中的参数，含空白或特殊字符时加引号
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/._-+:=@".contains(c))
+    {
+        arg.to_string()
+    } else {
+        format!("\"{}\"", escape_value(arg))
+    }
+}
+
+/// 生成单元文件内容
+/// 环境变量取自 ~/.openclaw/env，并带上扩展 PATH，保证 systemd 环境下能找到 node
+pub fn generate_unit() -> Result<String, String> {
+    let openclaw_path = shell::get_openclaw_path().ok_or_else(|| {
+        "找不到 openclaw 命令，请先通过 npm install -g openclaw@latest 安装".to_string()
+    })?;
+    let listen = gateway::get_listen_info();
+
+    let exec_start = [
+        openclaw_path.as_str(),
+        "gateway",
+        "--port",
+        &listen.port.to_string(),
+        "--bind",
+        &listen.bind,
+    ]
+    .iter()
+    .map(|a| quote_arg(a))
+    .collect::<Vec<_>>()
+    .join(" ");
+
+    let mut env_vars: Vec<(String, String)> =
+        dotenv::load_openclaw_env_vars().into_iter().collect();
+    env_vars.sort();
+    let mut environment = vec![format!(
+        "Environment=\"PATH={}\"",
+        escape_value(&shell::get_extended_path())
+    )];
+    environment.extend(
+        env_vars
+            .iter()
+            .filter(|(k, _)| k != "PATH")
+            .map(|(k, v)| format!("Environment=\"{}={}\"", k, escape_value(v))),
+    );
+
+    Ok(format!(
+        "# 由 OpenClaw Manager 生成，修改 ~/.openclaw/env 后需在 Manager 中重新安装服务\n\
+         [Unit]\n\
+         Description=OpenClaw Gateway\n\
+         After=network-online.target\n\
+         Wants=network-online.target\n\
+         \n\
+         [Service]\n\
+         Type=simple\n\
+         ExecStart={}\n\
+         Restart=on-failure\n\
+         RestartSec=5\n\
+         {}\n\
+         \n\
+         [Install]\n\
+         WantedBy=default.target\n",
+        exec_start,
+        environment.join("\n")
+    ))
+}
+
+/// 已安装单元文件中的 Environment= 变量，即 systemd 启动 Gateway 时实际传入的环境
+/// （安装时从 env 文件快照，之后修改 env 文件不会生效）；单元文件不存在时返回 None
+pub fn unit_environment() -> Option<Vec<(String, String)>> {
+    let content = fs::read_to_string(unit_path()).ok()?;
+    Some(
+        content
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("Environment="))
+            .filter_map(|value| value.strip_prefix('"')?.strip_suffix('"'))
+            .filter_map(|value| {
+                let (key, value) = value.split_once('=')?;
+                Some((key.to_string(), unescape_value(value)))
+            })
+            .collect(),
+    )
+}
+
+/// 写入单元文件并重新加载 systemd
+pub fn install() -> Result<PathBuf, String> {
+    let content = generate_unit()?;
+    let path = unit_path();
+    fs::create_dir_all(unit_dir()).map_err(|e| format!("创建目录失败: {}", e))?;
+
+    // 单元文件包含 API Key 等环境变量，创建时即仅允许当前用户读取；
+    // 已存在的文件在写入内容前先收紧权限
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(&path)
+        .and_then(|mut file| {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                file.set_permissions(fs::Permissions::from_mode(0o600))?;
+            }
+            file.write_all(content.as_bytes())
+        })
+        .map_err(|e| format!("写入单元文件失败: {}", e))?;
+
+    systemctl(&["daemon-reload"]).map_err(|e| format!("systemctl daemon-reload 失败: {}", e))?;
+    info!("[systemd] ✓ 已安装 {}", path.display());
+    Ok(path)
+}
+
+/// 已安装的单元文件与按当前配置生成的内容是否不同（端口、绑定模式或环境变量已修改）
+fn is_stale() -> bool {
+    let Ok(installed) = fs::read_to_string(unit_path()) else {
+        return false;
+    };
+    generate_unit().is_ok_and(|expected| expected != installed)
+}
+
+/// 监听或访问设置修改后重新生成已安装的单元文件，否则 systemd 重启 Gateway 时仍使用旧的端口和绑定模式
+/// 未安装或内容未变化时不做任何事，返回是否重新生成
+pub fn sync_unit() -> Result<bool, String> {
+    if !is_supported() || !unit_path().exists() || !is_stale() {
+        return Ok(false);
+    }
+    install()?;
+    Ok(true)
+}
+
+/// 设置开机（登录）自启，可选立即启动
+pub fn enable(start_now: bool) -> Result<(), String> {
+    let mut args = vec!["enable"];
+    if start_now {
+        args.push("--now");
+    }
+    args.push(UNIT_NAME);
+    systemctl(&args)
+        .map(|_| ())
+        .map_err(|e| format!("启用服务失败: {}", e))
+}
+
+/// 取消自启并停止服务
+pub fn disable() -> Result<(), String> {
+    systemctl(&["disable", "--now", UNIT_NAME])
+        .map(|_| ())
+        .map_err(|e| format!("禁用服务失败: {}", e))
+}
+
+/// 启动服务
+pub fn start() -> Result<(), String> {
+    systemctl(&["start", UNIT_NAME])
+        .map(|_| ())
+        .map_err(|e| format!("systemctl start 失败: {}", e))
+}
+
+/// 停止服务
+pub fn stop() -> Result<(), String> {
+    systemctl(&["stop", UNIT_NAME])
+        .map(|_| ())
+        .map_err(|e| format!("systemctl stop 失败: {}", e))
+}
+
+/// 停止、禁用并删除单元文件
+pub fn remove() -> Result<(), String> {
+    if let Err(e) = systemctl(&["disable", "--now", UNIT_NAME]) {
+        warn!("[systemd] 禁用服务失败（继续删除）: {}", e);
+    }
+    let path = unit_path();
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("删除单元文件失败: {}", e))?;
+    }
+    let _ = systemctl(&["daemon-reload"]);
+    let _ = systemctl(&["reset-failed", UNIT_NAME]);
+    info!("[systemd] ✓ 已删除 {}", path.display());
+    Ok(())
+}
+
+/// 查询 systemd 用户服务状态
+pub fn status() -> SystemdServiceStatus {
+    let path = unit_path();
+    let mut status = SystemdServiceStatus {
+        supported: is_supported(),
+        unit_name: UNIT_NAME.to_string(),
+        unit_path: path.display().to_string(),
+        installed: path.exists(),
+        ..SystemdServiceStatus::default()
+    };
+    status.stale = status.installed && is_stale();
+    if !status.supported || !status.installed {
+        return status;
+    }
+
+    let props: HashMap<String, String> = systemctl(&[
+        "show",
+        UNIT_NAME,
+        "--property=ActiveState,SubState,UnitFileState,MainPID",
+    ])
+    .unwrap_or_default()
+    .lines()
+    .filter_map(|l| l.split_once('='))
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+
+    status.active_state = props.get("ActiveState").cloned();
+    status.sub_state = props.get("SubState").cloned();
+    status.enabled = props
+        .get("UnitFileState")
+        .map(|s| s == "enabled")
+        .unwrap_or(false);
+    status.active = status.active_state.as_deref() == Some("active");
+    status.main_pid = props
+        .get("MainPID")
+        .and_then(|p| p.parse::<u32>().ok())
+        .filter(|p| *p > 0);
+
+    // 未开启 linger 时，用户退出登录后服务会被停止
+    if let Ok(user) = std::env::var("USER") {
+        status.linger =
+            shell::run_command_output("loginctl", &["show-user", &user, "--property=Linger"])
+                .ok()
+                .map(|s| s.trim() == "Linger=yes");
+    }
+    status
+}
+
+/// 判断进程是否运行在本服务的 cgroup 中
+pub fn owns_pid(pid: u32) -> bool {
+    if !platform::is_linux() {
+        return false;
+    }
+    fs::read_to_string(format!("/proc/{}/cgroup", pid))
+        .map(|c| c.contains(UNIT_NAME))
+        .unwrap_or(false)
+}
@@ -0,0 +1,315 @@
+use crate::models::GatewayTlsStatus;
+use crate::utils::{config_repo, gateway, platform};
+use chrono::{DateTime, Datelike, Duration, Utc};
+use log::info;
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DistinguishedName, DnType,
+    ExtendedKeyUsagePurpose, IsCa, KeyPair, KeyUsagePurpose, SerialNumber,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 本地 CA 名称（之后签发证书时按同样的名称重建 CA，需要保持不变）
+const CA_COMMON_NAME: &str = "OpenClaw Manager Local CA";
+
+const CA_ORGANIZATION: &str = "OpenClaw Manager";
+
+/// 本地 CA 有效期（天）
+const CA_VALID_DAYS: i64 = 3650;
+
+/// 服务器证书有效期（天），iOS / macOS 不信任超过 825 天的服务器证书
+const CERT_VALID_DAYS: i64 = 825;
+
+/// 服务器证书剩余有效期少于该天数时提示重新签发
+const RENEW_BEFORE_DAYS: i64 = 30;
+
+const CA_CERT_FILE: &str = "ca.pem";
+const CA_KEY_FILE: &str = "ca-key.pem";
+const CERT_FILE: &str = "server.pem";
+const KEY_FILE: &str = "server-key.pem";
+const STATE_FILE: &str = "tls.json";
+
+/// 证书信息（tls.json），读取状态时不需要解析证书
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct TlsState {
+    ca_fingerprint: Option<String>,
+    ca_expires_at: Option<DateTime<Utc>>,
+    cert_names: Vec<String>,
+    cert_expires_at: Option<DateTime<Utc>>,
+}
+
+fn tls_path(name: &str) -> PathBuf {
+    platform::get_tls_dir().join(name)
+}
+
+fn path_string(name: &str) -> String {
+    tls_path(name).to_string_lossy().to_string()
+}
+
+fn load_state() -> TlsState {
+    fs::read_to_string(tls_path(STATE_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &TlsState) -> Result<(), String> {
+    let content =
+        serde_json::to_string_pretty(state).map_err(|e| format!("序列化证书信息失败: {}", e))?;
+    write_pem(STATE_FILE, &content, false)
+}
+
+/// 写入证书目录下的文件，私钥创建时即只允许当前用户读写
+fn write_pem(name: &str, content: &str, private: bool) -> Result<(), String> {
+    let path = tls_path(name);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    if private {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+    }
+    options
+        .open(&path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|e| format!("写入 {} 失败: {}", path.display(), e))
+}
+
+/// 设置证书有效期（从昨天开始，避免设备时钟略慢时证书尚未生效），返回过期时间
+fn set_validity(params: &mut CertificateParams, days: i64) -> DateTime<Utc> {
+    let now = Utc::now();
+    let start = (now - Duration::days(1)).date_naive();
+    let end = (now + Duration::days(days)).date_naive();
+    params.not_before = rcgen::date_time_ymd(start.year(), start.month() as u8, start.day() as u8);
+    params.not_after = rcgen::date_time_ymd(end.year(), end.month() as u8, end.day() as u8);
+    end.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+}
+
+fn random_serial() -> Result<SerialNumber, String> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("读取系统随机数失败: {}", e))?;
+    // 序列号必须为正数
+    bytes[0] &= 0x7f;
+    Ok(SerialNumber::from_slice(&bytes))
+}
+
+/// 证书 SHA-256 指纹（AB:CD:... 格式，与系统证书详情中显示的一致）
+fn fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn ca_params() -> CertificateParams {
+    let mut params = CertificateParams::default();
+    let mut name = DistinguishedName::new();
+    name.push(DnType::CommonName, CA_COMMON_NAME);
+    name.push(DnType::OrganizationName, CA_ORGANIZATION);
+    params.distinguished_name = name;
+    params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+    params.key_usages = vec![
+        KeyUsagePurpose::KeyCertSign,
+        KeyUsagePurpose::CrlSign,
+        KeyUsagePurpose::DigitalSignature,
+    ];
+    params
+}
+
+/// 加载本地 CA，不存在时生成
+fn ensure_ca(state: &mut TlsState) -> Result<(Certificate, KeyPair), String> {
+    let key_path = tls_path(CA_KEY_FILE);
+    if key_path.exists() && tls_path(CA_CERT_FILE).exists() {
+        let pem = fs::read_to_string(&key_path).map_err(|e| format!("读取 CA 私钥失败: {}", e))?;
+        let key = KeyPair::from_pem(&pem).map_err(|e| format!("CA 私钥格式不正确: {}", e))?;
+        // 签发证书只用到 CA 的名称和密钥，按生成时的参数重建即可，不会改动 ca.pem
+        let cert = ca_params()
+            .self_signed(&key)
+            .map_err(|e| format!("加载 CA 失败: {}", e))?;
+        return Ok((cert, key));
+    }
+
+    info!("[HTTPS] 生成本地 CA...");
+    fs::create_dir_all(platform::get_tls_dir()).map_err(|e| format!("创建证书目录失败: {}", e))?;
+    let key = KeyPair::generate().map_err(|e| format!("生成 CA 密钥失败: {}", e))?;
+    let mut params = ca_params();
+    params.serial_number = Some(random_serial()?);
+    let expires_at = set_validity(&mut params, CA_VALID_DAYS);
+    let cert = params
+        .self_signed(&key)
+        .map_err(|e| format!("生成 CA 证书失败: {}", e))?;
+    write_pem(CA_KEY_FILE, &key.serialize_pem(), true)?;
+    write_pem(CA_CERT_FILE, &cert.pem(), false)?;
+
+    state.ca_fingerprint = Some(fingerprint(cert.der()));
+    state.ca_expires_at = Some(expires_at);
+    // 新 CA 无法验证旧的服务器证书
+    state.cert_names.clear();
+    state.cert_expires_at = None;
+    info!("[HTTPS] ✓ 本地 CA 已生成");
+    Ok((cert, key))
+}
+
+/// 本机的主机名和 IP（服务器证书需要包含的名称）
+pub fn machine_names() -> Vec<String> {
+    let mut names = vec!["localhost".to_string()];
+    let host = gethostname::gethostname()
+        .to_string_lossy()
+        .trim()
+        .to_lowercase();
+    let valid_host = !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.');
+    if valid_host && host != "localhost" {
+        // 手机通过 mDNS 访问时使用 <主机名>.local
+        if !host.contains('.') {
+            names.push(format!("{}.local", host));
+        }
+        names.push(host);
+    }
+    names.push("127.0.0.1".to_string());
+    names.push("::1".to_string());
+    names.extend(gateway::lan_addresses().iter().map(|ip| ip.to_string()));
+    names
+}
+
+/// 签发覆盖本机主机名和 IP 的服务器证书（本地 CA 不存在时先生成）
+/// 本机 IP 变化后需要重新签发
+pub fn issue_certificate() -> Result<GatewayTlsStatus, String> {
+    let mut state = load_state();
+    let (ca_cert, ca_key) = ensure_ca(&mut state)?;
+
+    let names = machine_names();
+    info!("[HTTPS] 签发服务器证书: {:?}", names);
+    let mut params =
+        CertificateParams::new(names.clone()).map_err(|e| format!("证书主机名无效: {}", e))?;
+    let mut name = DistinguishedName::new();
+    name.push(DnType::CommonName, "OpenClaw Gateway");
+    name.push(DnType::OrganizationName, CA_ORGANIZATION);
+    params.distinguished_name = name;
+    params.serial_number = Some(random_serial()?);
+    params.key_usages = vec![
+        KeyUsagePurpose::DigitalSignature,
+        KeyUsagePurpose::KeyEncipherment,
+    ];
+    params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+    params.use_authority_key_identifier_extension = true;
+    let expires_at = set_validity(&mut params, CERT_VALID_DAYS);
+
+    let key = KeyPair::generate().map_err(|e| format!("生成服务器密钥失败: {}", e))?;
+    let cert = params
+        .signed_by(&key, &ca_cert, &ca_key)
+        .map_err(|e| format!("签发服务器证书失败: {}", e))?;
+    let ca_pem = fs::read_to_string(tls_path(CA_CERT_FILE))
+        .map_err(|e| format!("读取 CA 证书失败: {}", e))?;
+
+    // server.pem 包含完整证书链（服务器证书 + CA 证书）
+    write_pem(KEY_FILE, &key.serialize_pem(), true)?;
+    write_pem(CERT_FILE, &format!("{}{}", cert.pem(), ca_pem), false)?;
+    state.cert_names = names;
+    state.cert_expires_at = Some(expires_at);
+    save_state(&state)?;
+
+    info!(
+        "[HTTPS] ✓ 服务器证书已签发，有效期至 {}",
+        expires_at.to_rfc3339()
+    );
+    Ok(status())
+}
+
+/// 读取 HTTPS 状态
+pub fn status() -> GatewayTlsStatus {
+    let state = load_state();
+    let enabled = config_repo::load().is_ok_and(|c| c.gateway_tls_enabled());
+    let ca_exists = tls_path(CA_CERT_FILE).exists();
+    let cert_exists = tls_path(CERT_FILE).exists() && tls_path(KEY_FILE).exists();
+    let current_names = machine_names();
+
+    let expiring = state
+        .cert_expires_at
+        .is_none_or(|t| t - Utc::now() < Duration::days(RENEW_BEFORE_DAYS));
+    let missing_names = current_names.iter().any(|n| !state.cert_names.contains(n));
+    let urls = if enabled {
+        let mut urls = vec![format!("https://localhost:{}", gateway::get_gateway_port())];
+        urls.extend(gateway::lan_urls());
+        urls
+    } else {
+        Vec::new()
+    };
+
+    GatewayTlsStatus {
+        enabled,
+        ca_path: ca_exists.then(|| path_string(CA_CERT_FILE)),
+        ca_fingerprint: state.ca_fingerprint.filter(|_| ca_exists),
+        ca_expires_at: state
+            .ca_expires_at
+            .filter(|_| ca_exists)
+            .map(|t| t.to_rfc3339()),
+        cert_path: cert_exists.then(|| path_string(CERT_FILE)),
+        cert_expires_at: state
+            .cert_expires_at
+            .filter(|_| cert_exists)
+            .map(|t| t.to_rfc3339()),
+        cert_names: if cert_exists {
+            state.cert_names
+        } else {
+            Vec::new()
+        },
+        current_names,
+        needs_renewal: cert_exists && (expiring || missing_names),
+        urls,
+    }
+}
+
+/// 启用 HTTPS：证书不存在或需要重新签发时先签发，再在 openclaw.json 中启用 gateway.tls
+pub fn enable() -> Result<GatewayTlsStatus, String> {
+    let current = status();
+    if current.cert_path.is_none() || current.needs_renewal {
+        issue_certificate()?;
+    }
+
+    let cert_path = path_string(CERT_FILE);
+    let key_path = path_string(KEY_FILE);
+    config_repo::update(|config| {
+        let tls = config
+            .gateway_mut()
+            .tls
+            .get_or_insert_with(Default::default);
+        tls.enabled = Some(true);
+        tls.cert_path = Some(cert_path.clone());
+        tls.key_path = Some(key_path.clone());
+        Ok(())
+    })?;
+    info!("[HTTPS] ✓ 已启用 gateway.tls");
+    Ok(status())
+}
+
+/// 停用 HTTPS（保留证书，之后可以直接重新启用）
+pub fn disable() -> Result<GatewayTlsStatus, String> {
+    config_repo::update(|config| {
+        if let Some(tls) = config.gateway.as_mut().and_then(|g| g.tls.as_mut()) {
+            tls.enabled = Some(false);
+        }
+        Ok(())
+    })?;
+    info!("[HTTPS] ✓ 已停用 gateway.tls");
+    Ok(status())
+}
+
+/// 导出 CA 证书，用于安装到手机等客户端设备
+pub fn export_ca(dest: &Path) -> Result<(), String> {
+    let source = tls_path(CA_CERT_FILE);
+    if !source.exists() {
+        return Err("尚未生成本地 CA，请先启用 HTTPS".to_string());
+    }
+    fs::copy(&source, dest).map_err(|e| format!("导出 CA 证书失败: {}", e))?;
+    info!("[HTTPS] ✓ CA 证书已导出到 {}", dest.display());
+    Ok(())
+}
@@ -2,6 +2,7 @@ import { Activity, Cpu, HardDrive, Clock } from 'lucide-react';
 import clsx from 'clsx';
 
 interface ServiceStatus {
+  state?: string;
   running: boolean;
   pid: number | null;
   port: number;
@@ -15,7 +16,22 @@ interface StatusCardProps {
   loading: boolean;
 }
 
+const STATE_LABELS: Record<string, { label: string; dot: string; text: string }> = {
+  starting: { label: '启动中', dot: 'warning', text: 'text-yellow-400' },
+  running: { label: '运行中', dot: 'running', text: 'text-green-400' },
+  degraded: { label: '异常', dot: 'warning', text: 'text-yellow-400' },
+  stopping: { label: '停止中', dot: 'warning', text: 'text-yellow-400' },
+  stopped: { label: '已停止', dot: 'stopped', text: 'text-red-400' },
+  crashed: { label: '已崩溃', dot: 'stopped', text: 'text-red-400' },
+  occupied: { label: '端口被占用', dot: 'warning', text: 'text-yellow-400' },
+};
+
 export function StatusCard({ status, loading }: StatusCardProps) {
+  const stateKey = status?.state ?? (status?.running ? 'running' : 'stopped');
+  const stateInfo = loading
+    ? { label: '检测中...', dot: 'warning', text: 'text-yellow-400' }
+    : STATE_LABELS[stateKey] ?? STATE_LABELS.stopped;
+
   const formatUptime = (seconds: number | null) => {
     if (!seconds) return '--';
     const hours = Math.floor(seconds / 3600);
@@ -29,23 +45,9 @@ export function StatusCard({ status, loading }: StatusCardProps) {
       <div className="flex items-center justify-between mb-6">
         <h3 className="text-lg font-semibold text-white">服务状态</h3>
         <div className="flex items-center gap-2">
-          <div
-            className={clsx(
-              'status-dot',
-              loading ? 'warning' : status?.running ? 'running' : 'stopped'
-            )}
-          />
-          <span
-            className={clsx(
-              'text-sm font-medium',
-              loading
-                ? 'text-yellow-400'
-                : status?.running
-                ? 'text-green-400'
-                : 'text-red-400'
-            )}
-          >
-            {loading ? '检测中...' : status?.running ? '运行中' : '已停止'}
+          <div className={clsx('status-dot', stateInfo.dot)} />
+          <span className={clsx('text-sm font-medium', stateInfo.text)}>
+            {stateInfo.label}
           </span>
         </div>
       </div>
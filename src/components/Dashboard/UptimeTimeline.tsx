@@ -0,0 +1,109 @@
+import { useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import clsx from 'clsx';
+import {
+  api,
+  isTauri,
+  SERVICE_STATE_EVENT,
+  ServiceState,
+  UptimeReport,
+} from '../../lib/tauri';
+
+const WINDOW_OPTIONS = [
+  { hours: 24, label: '24 小时' },
+  { hours: 24 * 7, label: '7 天' },
+  { hours: 24 * 30, label: '30 天' },
+];
+
+const SEGMENT_COLORS: Record<ServiceState, string> = {
+  running: 'bg-green-500',
+  degraded: 'bg-yellow-500',
+  starting: 'bg-yellow-500/60',
+  stopping: 'bg-yellow-500/60',
+  stopped: 'bg-dark-500',
+  crashed: 'bg-red-500',
+  occupied: 'bg-orange-500',
+};
+
+const STATE_NAMES: Record<ServiceState, string> = {
+  running: '运行中',
+  degraded: '异常',
+  starting: '启动中',
+  stopping: '停止中',
+  stopped: '已停止',
+  crashed: '已崩溃',
+  occupied: '端口被占用',
+};
+
+export function UptimeTimeline() {
+  const [windowHours, setWindowHours] = useState(24);
+  const [report, setReport] = useState<UptimeReport | null>(null);
+
+  useEffect(() => {
+    if (!isTauri()) return;
+    const fetchReport = () => {
+      api.getUptimeReport(windowHours).then(setReport).catch(() => {});
+    };
+    fetchReport();
+    // 状态变化时刷新时间线
+    const unlisten = listen(SERVICE_STATE_EVENT, fetchReport);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [windowHours]);
+
+  const formatDuration = (seconds: number) => {
+    const hours = Math.floor(seconds / 3600);
+    const minutes = Math.floor((seconds % 3600) / 60);
+    if (hours > 0) return `${hours}h ${minutes}m`;
+    return `${minutes}m`;
+  };
+
+  const since = report ? new Date(report.since).getTime() : 0;
+  const total = report ? new Date(report.until).getTime() - since : 0;
+
+  return (
+    <div className="bg-dark-700 rounded-2xl p-6 border border-dark-500">
+      <div className="flex items-center justify-between mb-4">
+        <h3 className="text-lg font-semibold text-white">可用率</h3>
+        <select
+          value={windowHours}
+          onChange={(e) => setWindowHours(Number(e.target.value))}
+          className="bg-dark-600 border border-dark-500 rounded-lg px-2 py-1 text-xs text-gray-300"
+        >
+          {WINDOW_OPTIONS.map((o) => (
+            <option key={o.hours} value={o.hours}>{o.label}</option>
+          ))}
+        </select>
+      </div>
+
+      <div className="flex items-baseline gap-6 mb-4">
+        <span className="text-3xl font-semibold text-white">
+          {report?.uptime_percent != null ? `${report.uptime_percent.toFixed(2)}%` : '--'}
+        </span>
+        <span className="text-xs text-gray-400">崩溃 {report?.crashes ?? 0} 次</span>
+        <span className="text-xs text-gray-400">启动 / 重启 {report?.restarts ?? 0} 次</span>
+      </div>
+
+      {/* 状态时间线，首条记录之前的时间留空 */}
+      <div className="relative h-3 rounded-full bg-dark-600 overflow-hidden">
+        {report && total > 0 &&
+          report.segments.map((segment) => {
+            const start = new Date(segment.start).getTime();
+            const end = new Date(segment.end).getTime();
+            return (
+              <div
+                key={segment.start}
+                className={clsx('absolute top-0 h-full', SEGMENT_COLORS[segment.state])}
+                style={{
+                  left: `${((start - since) / total) * 100}%`,
+                  width: `${((end - start) / total) * 100}%`,
+                }}
+                title={`${STATE_NAMES[segment.state]} · ${formatDuration(segment.duration_secs)}`}
+              />
+            );
+          })}
+      </div>
+    </div>
+  );
+}
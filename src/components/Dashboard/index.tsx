@@ -1,10 +1,18 @@
 import { useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import { motion } from 'framer-motion';
 import { StatusCard } from './StatusCard';
 import { QuickActions } from './QuickActions';
 import { SystemInfo } from './SystemInfo';
+import { UptimeTimeline } from './UptimeTimeline';
 import { Setup } from '../Setup';
-import { api, ServiceStatus, isTauri } from '../../lib/tauri';
+import {
+  api,
+  ServiceStatus,
+  isTauri,
+  SERVICE_STATE_EVENT,
+  ServiceStateEvent,
+} from '../../lib/tauri';
 import { EnvironmentStatus } from '../../App';
 
 interface DashboardProps {
@@ -32,13 +40,25 @@ export function Dashboard({ envStatus, onSetupComplete }: DashboardProps) {
     }
   };
 
+  // 状态变化由后端推送；运行中时低频刷新内存 / CPU 等指标
   useEffect(() => {
     fetchStatus();
     if (!isTauri()) return;
-    const statusInterval = setInterval(fetchStatus, 3000);
-    return () => clearInterval(statusInterval);
+    const unlisten = listen<ServiceStateEvent>(SERVICE_STATE_EVENT, (event) => {
+      setStatus(event.payload.status);
+      setLoading(false);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
   }, []);
 
+  useEffect(() => {
+    if (!isTauri() || !status?.running) return;
+    const metricsInterval = setInterval(fetchStatus, 15000);
+    return () => clearInterval(metricsInterval);
+  }, [status?.running]);
+
   const handleStart = async () => {
     if (!isTauri()) return;
     setActionLoading(true);
@@ -128,6 +148,11 @@ export function Dashboard({ envStatus, onSetupComplete }: DashboardProps) {
           />
         </motion.div>
 
+        {/* 可用率与状态时间线 */}
+        <motion.div variants={itemVariants}>
+          <UptimeTimeline />
+        </motion.div>
+
         {/* 系统信息 */}
         <motion.div variants={itemVariants}>
           <SystemInfo
@@ -0,0 +1,255 @@
+import { useEffect, useRef, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { Filter, Pause, Play, RefreshCw, Search, Server, Trash2 } from 'lucide-react';
+import clsx from 'clsx';
+import {
+  api,
+  isTauri,
+  GATEWAY_LOG_EVENT,
+  GatewayLogBatch,
+  GatewayLogEntry,
+} from '../../lib/tauri';
+
+type FilterLevel = 'all' | 'debug' | 'info' | 'warn' | 'error';
+
+const LEVEL_ORDER = ['debug', 'info', 'warn', 'error'];
+
+const LEVEL_COLORS: Record<string, string> = {
+  debug: 'text-gray-400',
+  info: 'text-green-400',
+  warn: 'text-yellow-400',
+  error: 'text-red-400',
+};
+
+const LEVEL_BG: Record<string, string> = {
+  debug: 'bg-gray-500/10',
+  info: 'bg-green-500/10',
+  warn: 'bg-yellow-500/10',
+  error: 'bg-red-500/10',
+};
+
+// 页面最多保留的条数，超出后丢弃最旧的
+const MAX_ENTRIES = 2000;
+
+// 初次加载的历史行数
+const INITIAL_LINES = 500;
+
+export function GatewayLogs() {
+  const [entries, setEntries] = useState<GatewayLogEntry[]>([]);
+  const [filter, setFilter] = useState<FilterLevel>('all');
+  const [query, setQuery] = useState('');
+  const [following, setFollowing] = useState(true);
+  const [dropped, setDropped] = useState(0);
+  const [error, setError] = useState<string | null>(null);
+  const logsEndRef = useRef<HTMLDivElement>(null);
+
+  const loadHistory = async () => {
+    try {
+      setError(null);
+      const result = query.trim()
+        ? await api.searchGatewayLogs(query.trim(), INITIAL_LINES)
+        : await api.getGatewayLogs(INITIAL_LINES);
+      setEntries(result);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  // 初始加载历史日志
+  useEffect(() => {
+    if (isTauri()) loadHistory();
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
+  // 跟随模式：订阅 gateway-log 事件
+  useEffect(() => {
+    if (!following || !isTauri()) return;
+
+    let cancelled = false;
+    let subscriptionId: number | null = null;
+    let unlisten: (() => void) | null = null;
+
+    (async () => {
+      unlisten = await listen<GatewayLogBatch>(GATEWAY_LOG_EVENT, (event) => {
+        const batch = event.payload;
+        if (batch.dropped > 0) setDropped((d) => d + batch.dropped);
+        if (batch.entries.length === 0) return;
+        setEntries((prev) => {
+          const next = prev.concat(batch.entries);
+          return next.length > MAX_ENTRIES ? next.slice(next.length - MAX_ENTRIES) : next;
+        });
+      });
+      try {
+        const id = await api.startLogStream();
+        if (cancelled) {
+          api.stopLogStream(id).catch(() => {});
+        } else {
+          subscriptionId = id;
+        }
+      } catch (e) {
+        setError(String(e));
+      }
+    })();
+
+    return () => {
+      cancelled = true;
+      unlisten?.();
+      if (subscriptionId !== null) {
+        api.stopLogStream(subscriptionId).catch(() => {});
+      }
+    };
+  }, [following]);
+
+  // 跟随时自动滚动到底部
+  useEffect(() => {
+    if (following && logsEndRef.current) {
+      logsEndRef.current.scrollIntoView({ behavior: 'auto' });
+    }
+  }, [entries, following]);
+
+  const keyword = query.trim().toLowerCase();
+  const filteredEntries = entries.filter((entry) => {
+    if (filter !== 'all' && LEVEL_ORDER.indexOf(entry.level) < LEVEL_ORDER.indexOf(filter)) {
+      return false;
+    }
+    if (keyword && !entry.raw.toLowerCase().includes(keyword)) return false;
+    return true;
+  });
+
+  const formatTime = (timestamp: string | null) => {
+    if (!timestamp) return '--:--:--';
+    const date = new Date(timestamp);
+    return date.toLocaleTimeString('zh-CN', {
+      hour12: false,
+      hour: '2-digit',
+      minute: '2-digit',
+      second: '2-digit',
+    }) + '.' + String(date.getMilliseconds()).padStart(3, '0');
+  };
+
+  return (
+    <div className="h-full flex flex-col overflow-hidden">
+      {/* 工具栏 */}
+      <div className="flex items-center gap-4 mb-4 flex-wrap">
+        <div className="flex items-center gap-2">
+          <Filter size={14} className="text-gray-500" />
+          <select
+            value={filter}
+            onChange={(e) => setFilter(e.target.value as FilterLevel)}
+            className="bg-dark-700 border border-dark-500 rounded-lg px-3 py-1.5 text-sm text-gray-300"
+          >
+            <option value="all">所有级别</option>
+            <option value="debug">Debug 及以上</option>
+            <option value="info">Info 及以上</option>
+            <option value="warn">Warn 及以上</option>
+            <option value="error">Error</option>
+          </select>
+        </div>
+
+        <div className="flex items-center gap-2 bg-dark-700 border border-dark-500 rounded-lg px-3 py-1.5">
+          <Search size={14} className="text-gray-500" />
+          <input
+            value={query}
+            onChange={(e) => setQuery(e.target.value)}
+            onKeyDown={(e) => e.key === 'Enter' && loadHistory()}
+            placeholder="搜索（回车搜索历史文件）"
+            className="bg-transparent text-sm text-gray-300 outline-none w-56"
+          />
+        </div>
+
+        <div className="flex-1" />
+
+        <div className="flex items-center gap-3 text-xs text-gray-500">
+          <span>{filteredEntries.length} / {entries.length} 条</span>
+          {dropped > 0 && (
+            <span className="text-yellow-400" title="输出过快时为保护界面而丢弃的日志">
+              已丢弃 {dropped} 条
+            </span>
+          )}
+        </div>
+
+        <div className="flex items-center gap-2">
+          <button
+            onClick={() => setFollowing(!following)}
+            className="icon-button text-gray-400 hover:text-white"
+            title={following ? '暂停跟随' : '实时跟随'}
+          >
+            {following ? <Pause size={16} /> : <Play size={16} />}
+          </button>
+          <button
+            onClick={loadHistory}
+            className="icon-button text-gray-400 hover:text-white"
+            title="重新加载"
+          >
+            <RefreshCw size={16} />
+          </button>
+          <button
+            onClick={() => {
+              setEntries([]);
+              setDropped(0);
+            }}
+            className="icon-button text-gray-400 hover:text-red-400"
+            title="清空显示"
+          >
+            <Trash2 size={16} />
+          </button>
+        </div>
+      </div>
+
+      {/* 日志列表 */}
+      <div className="flex-1 bg-dark-800 rounded-xl border border-dark-600 overflow-hidden flex flex-col">
+        <div className="flex items-center gap-2 px-4 py-2 bg-dark-700 border-b border-dark-600">
+          <Server size={14} className="text-gray-500" />
+          <span className="text-xs text-gray-400 font-medium">Gateway 日志</span>
+          {following && (
+            <span className="text-[10px] text-green-400">● 实时</span>
+          )}
+        </div>
+
+        <div className="flex-1 overflow-y-auto p-2 font-mono text-xs">
+          {error ? (
+            <div className="h-full flex items-center justify-center text-red-400">{error}</div>
+          ) : filteredEntries.length === 0 ? (
+            <div className="h-full flex items-center justify-center text-gray-500">
+              <div className="text-center">
+                <Server size={32} className="mx-auto mb-2 opacity-50" />
+                <p>暂无 Gateway 日志</p>
+              </div>
+            </div>
+          ) : (
+            <>
+              {filteredEntries.map((entry, index) => (
+                <div
+                  key={`${entry.timestamp}-${index}`}
+                  className={clsx('py-1 px-2 rounded mb-0.5', LEVEL_BG[entry.level])}
+                >
+                  <div className="flex items-start gap-2">
+                    <span className="text-gray-600 flex-shrink-0">
+                      {formatTime(entry.timestamp)}
+                    </span>
+                    <span className={clsx(
+                      'px-1.5 py-0.5 rounded text-[10px] uppercase flex-shrink-0',
+                      LEVEL_COLORS[entry.level]
+                    )}>
+                      {entry.level}
+                    </span>
+                    {entry.subsystem && (
+                      <span className="flex-shrink-0 text-blue-400">[{entry.subsystem}]</span>
+                    )}
+                    {entry.stream === 'manager' && (
+                      <span className="flex-shrink-0 text-purple-400">[Manager]</span>
+                    )}
+                    <span className="text-gray-300 break-all whitespace-pre-wrap">
+                      {entry.message}
+                    </span>
+                  </div>
+                </div>
+              ))}
+              <div ref={logsEndRef} />
+            </>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
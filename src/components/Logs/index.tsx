@@ -9,9 +9,12 @@ import {
 } from 'lucide-react';
 import clsx from 'clsx';
 import { logStore, LogEntry } from '../../lib/logger';
+import { GatewayLogs } from './GatewayLogs';
 
 type FilterLevel = 'all' | 'debug' | 'info' | 'warn' | 'error';
 
+type LogSource = 'gateway' | 'app';
+
 const LEVEL_COLORS: Record<string, string> = {
   debug: 'text-gray-400',
   info: 'text-green-400',
@@ -39,6 +42,38 @@ const MODULE_COLORS: Record<string, string> = {
 };
 
 export function Logs() {
+  const [source, setSource] = useState<LogSource>('gateway');
+
+  return (
+    <div className="h-full flex flex-col overflow-hidden">
+      {/* 日志来源切换 */}
+      <div className="flex items-center gap-2 mb-4">
+        {([
+          ['gateway', 'Gateway 日志'],
+          ['app', '应用日志'],
+        ] as [LogSource, string][]).map(([value, label]) => (
+          <button
+            key={value}
+            onClick={() => setSource(value)}
+            className={clsx(
+              'px-3 py-1.5 rounded-lg text-sm transition-colors',
+              source === value
+                ? 'bg-claw-500/20 text-claw-400'
+                : 'text-gray-400 hover:text-white hover:bg-dark-700'
+            )}
+          >
+            {label}
+          </button>
+        ))}
+      </div>
+      <div className="flex-1 overflow-hidden">
+        {source === 'gateway' ? <GatewayLogs /> : <AppLogs />}
+      </div>
+    </div>
+  );
+}
+
+function AppLogs() {
   const [logs, setLogs] = useState<LogEntry[]>([]);
   const [filter, setFilter] = useState<FilterLevel>('all');
   const [moduleFilter, setModuleFilter] = useState<string>('all');
@@ -3,6 +3,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { Play, Square, RotateCcw, Loader2 } from 'lucide-react';
 import clsx from 'clsx';
 import { serviceLogger } from '../../lib/logger';
+import { formatError } from '../../lib/tauri';
 
 export function ServiceManager() {
   const [actionLoading, setActionLoading] = useState<string | null>(null);
@@ -18,7 +19,7 @@ export function ServiceManager() {
       serviceLogger.info(`✅ ${action} 操作成功`, result);
     } catch (e) {
       serviceLogger.error(`❌ ${action} 操作失败`, e);
-      alert(`操作失败: ${e}`);
+      alert(`操作失败: ${formatError(e)}`);
     } finally {
       setActionLoading(null);
     }
@@ -1,6 +1,7 @@
 import { useEffect, useCallback } from 'react';
 import { useAppStore } from '../stores/appStore';
-import { api } from '../lib/tauri';
+import { listen } from '@tauri-apps/api/event';
+import { api, isTauri, SERVICE_STATE_EVENT, ServiceStateEvent } from '../lib/tauri';
 import { serviceLogger } from '../lib/logger';
 
 export function useService() {
@@ -58,16 +59,22 @@ export function useService() {
     }
   }, [fetchStatus]);
 
-  // 自动刷新状态
+  // 初始获取一次，之后由后端在状态变化时推送
   useEffect(() => {
-    serviceLogger.debug('启动状态自动刷新');
     fetchStatus();
-    const interval = setInterval(fetchStatus, 3000);
+    if (!isTauri()) return;
+
+    serviceLogger.debug('订阅服务状态事件');
+    const unlisten = listen<ServiceStateEvent>(SERVICE_STATE_EVENT, (event) => {
+      const { state, previous, reason, status } = event.payload;
+      serviceLogger.state('服务状态变化', { previous, state, reason });
+      setServiceStatus(status);
+    });
     return () => {
-      serviceLogger.debug('停止状态自动刷新');
-      clearInterval(interval);
+      serviceLogger.debug('取消订阅服务状态事件');
+      unlisten.then((fn) => fn());
     };
-  }, [fetchStatus]);
+  }, [fetchStatus, setServiceStatus]);
 
   return {
     status: serviceStatus,
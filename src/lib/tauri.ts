@@ -23,15 +23,501 @@ async function invokeWithLog<T>(cmd: string, args?: Record<string, unknown>): Pr
 }
 
 // 服务状态
+export type ServiceState =
+  | 'starting'
+  | 'running'
+  | 'degraded'
+  | 'stopping'
+  | 'stopped'
+  | 'crashed'
+  | 'occupied';
+
+// 端口占用进程
+export interface PortOwner {
+  pid: number | null;
+  name: string | null;
+  exe: string | null;
+  command_line: string | null;
+  is_gateway: boolean;
+  /** 无法获取进程信息时为 false */
+  verified: boolean;
+}
+
 export interface ServiceStatus {
+  state: ServiceState;
   running: boolean;
   pid: number | null;
   port: number;
+  bind: string;
   uptime_seconds: number | null;
   memory_mb: number | null;
   cpu_percent: number | null;
+  thread_count: number | null;
+  command_line: string | null;
+  /** systemd | manager | external，未运行时为 null */
+  managed_by: string | null;
+  /** 端口占用者；不是 Gateway 时 running 为 false */
+  port_owner: PortOwner | null;
+  /** HTTP 健康检查结果（仅运行中时探测） */
+  health: GatewayHealth | null;
+}
+
+// Gateway 健康状态
+export type HealthState =
+  | 'unreachable'
+  | 'not_ready'
+  | 'ready'
+  | 'degraded'
+  | 'auth_required'
+  | 'unknown';
+
+export interface GatewayHealth {
+  state: HealthState;
+  latency_ms: number | null;
+  http_status: number | null;
+  endpoint: string | null;
+  degraded_channels: string[];
+  message: string;
+  checked_at: string;
+}
+
+// 服务状态变化事件（service-state-changed）
+export interface ServiceStateEvent {
+  state: ServiceState;
+  previous: ServiceState | null;
+  reason: string;
+  changed_at: string;
+  status: ServiceStatus;
+}
+
+export const SERVICE_STATE_EVENT = 'service-state-changed';
+
+// 停止流程阶段
+export type StopPhase =
+  | 'already_stopped'
+  | 'systemd'
+  | 'graceful'
+  | 'force'
+  | 'terminate'
+  | 'kill';
+
+// 停止流程中的一步
+export interface StopStep {
+  phase: StopPhase;
+  action_ok: boolean;
+  output: string;
+  stopped: boolean;
+  elapsed_ms: number;
+}
+
+// 停止结果
+export interface StopResult {
+  stopped: boolean;
+  /** 最终生效的阶段 */
+  phase: StopPhase | null;
+  pid: number | null;
+  steps: StopStep[];
+  elapsed_ms: number;
+  message: string;
+}
+
+// 停止进度事件
+export interface StopProgressEvent {
+  phase: StopPhase;
+  step: number;
+  message: string;
+  pid: number | null;
+  elapsed_ms: number;
+}
+
+export const STOP_PROGRESS_EVENT = 'service-stop-progress';
+
+// Gateway 进程退出记录
+export interface GatewayExitRecord {
+  exited_at: string;
+  pid: number;
+  exit_code: number | null;
+  signal: number | null;
+  reason: string;
+  uptime_seconds: number;
+  expected: boolean;
+  restart_scheduled: boolean;
+}
+
+// Gateway 守护状态
+export interface SupervisorStatus {
+  supervising: boolean;
+  pid: number | null;
+  auto_restart: boolean;
+  restart_count: number;
+  crash_loop: boolean;
+  next_restart_at: string | null;
+  last_exit: GatewayExitRecord | null;
+  history: GatewayExitRecord[];
+}
+
+// Gateway 监听配置
+export interface GatewayListenInfo {
+  port: number;
+  bind: string;
+  /** manager | openclaw | default */
+  source: string;
+  auto_port: boolean;
+  active_port: number | null;
+}
+
+export type GatewayAuthMode = 'token' | 'password' | 'none';
+
+// Gateway 认证与网络暴露设置
+export interface GatewayAccessSettings {
+  auth_mode: GatewayAuthMode;
+  token_set: boolean;
+  password_set: boolean;
+  bind: string;
+  allowed_origins: string[];
+  /** 局域网内其他设备（如手机）访问控制台的地址 */
+  lan_urls: string[];
+  /** 当前设置存在的安全风险 */
+  risks: string[];
+}
+
+// Gateway HTTPS 状态（本地 CA 与服务器证书）
+export interface GatewayTlsStatus {
+  enabled: boolean;
+  /** 本地 CA 证书路径，需要安装到手机等客户端设备上 */
+  ca_path: string | null;
+  /** CA 证书 SHA-256 指纹，安装时用于核对 */
+  ca_fingerprint: string | null;
+  ca_expires_at: string | null;
+  cert_path: string | null;
+  cert_expires_at: string | null;
+  /** 服务器证书包含的主机名和 IP */
+  cert_names: string[];
+  /** 本机当前的主机名和 IP */
+  current_names: string[];
+  /** 证书即将过期或不包含本机当前的主机名 / IP */
+  needs_renewal: boolean;
+  /** HTTPS 访问地址 */
+  urls: string[];
+}
+
+// systemd 用户服务状态（仅 Linux）
+export interface SystemdServiceStatus {
+  supported: boolean;
+  unit_name: string;
+  unit_path: string;
+  installed: boolean;
+  enabled: boolean;
+  active: boolean;
+  active_state: string | null;
+  sub_state: string | null;
+  main_pid: number | null;
+  /** 未开启 linger 时，退出登录后服务会停止 */
+  linger: boolean | null;
+  /** 单元文件与当前配置不一致，需要重新安装服务 */
+  stale: boolean;
+}
+
+// 服务事件类型
+export type ServiceAction =
+  | 'start'
+  | 'stop'
+  | 'restart'
+  | 'crash'
+  | 'auto_restart'
+  | 'state_changed';
+
+// 事件发起方
+export type EventTrigger = 'user' | 'scheduler' | 'watchdog' | 'config_watch';
+
+// 服务事件历史记录
+export interface ServiceEventRecord {
+  timestamp: string;
+  action: ServiceAction;
+  trigger: EventTrigger;
+  pid: number | null;
+  success: boolean;
+  state: ServiceState | null;
+  message: string;
+}
+
+// 状态时间线中的一段
+export interface UptimeSegment {
+  state: ServiceState;
+  start: string;
+  end: string;
+  duration_secs: number;
+}
+
+// 可用率统计
+export interface UptimeReport {
+  since: string;
+  until: string;
+  observed_secs: number;
+  up_secs: number;
+  /** 百分比，没有记录时为 null */
+  uptime_percent: number | null;
+  crashes: number;
+  restarts: number;
+  segments: UptimeSegment[];
+}
+
+// 配置文件中的一处变化
+export interface ConfigChange {
+  /** openclaw.json | env */
+  file: string;
+  path: string;
+  kind: 'added' | 'removed' | 'modified';
+  restart_required: boolean;
 }
 
+// 配置变化检测状态
+export interface ConfigWatchStatus {
+  restart_required: boolean;
+  changes: ConfigChange[];
+  detected_at: string | null;
+  auto_restart: boolean;
+  debounce_secs: number;
+}
+
+export const CONFIG_RESTART_EVENT = 'config-restart-required';
+
+// openclaw.json 备份
+export interface ConfigBackup {
+  id: string;
+  created_at: string;
+  size: number;
+}
+
+// 两份 JSON 之间的一处变化
+export interface JsonChange {
+  path: string;
+  kind: 'added' | 'removed' | 'modified';
+  old: unknown | null;
+  new: unknown | null;
+}
+
+export interface BackupSettings {
+  maxBackups: number;
+}
+
+// 配置包：导出时如何处理密钥
+export type SecretMode = 'include' | 'strip' | 'encrypt';
+
+export interface BundlePlugin {
+  id: string;
+  spec: string | null;
+  version: string | null;
+}
+
+// 配置包清单（manifest.json）
+export interface BundleManifest {
+  format_version: number;
+  created_at: string;
+  manager_version: string;
+  os: string;
+  secret_mode: SecretMode;
+  encryption: { iterations: number; salt: string; nonce: string } | null;
+  /** 被移出的密钥位置：openclaw.json#<JSON Pointer> 或 env#<变量名> */
+  secret_locations: string[];
+  plugins: BundlePlugin[];
+  files: string[];
+}
+
+export interface BundleImportPreview {
+  manifest: BundleManifest;
+  config_changes: JsonChange[];
+  env_changes: JsonChange[];
+  /** 会新建（added）或覆盖（modified）的文件 */
+  files: { path: string; kind: 'added' | 'modified' }[];
+  /** spec 为实际安装时使用的规格 */
+  missing_plugins: BundlePlugin[];
+  missing_secrets: string[];
+}
+
+export interface BundleImportResult {
+  snapshot_path: string;
+  files_written: string[];
+  plugins_installed: string[];
+  plugin_errors: string[];
+  missing_secrets: string[];
+}
+
+// openclaw.json 修改（与对应命令的参数相同，用于预览 / 应用）
+export type ConfigMutation =
+  | { type: 'save_config'; config: unknown }
+  | {
+      type: 'save_provider';
+      provider_name: string;
+      base_url: string;
+      api_key: string | null;
+      api_type: string;
+      models: ModelConfig[];
+    }
+  | { type: 'delete_provider'; provider_name: string }
+  | { type: 'set_primary_model'; model_id: string }
+  | { type: 'save_channel_config'; channel: ChannelConfig }
+  | { type: 'clear_channel_config'; channel_id: string };
+
+// 配置修改预览（密钥已脱敏）
+export interface ConfigPreview {
+  /** 预览时 openclaw.json 的哈希，应用时用于确认文件未被改动 */
+  base_hash: string;
+  changes: JsonChange[];
+}
+
+// 配置校验问题
+export interface ConfigIssue {
+  severity: 'error' | 'warning';
+  code: string;
+  /** 配置路径，如 agents.defaults.model.primary */
+  path: string;
+  message: string;
+  hint: string | null;
+}
+
+// ~/.openclaw/env 中的变量（密钥已脱敏）
+export interface EnvEntry {
+  key: string;
+  value: string;
+  secret: boolean;
+}
+
+// Gateway 环境变量来源：Manager 进程 / env 文件 / Manager 设置（PATH）
+export type EnvSource = 'process' | 'env_file' | 'manager';
+
+export interface EffectiveEnvVar {
+  key: string;
+  value: string;
+  secret: boolean;
+  source: EnvSource;
+  /** 被覆盖的同名变量来源 */
+  overrides: EnvSource | null;
+  /** systemd 单元文件中的值已过期，重新安装服务并重启后生效 */
+  pending_restart: boolean;
+}
+
+// Gateway 启动时实际收到的环境变量
+export interface EffectiveEnv {
+  /** 由 systemd 用户服务启动时不继承 Manager 进程的环境变量 */
+  via_systemd: boolean;
+  vars: EffectiveEnvVar[];
+}
+
+// 更换 Gateway Token 的结果
+export interface GatewayTokenRotation {
+  token: string;
+  /** 同步更新的其他位置：openclaw.json / env / systemd */
+  updated: string[];
+  restarted: boolean;
+  restart_error: string | null;
+}
+
+// openclaw.json 结构校验问题
+export interface SchemaIssue {
+  severity: 'error' | 'warning';
+  /** JSON Pointer，如 /models/providers/openai/api */
+  pointer: string;
+  line: number | null;
+  column: number | null;
+  message: string;
+  fix: string | null;
+}
+
+// openclaw.json 结构校验结果
+export interface SchemaReport {
+  valid: boolean;
+  issues: SchemaIssue[];
+}
+
+// 启动前配置校验结果
+export interface PreflightReport {
+  passed: boolean;
+  issues: ConfigIssue[];
+}
+
+// 启动服务失败（配置校验未通过时带问题列表）
+export interface StartError {
+  message: string;
+  issues: ConfigIssue[];
+}
+
+// 将命令返回的错误（字符串或 StartError）转为可读文本
+export function formatError(error: unknown): string {
+  if (error && typeof error === 'object' && 'message' in error) {
+    const { message, issues } = error as StartError;
+    if (!issues || issues.length === 0) return String(message);
+    return [message, ...issues.map((i) => `- ${i.path || 'openclaw.json'}: ${i.message}`)].join('\n');
+  }
+  return String(error);
+}
+
+// 定时启停规则：运行时间窗口
+export interface ScheduleRule {
+  name: string;
+  enabled: boolean;
+  /** 1 = 周一 ... 7 = 周日，为空表示每天 */
+  days: number[];
+  /** HH:MM */
+  start: string;
+  /** HH:MM，早于 start 表示跨天 */
+  end: string;
+}
+
+// 定时启停配置
+export interface ScheduleSettings {
+  enabled: boolean;
+  rules: ScheduleRule[];
+}
+
+export type ScheduleAction = 'start' | 'stop';
+
+// 即将发生的定时启停
+export interface ScheduleTransition {
+  at: string;
+  action: ScheduleAction;
+  rule: string;
+}
+
+// 定时计划最近一次执行的动作
+export interface ScheduleActionRecord {
+  at: string;
+  action: ScheduleAction;
+  success: boolean;
+  message: string;
+}
+
+// 定时计划状态
+export interface ScheduleStatus {
+  enabled: boolean;
+  in_window: boolean;
+  active_rule: string | null;
+  last_action: ScheduleActionRecord | null;
+  upcoming: ScheduleTransition[];
+}
+
+// Gateway 日志条目
+export interface GatewayLogEntry {
+  timestamp: string | null;
+  /** debug | info | warn | error */
+  level: string;
+  subsystem: string | null;
+  /** stdout | stderr | manager */
+  stream: string | null;
+  message: string;
+  raw: string;
+}
+
+// 实时日志推送批次（gateway-log 事件）
+export interface GatewayLogBatch {
+  entries: GatewayLogEntry[];
+  /** 因积压过多而丢弃的条数 */
+  dropped: number;
+  pending: number;
+}
+
+export const GATEWAY_LOG_EVENT = 'gateway-log';
+
 // 系统信息
 export interface SystemInfo {
   os: string;
@@ -156,9 +642,89 @@ export interface AITestResult {
 export const api = {
   // 服务管理
   getServiceStatus: () => invokeWithLog<ServiceStatus>('get_service_status'),
-  startService: () => invokeWithLog<string>('start_service'),
-  stopService: () => invokeWithLog<string>('stop_service'),
+  startService: (force?: boolean) => invokeWithLog<string>('start_service', { force }),
+  stopService: () => invokeWithLog<StopResult>('stop_service'),
   restartService: () => invokeWithLog<string>('restart_service'),
+  probeGatewayHealth: () => invokeWithLog<GatewayHealth>('probe_gateway_health'),
+  checkGatewayConfig: () => invokeWithLog<PreflightReport>('check_gateway_config'),
+  getConfigWatchStatus: () => invokeWithLog<ConfigWatchStatus>('get_config_watch_status'),
+  saveConfigWatchSettings: (autoRestart: boolean, debounceSecs: number) =>
+    invokeWithLog<ConfigWatchStatus>('save_config_watch_settings', { autoRestart, debounceSecs }),
+  dismissConfigRestart: () => invokeWithLog<ConfigWatchStatus>('dismiss_config_restart'),
+  getSupervisorStatus: () => invokeWithLog<SupervisorStatus>('get_supervisor_status'),
+  getGatewayListenSettings: () =>
+    invokeWithLog<GatewayListenInfo>('get_gateway_listen_settings'),
+  /** 绑定到非本机地址且存在安全风险时需要 confirm 为 true */
+  saveGatewayListenSettings: (
+    port: number | null,
+    bind: string | null,
+    autoPort: boolean,
+    confirm?: boolean
+  ) =>
+    invokeWithLog<GatewayListenInfo>('save_gateway_listen_settings', {
+      port,
+      bind,
+      autoPort,
+      confirm,
+    }),
+  getGatewayAccessSettings: () =>
+    invokeWithLog<GatewayAccessSettings>('get_gateway_access_settings'),
+  /** password 为空时保留已有密码；存在安全风险时需要 confirm 为 true */
+  saveGatewayAccessSettings: (
+    authMode: GatewayAuthMode,
+    password: string | null,
+    bind: string,
+    allowedOrigins: string[],
+    confirm: boolean
+  ) =>
+    invokeWithLog<GatewayAccessSettings>('save_gateway_access_settings', {
+      authMode,
+      password,
+      bind,
+      allowedOrigins,
+      confirm,
+    }),
+
+  // Gateway HTTPS
+  getGatewayTlsStatus: () => invokeWithLog<GatewayTlsStatus>('get_gateway_tls_status'),
+  enableGatewayTls: () => invokeWithLog<GatewayTlsStatus>('enable_gateway_tls'),
+  disableGatewayTls: () => invokeWithLog<GatewayTlsStatus>('disable_gateway_tls'),
+  renewGatewayCertificate: () => invokeWithLog<GatewayTlsStatus>('renew_gateway_certificate'),
+  exportGatewayCa: (path: string) => invokeWithLog<string>('export_gateway_ca', { path }),
+
+  // systemd 用户服务 (Linux)
+  getSystemdServiceStatus: () => invokeWithLog<SystemdServiceStatus>('get_systemd_service_status'),
+  generateSystemdUnit: () => invokeWithLog<string>('generate_systemd_unit'),
+  installSystemdService: () => invokeWithLog<SystemdServiceStatus>('install_systemd_service'),
+  enableSystemdService: (startNow: boolean) =>
+    invokeWithLog<SystemdServiceStatus>('enable_systemd_service', { startNow }),
+  disableSystemdService: () => invokeWithLog<SystemdServiceStatus>('disable_systemd_service'),
+  removeSystemdService: () => invokeWithLog<SystemdServiceStatus>('remove_systemd_service'),
+
+  // 服务事件历史
+  getServiceEvents: (since?: string, until?: string, limit?: number) =>
+    invokeWithLog<ServiceEventRecord[]>('get_service_events', { since, until, limit }),
+  getUptimeReport: (windowHours?: number) =>
+    invokeWithLog<UptimeReport>('get_uptime_report', { windowHours }),
+
+  // 定时启停
+  getScheduleSettings: () => invokeWithLog<ScheduleSettings>('get_schedule_settings'),
+  saveScheduleSettings: (schedule: ScheduleSettings) =>
+    invokeWithLog<ScheduleStatus>('save_schedule_settings', { schedule }),
+  getScheduleStatus: () => invokeWithLog<ScheduleStatus>('get_schedule_status'),
+  getUpcomingTransitions: (limit?: number) =>
+    invokeWithLog<ScheduleTransition[]>('get_upcoming_transitions', { limit }),
+
+  // Gateway 日志
+  getGatewayLogs: (lines?: number, level?: string, since?: string, until?: string) =>
+    invokeWithLog<GatewayLogEntry[]>('get_gateway_logs', { lines, level, since, until }),
+  searchGatewayLogs: (query: string, limit?: number) =>
+    invokeWithLog<GatewayLogEntry[]>('search_gateway_logs', { query, limit }),
+  getGatewayLogPath: () => invokeWithLog<string>('get_gateway_log_path'),
+  /** 开始推送 gateway-log 事件，返回订阅 ID */
+  startLogStream: () => invokeWithLog<number>('start_log_stream'),
+  stopLogStream: (subscriptionId: number) =>
+    invokeWithLog<boolean>('stop_log_stream', { subscriptionId }),
 
   // 系统信息
   getSystemInfo: () => invokeWithLog<SystemInfo>('get_system_info'),
@@ -176,9 +742,57 @@ export const api = {
   // 配置管理
   getConfig: () => invokeWithLog<unknown>('get_config'),
   saveConfig: (config: unknown) => invokeWithLog<string>('save_config', { config }),
+  validateOpenclawConfig: () => invokeWithLog<SchemaReport>('validate_openclaw_config'),
   getEnvValue: (key: string) => invokeWithLog<string | null>('get_env_value', { key }),
   saveEnvValue: (key: string, value: string) =>
     invokeWithLog<string>('save_env_value', { key, value }),
+  listEnvVars: () => invokeWithLog<EnvEntry[]>('list_env_vars'),
+  upsertEnvVars: (vars: { key: string; value: string }[]) =>
+    invokeWithLog<EnvEntry[]>('upsert_env_vars', { vars }),
+  deleteEnvVars: (keys: string[]) => invokeWithLog<EnvEntry[]>('delete_env_vars', { keys }),
+  renameEnvVar: (from: string, to: string) =>
+    invokeWithLog<EnvEntry[]>('rename_env_var', { from, to }),
+  getEffectiveEnv: () => invokeWithLog<EffectiveEnv>('get_effective_env'),
+
+  // Gateway Token
+  getOrCreateGatewayToken: () => invokeWithLog<string>('get_or_create_gateway_token'),
+  rotateGatewayToken: () => invokeWithLog<GatewayTokenRotation>('rotate_gateway_token'),
+  getDashboardUrl: () => invokeWithLog<string>('get_dashboard_url'),
+
+  // 配置修改预览 / 应用
+  previewConfigChange: (change: ConfigMutation) =>
+    invokeWithLog<ConfigPreview>('preview_config_change', { change }),
+  applyConfigChange: (change: ConfigMutation, baseHash: string) =>
+    invokeWithLog<string>('apply_config_change', { change, baseHash }),
+
+  // openclaw.json 备份
+  listConfigBackups: () => invokeWithLog<ConfigBackup[]>('list_config_backups'),
+  diffConfigBackup: (backupId: string) =>
+    invokeWithLog<JsonChange[]>('diff_config_backup', { backupId }),
+  restoreConfigBackup: (backupId: string) =>
+    invokeWithLog<string>('restore_config_backup', { backupId }),
+  getBackupSettings: () => invokeWithLog<BackupSettings>('get_backup_settings'),
+  saveBackupSettings: (maxBackups: number) =>
+    invokeWithLog<BackupSettings>('save_backup_settings', { maxBackups }),
+
+  // 配置包导出 / 导入
+  exportSetupBundle: (path: string, secretMode: SecretMode, passphrase?: string) =>
+    invokeWithLog<BundleManifest>('export_setup_bundle', {
+      path,
+      secretMode,
+      passphrase: passphrase ?? null,
+    }),
+  previewSetupBundle: (path: string, passphrase?: string) =>
+    invokeWithLog<BundleImportPreview>('preview_setup_bundle', {
+      path,
+      passphrase: passphrase ?? null,
+    }),
+  importSetupBundle: (path: string, installPlugins: boolean, passphrase?: string) =>
+    invokeWithLog<BundleImportResult>('import_setup_bundle', {
+      path,
+      passphrase: passphrase ?? null,
+      installPlugins,
+    }),
 
   // AI Provider（旧版兼容）
   getAIProviders: () => invokeWithLog<AIProviderOption[]>('get_ai_providers'),